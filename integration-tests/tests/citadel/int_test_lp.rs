@@ -4,6 +4,7 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+use dusk_wallet::RuskHttpClient;
 use toml_base_config::BaseConfig;
 use zk_citadel_moat::license_provider::ReferenceLP;
 use zk_citadel_moat::wallet_accessor::BlockchainAccessConfig;
@@ -22,9 +23,11 @@ async fn lp_scan() -> Result<(), Error> {
     let blockchain_config =
         BlockchainAccessConfig::load_path(blockchain_config_path)?;
 
+    let client = RuskHttpClient::new(blockchain_config.rusk_address.clone());
+
     let mut reference_lp = ReferenceLP::create(&lp_config_path)?;
 
-    reference_lp.scan(&blockchain_config).await?;
+    reference_lp.scan(&client).await?;
     Ok(())
 }
 
@@ -66,9 +69,11 @@ async fn lp_scan_2_lps() -> Result<(), Error> {
     let blockchain_config =
         BlockchainAccessConfig::load_path(blockchain_config_path)?;
 
+    let client = RuskHttpClient::new(blockchain_config.rusk_address.clone());
+
     let mut reference_lp1 = ReferenceLP::create(&lp1_config_path)?;
     let mut reference_lp2 = ReferenceLP::create(&lp2_config_path)?;
-    let (_, _lp1_count) = reference_lp1.scan(&blockchain_config).await?;
-    let (_, _lp2_count) = reference_lp2.scan(&blockchain_config).await?;
+    let (_, _lp1_count) = reference_lp1.scan(&client).await?;
+    let (_, _lp2_count) = reference_lp2.scan(&client).await?;
     Ok(())
 }
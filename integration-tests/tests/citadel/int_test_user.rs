@@ -19,7 +19,6 @@
 //!     nullifier (or session id) in a collection which stops us from double
 //!     usage of the license)
 
-use dusk_bls12_381::BlsScalar;
 use dusk_bytes::DeserializableSlice;
 use dusk_pki::SecretSpendKey;
 use dusk_plonk::prelude::*;
@@ -35,8 +34,8 @@ use zk_citadel_moat::wallet_accessor::BlockchainAccessConfig;
 use zk_citadel_moat::wallet_accessor::Password::PwdHash;
 use zk_citadel_moat::{
     BcInquirer, CitadelInquirer, CrsGetter, Error, JsonLoader, LicenseCircuit,
-    LicenseSessionId, LicenseUser, PayloadRetriever, RequestCreator,
-    RequestJson, RequestSender, TxAwaiter,
+    LicenseReceipt, LicenseSessionId, LicenseUser, PayloadRetriever,
+    RequestCreator, RequestJson, RequestSender, TxAwaiter,
 };
 
 const WALLET_PATH: &str = concat!(env!("HOME"), "/.dusk/rusk-wallet");
@@ -55,7 +54,7 @@ async fn issue_license(
     wallet_path: &WalletPath,
     request: &Request,
     rng: &mut StdRng,
-) -> Result<BlsScalar, Error> {
+) -> Result<LicenseReceipt, Error> {
     let license_issuer = LicenseIssuer::new(
         blockchain_config.clone(),
         wallet_path.clone(),
@@ -66,15 +65,14 @@ async fn issue_license(
 
     const ATTRIBUTE_DATA_EXAMPLE: u64 = 1234;
 
-    let (tx_id, _) = license_issuer
+    license_issuer
         .issue_license(
             rng,
             &request,
             &reference_lp.ssk_lp,
             &JubJubScalar::from(ATTRIBUTE_DATA_EXAMPLE),
         )
-        .await?;
-    Ok(tx_id)
+        .await
 }
 
 /// Displays license contract current state summary.
@@ -195,7 +193,7 @@ async fn user_round_trip() -> Result<(), Error> {
     // as a LP, call issue license, wait for tx to confirm
     show_state(&client, "before issue_license").await?;
     info!("calling issue_license (as an LP)");
-    let issue_license_txid = issue_license(
+    let license_receipt = issue_license(
         &reference_lp,
         &blockchain_config,
         &wallet_path,
@@ -204,7 +202,7 @@ async fn user_round_trip() -> Result<(), Error> {
     )
     .await?;
     show_state(&client, "after issue_license").await?;
-    TxAwaiter::wait_for(&client, issue_license_txid).await?;
+    info!("license issued in block {}", license_receipt.block_height);
     let end_height = BcInquirer::block_height(&client).await?;
     info!("end_height={}", end_height);
 
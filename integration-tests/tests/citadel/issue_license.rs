@@ -13,7 +13,9 @@ use toml_base_config::BaseConfig;
 use zk_citadel_moat::license_provider::{LicenseIssuer, ReferenceLP};
 use zk_citadel_moat::wallet_accessor::BlockchainAccessConfig;
 use zk_citadel_moat::wallet_accessor::Password::PwdHash;
-use zk_citadel_moat::{Error, JsonLoader, RequestCreator, RequestJson};
+use zk_citadel_moat::{
+    Error, JsonLoader, MoatCoreUtils, RequestCreator, RequestJson,
+};
 
 const WALLET_PATH: &str = concat!(env!("HOME"), "/.dusk/rusk-wallet");
 const PWD_HASH: &str =
@@ -75,3 +77,60 @@ async fn issue_license() -> Result<(), Error> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread")]
+#[cfg_attr(not(feature = "int_tests"), ignore)]
+async fn issue_licenses_batch() -> Result<(), Error> {
+    let request_path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/request/test_request.json"
+    );
+    let blockchain_config_path =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/config.toml");
+
+    let lp_config_path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/test_keys/test_keys_lp_2.json"
+    );
+
+    let reference_lp = ReferenceLP::create(&lp_config_path)?;
+
+    let request_json: RequestJson = RequestJson::from_file(request_path)?;
+
+    let rng = &mut StdRng::seed_from_u64(0xbeef);
+    let mut requests = vec![];
+    for attr_data in 1..=3u64 {
+        let request = RequestCreator::create_from_hex_args(
+            &request_json.user_ssk,
+            &request_json.provider_psk,
+            rng,
+        )?;
+        requests.push((request, JubJubScalar::from(attr_data)));
+    }
+
+    let blockchain_config =
+        BlockchainAccessConfig::load_path(blockchain_config_path)?;
+
+    let wallet_path = WalletPath::from(
+        PathBuf::from(WALLET_PATH).as_path().join("wallet.dat"),
+    );
+
+    let license_issuer = LicenseIssuer::new(
+        blockchain_config,
+        wallet_path,
+        PwdHash(PWD_HASH.to_string()),
+        GAS_LIMIT,
+        GAS_PRICE,
+    );
+
+    let results = license_issuer
+        .issue_licenses(rng, &requests, &reference_lp.ssk_lp)
+        .await?;
+    assert_eq!(results.len(), requests.len());
+    for ((request, _), result) in requests.iter().zip(results) {
+        let receipt = result?;
+        assert_eq!(receipt.request_hash, MoatCoreUtils::to_hash_hex(request));
+    }
+
+    Ok(())
+}
@@ -10,3 +10,4 @@ mod issue_license;
 mod license_queries;
 mod retrieve_requests;
 mod send_request;
+mod wallet_session;
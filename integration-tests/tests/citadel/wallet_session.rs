@@ -0,0 +1,89 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use dusk_wallet::{RuskHttpClient, WalletPath};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::path::PathBuf;
+use toml_base_config::BaseConfig;
+use zk_citadel_moat::wallet_accessor::{
+    BlockchainAccessConfig, Password::PwdHash, WalletSession,
+};
+use zk_citadel_moat::{
+    Error, JsonLoader, RequestCreator, RequestJson, TxAwaiter,
+    LICENSE_CONTRACT_ID, REQUEST_LICENSE_METHOD_NAME,
+};
+
+const WALLET_PATH: &str = concat!(env!("HOME"), "/.dusk/rusk-wallet");
+const PWD_HASH: &str =
+    "5e884898da28047151d0e56f8dc6292773603d0d6aabbdd62a11ef721d1542d8";
+const GAS_LIMIT: u64 = 5_000_000_000;
+const GAS_PRICE: u64 = 1;
+
+#[tokio::test(flavor = "multi_thread")]
+#[cfg_attr(not(feature = "int_tests"), ignore)]
+async fn send_requests_in_wallet_session() -> Result<(), Error> {
+    let request_path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/request/test_request.json"
+    );
+    let config_path = concat!(env!("CARGO_MANIFEST_DIR"), "/config.toml");
+
+    let request_json: RequestJson = RequestJson::from_file(request_path)?;
+    let config = BlockchainAccessConfig::load_path(config_path)?;
+    let wallet_path = WalletPath::from(
+        PathBuf::from(WALLET_PATH).as_path().join("wallet.dat"),
+    );
+
+    let mut session = WalletSession::open(
+        wallet_path,
+        PwdHash(PWD_HASH.to_string()),
+        &config,
+    )
+    .await?;
+
+    let rng = &mut StdRng::seed_from_u64(0xbeef);
+    let mut tx_ids = vec![];
+    for _ in 0..2 {
+        let request = RequestCreator::create_from_hex_args(
+            &request_json.user_ssk,
+            &request_json.provider_psk,
+            rng,
+        )?;
+        let tx_id = session
+            .execute_contract_method(
+                request,
+                LICENSE_CONTRACT_ID,
+                REQUEST_LICENSE_METHOD_NAME,
+                GAS_LIMIT,
+                GAS_PRICE,
+            )
+            .await?;
+        let pending_tx = session.pending_txs().last().expect("pending tx");
+        assert_eq!(pending_tx.tx_id, tx_id);
+        let nullifiers = &pending_tx.nullifiers;
+        assert!(nullifiers.iter().all(|n| session.is_pending_nullifier(n)));
+        tx_ids.push(tx_id);
+    }
+
+    // pending transactions spend distinct notes
+    let pending_txs = session.pending_txs();
+    for (i, pending_tx) in pending_txs.iter().enumerate() {
+        assert!(pending_txs[i + 1..].iter().all(|other| other
+            .nullifiers
+            .iter()
+            .all(|n| !pending_tx.nullifiers.contains(n))));
+    }
+
+    session.settle().await?;
+    assert!(session.pending_txs().is_empty());
+
+    let client = RuskHttpClient::new(config.rusk_address);
+    for tx_id in tx_ids {
+        TxAwaiter::wait_for(&client, tx_id).await?;
+    }
+    Ok(())
+}
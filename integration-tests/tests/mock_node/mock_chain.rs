@@ -0,0 +1,165 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::mock_node::mock_license_contract::MockLicenseContract;
+use dusk_bls12_381::BlsScalar;
+use dusk_bytes::Serializable;
+use serde_json::{json, Value};
+use zk_citadel_moat::LICENSE_CONTRACT_ID;
+
+struct MockTx {
+    id: String,
+    fn_name: String,
+    data: String,
+    err: Option<String>,
+    block_height: u64,
+}
+
+/// In-memory chain in which every contract call is placed in its own block.
+/// Answers the GraphQL queries issued by the library.
+pub struct MockChain {
+    /// indices of transactions contained in each block, by block height
+    blocks: Vec<Vec<usize>>,
+    txs: Vec<MockTx>,
+    pub contract: MockLicenseContract,
+}
+
+impl MockChain {
+    /// Creates chain containing the genesis block only.
+    pub fn new() -> Self {
+        Self {
+            blocks: vec![vec![]],
+            txs: Vec::new(),
+            contract: MockLicenseContract::new(),
+        }
+    }
+
+    pub fn top(&self) -> u64 {
+        self.blocks.len() as u64 - 1
+    }
+
+    /// Appends a given number of empty blocks.
+    pub fn advance(&mut self, blocks: u64) {
+        for _ in 0..blocks {
+            self.blocks.push(vec![]);
+        }
+    }
+
+    /// Provides id of the transaction executed next.
+    pub fn next_tx_id(&self) -> BlsScalar {
+        BlsScalar::from(self.txs.len() as u64 + 1)
+    }
+
+    /// Executes a license contract method in a new block. Transaction is
+    /// recorded with an error if the method fails.
+    pub fn execute(&mut self, fn_name: &str, data: Vec<u8>) -> BlsScalar {
+        let height = self.top() + 1;
+        let err = self.contract.execute(fn_name, &data, height).err();
+        let tx_id = self.next_tx_id();
+        self.txs.push(MockTx {
+            id: hex::encode(tx_id.to_bytes()),
+            fn_name: fn_name.to_string(),
+            data: hex::encode(data),
+            err,
+            block_height: height,
+        });
+        self.blocks.push(vec![self.txs.len() - 1]);
+        tx_id
+    }
+
+    /// Answers a GraphQL query, supports the block, blockTxs and tx
+    /// queries.
+    pub fn gql(&self, query: &str) -> Result<Value, String> {
+        if let Some(args) = Self::args_of(query, "blockTxs(") {
+            let heights = if let Some(range) = args.strip_prefix("range:") {
+                let (beg, end) = range
+                    .trim_matches(|c| c == '[' || c == ']')
+                    .split_once(',')
+                    .ok_or(format!("invalid range {}", range))?;
+                let beg = beg.parse::<u64>().map_err(|e| e.to_string())?;
+                let end = end.parse::<u64>().map_err(|e| e.to_string())?;
+                beg..=end.min(self.top())
+            } else if let Some(last) = args.strip_prefix("last:") {
+                let last = last.parse::<u64>().map_err(|e| e.to_string())?;
+                (self.top() + 1).saturating_sub(last)..=self.top()
+            } else {
+                return Err(format!("unsupported blockTxs args {}", args));
+            };
+            let block_txs: Vec<Value> = heights
+                .flat_map(|height| self.blocks[height as usize].iter())
+                .map(|index| self.spent_tx_json(&self.txs[*index]))
+                .collect();
+            Ok(json!({ "blockTxs": block_txs }))
+        } else if let Some(args) = Self::args_of(query, "tx(") {
+            let hash = args
+                .strip_prefix("hash:")
+                .ok_or(format!("unsupported tx args {}", args))?
+                .trim_matches('"');
+            let tx = self
+                .txs
+                .iter()
+                .find(|tx| tx.id == hash)
+                .map(|tx| self.spent_tx_json(tx));
+            Ok(json!({ "tx": tx }))
+        } else if let Some(args) = Self::args_of(query, "block(") {
+            let height = args
+                .strip_prefix("height:")
+                .ok_or(format!("unsupported block args {}", args))?
+                .parse::<i64>()
+                .map_err(|e| e.to_string())?;
+            let height = if height < 0 {
+                self.top()
+            } else {
+                height as u64
+            };
+            let block = (height <= self.top()).then(|| {
+                json!({
+                    "header": {
+                        "height": height,
+                        "hash": Self::block_hash(height)
+                    }
+                })
+            });
+            Ok(json!({ "block": block }))
+        } else {
+            Err(format!("unsupported query {}", query))
+        }
+    }
+
+    fn block_hash(height: u64) -> String {
+        format!("{:064x}", height + 1)
+    }
+
+    fn spent_tx_json(&self, tx: &MockTx) -> Value {
+        json!({
+            "err": tx.err,
+            "gasSpent": 0,
+            "blockHeight": tx.block_height,
+            "blockHash": Self::block_hash(tx.block_height),
+            "tx": {
+                "id": tx.id,
+                "raw": "",
+                "callData": {
+                    "contractId": hex::encode(LICENSE_CONTRACT_ID),
+                    "fnName": tx.fn_name,
+                    "data": tx.data
+                }
+            }
+        })
+    }
+
+    /// Returns arguments of a given query field, with whitespace removed.
+    fn args_of(query: &str, field: &str) -> Option<String> {
+        let beg = query.find(field)? + field.len();
+        let end = beg + query[beg..].find(')')?;
+        Some(
+            query[beg..end]
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .collect(),
+        )
+    }
+}
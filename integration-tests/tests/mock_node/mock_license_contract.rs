@@ -0,0 +1,136 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use dusk_bls12_381::BlsScalar;
+use dusk_bytes::Serializable;
+use poseidon_merkle::{Item, Tree};
+use std::collections::BTreeMap;
+use std::ops::Range;
+use zk_citadel::license::{Request, Session};
+use zk_citadel_moat::{
+    LicenseSession, LicenseSessionId, UseLicenseArg, ARITY, DEPTH,
+    GET_INFO_METHOD_NAME, GET_LICENSES_METHOD_NAME,
+    GET_MERKLE_OPENING_METHOD_NAME, GET_SESSION_METHOD_NAME,
+    ISSUE_LICENSE_METHOD_NAME, MAX_LICENSE_SIZE, MAX_RESPONSE_SIZE,
+    REQUEST_LICENSE_METHOD_NAME, USE_LICENSE_METHOD_NAME,
+};
+
+/// In-memory license contract.
+/// Proofs passed to use_license are not verified, sessions are created from
+/// the public inputs as they are.
+pub struct MockLicenseContract {
+    /// licenses along with heights of the blocks in which they were issued,
+    /// position of a license in the merkle tree is its index
+    licenses: Vec<(u64, Vec<u8>)>,
+    tree: Tree<(), DEPTH, ARITY>,
+    sessions: BTreeMap<[u8; 32], LicenseSession>,
+    num_requests: u32,
+}
+
+impl MockLicenseContract {
+    pub fn new() -> Self {
+        Self {
+            licenses: Vec::new(),
+            tree: Tree::new(),
+            sessions: BTreeMap::new(),
+            num_requests: 0,
+        }
+    }
+
+    /// Executes a state changing method in a block at a given height.
+    pub fn execute(
+        &mut self,
+        fn_name: &str,
+        data: &[u8],
+        height: u64,
+    ) -> Result<(), String> {
+        match fn_name {
+            REQUEST_LICENSE_METHOD_NAME => {
+                rkyv::from_bytes::<Request>(data)
+                    .map_err(|_| "invalid request".to_string())?;
+                self.num_requests += 1;
+            }
+            ISSUE_LICENSE_METHOD_NAME => {
+                let (license_blob, license_hash): (Vec<u8>, BlsScalar) =
+                    rkyv::from_bytes(data)
+                        .map_err(|_| "invalid license".to_string())?;
+                let pos = self.licenses.len() as u64;
+                self.tree.insert(pos, Item::new(license_hash, ()));
+                self.licenses.push((height, license_blob));
+            }
+            USE_LICENSE_METHOD_NAME => {
+                let use_license_arg: UseLicenseArg = rkyv::from_bytes(data)
+                    .map_err(|_| "invalid use license argument".to_string())?;
+                let session = Session::from(&use_license_arg.public_inputs);
+                let session_id = session.session_id.to_bytes();
+                if self.sessions.contains_key(&session_id) {
+                    return Err("License already used".to_string());
+                }
+                self.sessions.insert(
+                    session_id,
+                    LicenseSession {
+                        public_inputs: use_license_arg.public_inputs,
+                    },
+                );
+            }
+            _ => return Err(format!("unknown method {}", fn_name)),
+        }
+        Ok(())
+    }
+
+    /// Executes a query method, returns the serialized response.
+    pub fn query(&self, fn_name: &str, data: &[u8]) -> Result<Vec<u8>, String> {
+        Ok(match fn_name {
+            GET_LICENSES_METHOD_NAME => {
+                let block_heights: Range<u64> = rkyv::from_bytes(data)
+                    .map_err(|_| "invalid block heights".to_string())?;
+                let mut response = Vec::new();
+                for (pos, (height, license_blob)) in
+                    self.licenses.iter().enumerate()
+                {
+                    if block_heights.contains(height) {
+                        let item = (pos as u64, license_blob.clone());
+                        response.extend_from_slice(
+                            rkyv::to_bytes::<_, MAX_LICENSE_SIZE>(&item)
+                                .expect("Serializing should be infallible")
+                                .as_slice(),
+                        );
+                    }
+                }
+                response
+            }
+            GET_MERKLE_OPENING_METHOD_NAME => {
+                let pos: u64 = rkyv::from_bytes(data)
+                    .map_err(|_| "invalid position".to_string())?;
+                Self::serialize(&self.tree.opening(pos))
+            }
+            GET_SESSION_METHOD_NAME => {
+                let session_id: LicenseSessionId = rkyv::from_bytes(data)
+                    .map_err(|_| "invalid session id".to_string())?;
+                Self::serialize(
+                    &self.sessions.get(&session_id.id.to_bytes()).cloned(),
+                )
+            }
+            GET_INFO_METHOD_NAME => Self::serialize(&(
+                self.licenses.len() as u32,
+                self.num_requests,
+                self.sessions.len() as u32,
+            )),
+            _ => return Err(format!("unknown method {}", fn_name)),
+        })
+    }
+
+    fn serialize<T>(value: &T) -> Vec<u8>
+    where
+        T: rkyv::Serialize<
+            rkyv::ser::serializers::AllocSerializer<MAX_RESPONSE_SIZE>,
+        >,
+    {
+        rkyv::to_bytes::<_, MAX_RESPONSE_SIZE>(value)
+            .expect("Serializing should be infallible")
+            .to_vec()
+    }
+}
@@ -0,0 +1,202 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! In-process mock of a Rusk node, allowing the SDK to be tested without
+//! network access.
+//! The node serves the GraphQL queries of the `Chain` target, the CRS, and
+//! the license contract's query methods, including the streaming
+//! get_licenses. As transactions cannot be created without a real wallet,
+//! license contract's methods are executed by calling the node directly.
+
+mod mock_chain;
+mod mock_license_contract;
+
+use dusk_bls12_381::BlsScalar;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server, StatusCode};
+use mock_chain::MockChain;
+use sha2::{Digest, Sha256};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+use zk_citadel::license::Request as LicenseRequest;
+use zk_citadel_moat::wallet_accessor::BlockchainAccessConfig;
+use zk_citadel_moat::{
+    UseLicenseArg, ISSUE_LICENSE_METHOD_NAME, LICENSE_CONTRACT_ID,
+    MAX_CALL_SIZE, MAX_LICENSE_SIZE, MAX_REQUEST_SIZE,
+    REQUEST_LICENSE_METHOD_NAME, USE_LICENSE_METHOD_NAME,
+};
+
+const CRS_HASH_HEADER: &str = "crs-hash";
+
+pub struct MockNode {
+    chain: Arc<Mutex<MockChain>>,
+    address: SocketAddr,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl MockNode {
+    /// Starts node serving a given CRS on a local port, the node stops when
+    /// dropped. Needs to be called within a tokio runtime.
+    pub fn start(crs: Vec<u8>) -> Self {
+        let chain = Arc::new(Mutex::new(MockChain::new()));
+        let crs = Arc::new(crs);
+        let make_service = {
+            let chain = chain.clone();
+            make_service_fn(move |_conn| {
+                let chain = chain.clone();
+                let crs = crs.clone();
+                async move {
+                    Ok::<_, Infallible>(service_fn(move |request| {
+                        Self::handle(chain.clone(), crs.clone(), request)
+                    }))
+                }
+            })
+        };
+        let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0)))
+            .serve(make_service);
+        let address = server.local_addr();
+        let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+        tokio::spawn(server.with_graceful_shutdown(async {
+            let _ = shutdown_rx.await;
+        }));
+        Self {
+            chain,
+            address,
+            shutdown: Some(shutdown),
+        }
+    }
+
+    pub fn blockchain_access_config(&self) -> BlockchainAccessConfig {
+        BlockchainAccessConfig {
+            rusk_address: format!("http://{}", self.address),
+            prover_address: format!("http://{}", self.address),
+            ..Default::default()
+        }
+    }
+
+    /// Executes license contract's request_license method in a new block.
+    pub fn request_license(&self, request: &LicenseRequest) -> BlsScalar {
+        let data = rkyv::to_bytes::<_, MAX_REQUEST_SIZE>(request)
+            .expect("Serializing should be infallible")
+            .to_vec();
+        self.execute(REQUEST_LICENSE_METHOD_NAME, data)
+    }
+
+    /// Executes license contract's issue_license method in a new block.
+    pub fn issue_license(
+        &self,
+        license_blob: Vec<u8>,
+        license_hash: BlsScalar,
+    ) -> BlsScalar {
+        let data = rkyv::to_bytes::<_, MAX_LICENSE_SIZE>(&(
+            license_blob,
+            license_hash,
+        ))
+        .expect("Serializing should be infallible")
+        .to_vec();
+        self.execute(ISSUE_LICENSE_METHOD_NAME, data)
+    }
+
+    /// Executes license contract's use_license method in a new block.
+    pub fn use_license(&self, use_license_arg: &UseLicenseArg) -> BlsScalar {
+        let data = rkyv::to_bytes::<_, MAX_CALL_SIZE>(use_license_arg)
+            .expect("Serializing should be infallible")
+            .to_vec();
+        self.execute(USE_LICENSE_METHOD_NAME, data)
+    }
+
+    /// Appends a given number of empty blocks.
+    pub fn advance(&self, blocks: u64) {
+        self.chain.lock().expect("Chain lock").advance(blocks);
+    }
+
+    /// Provides id of the transaction executed next, allowing to refer to a
+    /// transaction before it is included, as if it was still propagating.
+    pub fn next_tx_id(&self) -> BlsScalar {
+        self.chain.lock().expect("Chain lock").next_tx_id()
+    }
+
+    /// Executes license contract's method with given serialized argument in
+    /// a new block.
+    pub fn execute(&self, fn_name: &str, data: Vec<u8>) -> BlsScalar {
+        self.chain
+            .lock()
+            .expect("Chain lock")
+            .execute(fn_name, data)
+    }
+
+    async fn handle(
+        chain: Arc<Mutex<MockChain>>,
+        crs: Arc<Vec<u8>>,
+        request: Request<Body>,
+    ) -> Result<Response<Body>, Infallible> {
+        let path = request.uri().path().to_string();
+        let body = hyper::body::to_bytes(request.into_body())
+            .await
+            .unwrap_or_default();
+        let (topic, data) = match Self::parse_rusk_request(&body) {
+            Some(topic_data) => topic_data,
+            None => {
+                return Ok(Self::error(StatusCode::BAD_REQUEST, "bad request"))
+            }
+        };
+        let license_contract = hex::encode(LICENSE_CONTRACT_ID);
+        let result = match path.trim_start_matches('/').split_once('/') {
+            Some(("2", "Chain")) if topic == "gql" => {
+                let query = String::from_utf8_lossy(data);
+                chain
+                    .lock()
+                    .expect("Chain lock")
+                    .gql(&query)
+                    .map(|json| json.to_string().into_bytes())
+            }
+            Some(("2", "rusk")) if topic == "crs" => {
+                let crs_hash = hex::encode(Sha256::digest(crs.as_slice()));
+                let mut response = Response::new(Body::from(crs.to_vec()));
+                response.headers_mut().insert(
+                    CRS_HASH_HEADER,
+                    crs_hash.parse().expect("Valid header value"),
+                );
+                return Ok(response);
+            }
+            Some(("1", contract)) if contract == license_contract => chain
+                .lock()
+                .expect("Chain lock")
+                .contract
+                .query(topic, data),
+            _ => Err(format!("unsupported target {}", path)),
+        };
+        Ok(match result {
+            Ok(bytes) => Response::new(Body::from(bytes)),
+            Err(e) => Self::error(StatusCode::INTERNAL_SERVER_ERROR, e),
+        })
+    }
+
+    /// Splits request body into topic and data, the body starts with the
+    /// topic length as little endian u32.
+    fn parse_rusk_request(body: &[u8]) -> Option<(&str, &[u8])> {
+        let topic_len = u32::from_le_bytes(body.get(..4)?.try_into().ok()?);
+        let topic_end = 4 + topic_len as usize;
+        let topic = std::str::from_utf8(body.get(4..topic_end)?).ok()?;
+        Some((topic, &body[topic_end..]))
+    }
+
+    fn error(status: StatusCode, message: impl Into<String>) -> Response<Body> {
+        let mut response = Response::new(Body::from(message.into()));
+        *response.status_mut() = status;
+        response
+    }
+}
+
+impl Drop for MockNode {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
@@ -0,0 +1,71 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::mock_node::MockNode;
+use dusk_plonk::prelude::*;
+use dusk_wallet::RuskHttpClient;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::fs;
+use zk_citadel_moat::{CircuitKeyStore, CrsGetter, Error};
+
+const CAPACITY: usize = 17; // capacity required for the setup
+
+#[tokio::test(flavor = "multi_thread")]
+#[cfg_attr(not(feature = "exp_tests"), ignore)]
+async fn offline_circuit_key_store() -> Result<(), Error> {
+    let rng = &mut StdRng::seed_from_u64(0xbeef);
+
+    let pp = PublicParameters::setup(1 << CAPACITY, rng)
+        .expect("Initializing public parameters should succeed");
+    let crs = pp.to_raw_var_bytes();
+    let crs_hash = CrsGetter::hash_of_bytes(crs.as_slice());
+    let node = MockNode::start(crs);
+    let config = node.blockchain_access_config();
+    let client = RuskHttpClient::new(config.rusk_address.clone());
+
+    let dir = std::env::temp_dir()
+        .join(format!("moat_circuit_key_store_{}", std::process::id()));
+
+    // keys are compiled from the node's CRS and saved
+    let mut key_store = CircuitKeyStore::new(&dir).with_crs_hash(crs_hash);
+    key_store.get(&client).await?;
+    let prover_path = key_store.prover_path().expect("prover path");
+    assert!(prover_path.exists());
+
+    // saved keys are loaded by a fresh store
+    let key_store = CircuitKeyStore::new(&dir);
+    assert!(key_store.load(&crs_hash).is_some());
+
+    // keys compiled from a different CRS or label are not loaded
+    assert!(key_store.load(&[0u8; 32]).is_none());
+    let other_label_store = CircuitKeyStore::new(&dir).with_label(b"other");
+    assert!(other_label_store.load(&crs_hash).is_none());
+
+    // without a pinned hash, keys compiled from a CRS other than the local
+    // CRS file are not used, and an invalid local CRS is rejected
+    let other_crs_path = dir.join("other_crs.bin");
+    fs::write(&other_crs_path, b"other crs bytes")?;
+    let mut other_crs_store =
+        CircuitKeyStore::new(&dir).with_crs_path(&other_crs_path);
+    assert!(other_crs_store.get_offline().is_err());
+    fs::remove_file(&other_crs_path)?;
+
+    // corrupted keys are not loaded, and are rebuilt
+    let mut prover_bytes = fs::read(&prover_path)?;
+    let last = prover_bytes.len() - 1;
+    prover_bytes[last] ^= 0xff;
+    fs::write(&prover_path, prover_bytes)?;
+    let mut key_store = CircuitKeyStore::new(&dir);
+    assert!(key_store.load(&crs_hash).is_none());
+    key_store.get(&client).await?;
+    assert!(CircuitKeyStore::new(&dir).load(&crs_hash).is_some());
+
+    key_store.clear()?;
+    assert!(!prover_path.exists());
+    fs::remove_dir_all(&dir)?;
+    Ok(())
+}
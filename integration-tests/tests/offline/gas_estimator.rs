@@ -0,0 +1,51 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::mock_node::MockNode;
+use dusk_bls12_381::BlsScalar;
+use dusk_wallet::RuskHttpClient;
+use std::time::Duration;
+use zk_citadel_moat::{
+    Error, GasEstimate, GasEstimator, ISSUE_LICENSE_METHOD_NAME,
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn offline_gas_estimate_cached_and_windowed() -> Result<(), Error> {
+    let node = MockNode::start(Vec::new());
+    let client =
+        RuskHttpClient::new(node.blockchain_access_config().rusk_address);
+    let samples = |estimate: Option<GasEstimate>| {
+        estimate.map(|estimate| estimate.samples)
+    };
+
+    node.issue_license(vec![1, 2, 3], BlsScalar::one());
+    let estimator = GasEstimator::new().with_ttl(Duration::from_secs(60));
+    let estimate = estimator.estimate(&client, ISSUE_LICENSE_METHOD_NAME);
+    assert_eq!(samples(estimate.await?), Some(1));
+
+    // cached estimate is reused, also by clones of the estimator
+    node.issue_license(vec![4, 5, 6], BlsScalar::one());
+    let estimate = estimator
+        .clone()
+        .estimate(&client, ISSUE_LICENSE_METHOD_NAME)
+        .await?;
+    assert_eq!(samples(estimate), Some(1));
+
+    // without caching, the new call is taken into account
+    let estimator = GasEstimator::new().with_ttl(Duration::ZERO);
+    let estimate = estimator.estimate(&client, ISSUE_LICENSE_METHOD_NAME);
+    assert_eq!(samples(estimate.await?), Some(2));
+
+    // calls outside of the window are not taken into account
+    node.advance(1);
+    let estimator = GasEstimator::new().with_blocks(2);
+    let estimate = estimator.estimate(&client, ISSUE_LICENSE_METHOD_NAME);
+    assert_eq!(samples(estimate.await?), Some(1));
+    let estimator = GasEstimator::new().with_blocks(1);
+    let estimate = estimator.estimate(&client, ISSUE_LICENSE_METHOD_NAME);
+    assert_eq!(samples(estimate.await?), None);
+    Ok(())
+}
@@ -0,0 +1,232 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::mock_node::MockNode;
+use dusk_bls12_381::BlsScalar;
+use dusk_jubjub::JubJubScalar;
+use dusk_pki::SecretSpendKey;
+use dusk_wallet::RuskHttpClient;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::time::Duration;
+use zk_citadel_moat::license_provider::{
+    LPCheckpointStore, LicenseIssuer, LicenseSubmission, ReferenceLP,
+    IN_FLIGHT_EXPIRY_BLOCKS,
+};
+use zk_citadel_moat::{
+    AwaitConfig, BcInquirer, CitadelInquirer, Error, LicenseCache,
+    MoatCoreUtils, RequestCreator, TxAwaiter, TxOutcome,
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn offline_request_and_issue_license() -> Result<(), Error> {
+    let rng = &mut StdRng::seed_from_u64(0xcafe);
+    let node = MockNode::start(Vec::new());
+    let config = node.blockchain_access_config();
+    let client = RuskHttpClient::new(config.rusk_address.clone());
+
+    let ssk_user = SecretSpendKey::random(rng);
+    let ssk_lp = SecretSpendKey::random(rng);
+    let psk_lp = ssk_lp.public_spend_key();
+
+    // user submits a request, followed by an unrelated one
+    let request = RequestCreator::create(&ssk_user, &psk_lp, rng)?;
+    let tx_id = node.request_license(&request);
+    TxAwaiter::wait_for(&client, tx_id).await?;
+    let other_psk_lp = SecretSpendKey::random(rng).public_spend_key();
+    let other_request = RequestCreator::create(&ssk_user, &other_psk_lp, rng)?;
+    node.request_license(&other_request);
+    node.advance(3);
+    assert_eq!(BcInquirer::block_height(&client).await?, 5);
+
+    // LP finds only the request addressed to it
+    let mut reference_lp = ReferenceLP::create_with_ssk(&ssk_lp)?;
+    let (total, owned) = reference_lp.scan(&client).await?;
+    assert_eq!((total, owned), (2, 1));
+    let request = reference_lp.take_request().expect("request should exist");
+
+    // LP issues license
+    let (license_blob, license_hash) = LicenseIssuer::license_payload(
+        rng,
+        &request,
+        &ssk_lp,
+        &JubJubScalar::from(1234u64),
+    );
+    let tx_id = node.issue_license(license_blob, license_hash);
+    TxAwaiter::wait_for(&client, tx_id).await?;
+
+    // user finds the license and its merkle opening
+    let mut license_cache = LicenseCache::new();
+    let synced_range = license_cache.sync(&client).await?;
+    assert_eq!(synced_range, 0..7);
+    let owned_licenses = license_cache.owned_licenses(&ssk_user);
+    assert_eq!(owned_licenses.len(), 1);
+    let (pos, _license) = &owned_licenses[0];
+    let opening = CitadelInquirer::get_merkle_opening(&client, *pos).await?;
+    assert!(opening.is_some());
+    assert!(CitadelInquirer::get_merkle_opening(&client, pos + 1)
+        .await?
+        .is_none());
+
+    // subsequent scan finds no new requests
+    assert_eq!(reference_lp.scan(&client).await?, (0, 0));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn offline_taken_request_not_rescanned() -> Result<(), Error> {
+    let rng = &mut StdRng::seed_from_u64(0xbeef);
+    let node = MockNode::start(Vec::new());
+    let config = node.blockchain_access_config();
+    let client = RuskHttpClient::new(config.rusk_address.clone());
+
+    let ssk_user = SecretSpendKey::random(rng);
+    let ssk_lp = SecretSpendKey::random(rng);
+    let psk_lp = ssk_lp.public_spend_key();
+
+    // request is in the tip block, which is scanned again by the next scan
+    let request = RequestCreator::create(&ssk_user, &psk_lp, rng)?;
+    let tx_id = node.request_license(&request);
+    TxAwaiter::wait_for(&client, tx_id).await?;
+
+    let store_path = std::env::temp_dir().join(format!(
+        "moat_lp_checkpoint_rescan_{}.json",
+        std::process::id()
+    ));
+    let store = LPCheckpointStore::new(&store_path);
+    let mut reference_lp = ReferenceLP::create_with_ssk(&ssk_lp)?;
+    let (_, owned) = reference_lp.scan_with_checkpoint(&client, &store).await?;
+    assert_eq!(owned, 1);
+    let taken = reference_lp.take_request().expect("request should exist");
+    let request_hash = MoatCoreUtils::to_hash_hex(&taken);
+    assert!(reference_lp.is_in_flight(&request_hash));
+    store.save(&reference_lp.checkpoint())?;
+
+    // rescanning the tip block does not bring the taken request back
+    let (_, owned) = reference_lp.scan_with_checkpoint(&client, &store).await?;
+    assert_eq!(owned, 0);
+    assert!(reference_lp.requests_to_process.is_empty());
+
+    // neither does rescanning after restoring from the checkpoint
+    let mut restored_lp = ReferenceLP::create_with_checkpoint(&ssk_lp, &store)?;
+    std::fs::remove_file(&store_path)?;
+    let (_, owned) = restored_lp.scan(&client).await?;
+    assert_eq!(owned, 0);
+    assert!(restored_lp.take_request().is_none());
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn offline_dropped_license_included_late() -> Result<(), Error> {
+    let rng = &mut StdRng::seed_from_u64(0xf00d);
+    let node = MockNode::start(Vec::new());
+    let config = node.blockchain_access_config();
+    let client = RuskHttpClient::new(config.rusk_address.clone());
+
+    let ssk_user = SecretSpendKey::random(rng);
+    let ssk_lp = SecretSpendKey::random(rng);
+    let psk_lp = ssk_lp.public_spend_key();
+
+    let request = RequestCreator::create(&ssk_user, &psk_lp, rng)?;
+    let tx_id = node.request_license(&request);
+    TxAwaiter::wait_for(&client, tx_id).await?;
+    let mut reference_lp = ReferenceLP::create_with_ssk(&ssk_lp)?;
+    reference_lp.scan(&client).await?;
+    let request = reference_lp.take_request().expect("request should exist");
+    let request_hash = MoatCoreUtils::to_hash_hex(&request);
+
+    // issue license transaction is still propagating when waiting for it
+    // times out
+    let (license_blob, license_hash) = LicenseIssuer::license_payload(
+        rng,
+        &request,
+        &ssk_lp,
+        &JubJubScalar::from(1234u64),
+    );
+    let submission = LicenseSubmission {
+        tx_id: node.next_tx_id(),
+        license_blob: license_blob.clone(),
+        height: BcInquirer::block_height(&client).await?,
+    };
+    let await_config = AwaitConfig {
+        timeout: Duration::from_millis(200),
+        poll_interval: Duration::from_millis(50),
+        ..AwaitConfig::default()
+    };
+    let outcome =
+        TxAwaiter::wait_for_outcome(&client, submission.tx_id, &await_config)
+            .await?;
+    assert_eq!(outcome, TxOutcome::Dropped);
+    reference_lp.record_submission(&request, submission);
+
+    // dropped request stays in flight across restarts, it is neither issued
+    // again nor found again by rescanning
+    let mut restored_lp = ReferenceLP::create_with_ssk(&ssk_lp)?;
+    restored_lp.restore(&reference_lp.checkpoint())?;
+    assert!(restored_lp.settle_in_flight(&client).await?.is_empty());
+    assert!(restored_lp.is_in_flight(&request_hash));
+    assert_eq!(restored_lp.scan(&client).await?.1, 0);
+    assert!(restored_lp.take_request().is_none());
+
+    // transaction is included late, request is settled as processed
+    node.issue_license(license_blob, license_hash);
+    let settled = restored_lp.settle_in_flight(&client).await?;
+    assert_eq!(settled.len(), 1);
+    assert!(matches!(settled[0].2, TxOutcome::Included { .. }));
+    assert!(restored_lp.is_processed(&request_hash));
+    assert!(!restored_lp.is_in_flight(&request_hash));
+    assert_eq!(restored_lp.scan(&client).await?.1, 0);
+    assert!(restored_lp.take_request().is_none());
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn offline_lost_license_issued_again() -> Result<(), Error> {
+    let rng = &mut StdRng::seed_from_u64(0xd00d);
+    let node = MockNode::start(Vec::new());
+    let config = node.blockchain_access_config();
+    let client = RuskHttpClient::new(config.rusk_address.clone());
+
+    let ssk_user = SecretSpendKey::random(rng);
+    let ssk_lp = SecretSpendKey::random(rng);
+    let psk_lp = ssk_lp.public_spend_key();
+
+    let request = RequestCreator::create(&ssk_user, &psk_lp, rng)?;
+    let tx_id = node.request_license(&request);
+    TxAwaiter::wait_for(&client, tx_id).await?;
+    let mut reference_lp = ReferenceLP::create_with_ssk(&ssk_lp)?;
+    reference_lp.scan(&client).await?;
+    let request = reference_lp.take_request().expect("request should exist");
+    let request_hash = MoatCoreUtils::to_hash_hex(&request);
+
+    // transaction which never gets included
+    let (license_blob, _) = LicenseIssuer::license_payload(
+        rng,
+        &request,
+        &ssk_lp,
+        &JubJubScalar::from(1234u64),
+    );
+    let submission = LicenseSubmission {
+        tx_id: BlsScalar::from(1000u64),
+        license_blob,
+        height: BcInquirer::block_height(&client).await?,
+    };
+    reference_lp.record_submission(&request, submission);
+
+    node.advance(IN_FLIGHT_EXPIRY_BLOCKS - 1);
+    assert!(reference_lp.settle_in_flight(&client).await?.is_empty());
+    assert!(reference_lp.is_in_flight(&request_hash));
+
+    // request is put back once the transaction is considered lost
+    node.advance(1);
+    let settled = reference_lp.settle_in_flight(&client).await?;
+    assert_eq!(settled.len(), 1);
+    assert_eq!(settled[0].2, TxOutcome::Dropped);
+    assert!(!reference_lp.is_in_flight(&request_hash));
+    let taken = reference_lp.take_request().expect("request should exist");
+    assert_eq!(MoatCoreUtils::to_hash_hex(&taken), request_hash);
+    Ok(())
+}
@@ -0,0 +1,17 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Tests running against the in-process mock node, they do not require
+//! network access nor a running Rusk node.
+
+mod circuit_key_store;
+mod gas_estimator;
+mod issue_license;
+mod request_status;
+mod sp_server;
+mod tx_awaiter;
+mod use_license;
+mod watch_requests;
@@ -0,0 +1,90 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::mock_node::MockNode;
+use dusk_bls12_381::BlsScalar;
+use dusk_jubjub::JubJubScalar;
+use dusk_pki::SecretSpendKey;
+use dusk_wallet::RuskHttpClient;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use zk_citadel_moat::license_provider::LicenseIssuer;
+use zk_citadel_moat::{
+    Error, LicenseCache, MoatCoreUtils, RequestCreator, RequestReceipt,
+    RequestStatus, TxAwaiter,
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn offline_request_status() -> Result<(), Error> {
+    let rng = &mut StdRng::seed_from_u64(0xcafe);
+    let node = MockNode::start(Vec::new());
+    let config = node.blockchain_access_config();
+    let client = RuskHttpClient::new(config.rusk_address.clone());
+
+    let ssk_user = SecretSpendKey::random(rng);
+    let ssk_lp = SecretSpendKey::random(rng);
+    let psk_lp = ssk_lp.public_spend_key();
+
+    let (request, lsa) =
+        RequestCreator::create_with_lsa(&ssk_user, &psk_lp, rng)?;
+    let request_blob = MoatCoreUtils::to_blob(&request);
+    let tx_id = node.request_license(&request);
+    let (block_height, gas_spent) =
+        TxAwaiter::wait_for_outcome(&client, tx_id, &Default::default())
+            .await?
+            .into_result()?;
+    let receipt = RequestReceipt::new(
+        &tx_id,
+        block_height,
+        gas_spent,
+        request_blob.as_slice(),
+    )
+    .with_lsa(&lsa);
+
+    // request is on-chain, license has not been issued yet
+    let mut license_cache = LicenseCache::new();
+    license_cache.sync(&client).await?;
+    let status =
+        RequestStatus::query(&client, &receipt, &license_cache).await?;
+    assert_eq!(status.request_hash, MoatCoreUtils::to_hash_hex(&request));
+    assert_eq!(status.block_height, Some(block_height));
+    assert!(status.is_on_chain());
+    assert!(!status.is_licensed());
+
+    // unrelated license is issued, followed by the license for the request
+    let other_request = RequestCreator::create(&ssk_user, &psk_lp, rng)?;
+    let attr_data = JubJubScalar::from(1234u64);
+    let (license_blob, license_hash) = LicenseIssuer::license_payload(
+        rng,
+        &other_request,
+        &ssk_lp,
+        &attr_data,
+    );
+    node.issue_license(license_blob, license_hash);
+    let (license_blob, license_hash) =
+        LicenseIssuer::license_payload(rng, &request, &ssk_lp, &attr_data);
+    let tx_id = node.issue_license(license_blob.clone(), license_hash);
+    TxAwaiter::wait_for(&client, tx_id).await?;
+
+    license_cache.sync(&client).await?;
+    let status =
+        RequestStatus::query(&client, &receipt, &license_cache).await?;
+    assert!(status.is_licensed());
+    assert_eq!(status.license_pos, Some(1));
+    assert_eq!(
+        status.license_hash,
+        Some(MoatCoreUtils::blob_to_hash_hex(license_blob.as_slice()))
+    );
+
+    // request which never made it to the chain
+    let missing_receipt =
+        RequestReceipt::new(&BlsScalar::one(), 0, 0, request_blob.as_slice());
+    let status =
+        RequestStatus::query(&client, &missing_receipt, &license_cache).await?;
+    assert!(!status.is_on_chain());
+    assert!(!status.is_licensed());
+    Ok(())
+}
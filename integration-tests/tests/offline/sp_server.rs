@@ -0,0 +1,151 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::mock_node::MockNode;
+use dusk_jubjub::JubJubScalar;
+use dusk_pki::SecretSpendKey;
+use dusk_plonk::prelude::*;
+use dusk_wallet::RuskHttpClient;
+use moat_sp_server::SPServer;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use zk_citadel_moat::license_provider::LicenseIssuer;
+use zk_citadel_moat::{
+    CitadelInquirer, CrsGetter, Error, LicenseCache, LicenseCircuit,
+    LicenseUser, RequestCreator, ServiceClient, ServiceRequest,
+    ServiceResponse, ServiceVerifier, TxAwaiter, UsedSessions,
+};
+
+static LABEL: &[u8] = b"dusk-network";
+const CAPACITY: usize = 17; // capacity required for the setup
+const CHALLENGE_TTL: Duration = Duration::from_secs(60);
+
+#[tokio::test(flavor = "multi_thread")]
+#[cfg_attr(not(feature = "exp_tests"), ignore)]
+async fn offline_sp_server_verifies_sessions() -> Result<(), Error> {
+    let rng = &mut StdRng::seed_from_u64(0xdeed);
+
+    let pp = PublicParameters::setup(1 << CAPACITY, rng)
+        .expect("Initializing public parameters should succeed");
+    let node = MockNode::start(pp.to_raw_var_bytes());
+    let config = node.blockchain_access_config();
+    let client = RuskHttpClient::new(config.rusk_address.clone());
+
+    let pp_vec = CrsGetter::get_crs(&client).await?;
+    let pp =
+        // SAFETY: CRS vector is checked by the hash check when it is received from the node
+        unsafe { PublicParameters::from_slice_unchecked(pp_vec.as_slice()) };
+    let (prover, verifier) = Compiler::compile::<LicenseCircuit>(&pp, LABEL)
+        .expect("Compiling circuit should succeed");
+
+    let ssk_user = SecretSpendKey::random(rng);
+    let ssk_lp = SecretSpendKey::random(rng);
+    let psk_lp = ssk_lp.public_spend_key();
+    let psk_sp = SecretSpendKey::random(rng).public_spend_key();
+
+    // request and issue license
+    let request = RequestCreator::create(&ssk_user, &psk_lp, rng)?;
+    node.request_license(&request);
+    let (license_blob, license_hash) = LicenseIssuer::license_payload(
+        rng,
+        &request,
+        &ssk_lp,
+        &JubJubScalar::from(1234u64),
+    );
+    let tx_id = node.issue_license(license_blob, license_hash);
+    TxAwaiter::wait_for(&client, tx_id).await?;
+
+    let mut license_cache = LicenseCache::new();
+    license_cache.sync(&client).await?;
+    let (pos, license) = license_cache
+        .owned_licenses(&ssk_user)
+        .pop()
+        .expect("license should be found");
+
+    // SP server runs on a local port against the mock node
+    let service_verifier = ServiceVerifier::new(
+        config,
+        psk_sp,
+        UsedSessions::new(),
+        CHALLENGE_TTL,
+    );
+    let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+    let (addr, serving) = SPServer::new(service_verifier)
+        .bind(&SocketAddr::from(([127, 0, 0, 1], 0)), async {
+            let _ = shutdown_rx.await;
+        })
+        .expect("Binding server should succeed");
+    let server = tokio::spawn(serving);
+    let sp_address = format!("http://{}", addr);
+
+    // user proves the use of the license with a challenge from the server
+    let challenge = ServiceClient::get_challenge(&sp_address).await?;
+    assert!(challenge.is_bound_to(&psk_sp));
+    let opening = CitadelInquirer::get_merkle_opening(&client, pos)
+        .await?
+        .expect("opening should be found");
+    let bundle = LicenseUser::create_use_license_bundle(
+        &ssk_user,
+        &psk_lp,
+        &psk_sp,
+        &prover,
+        &verifier,
+        &license,
+        opening,
+        rng,
+        &challenge.challenge()?,
+    )?;
+    let tx_id = node.use_license(&bundle.use_license_arg);
+    TxAwaiter::wait_for(&client, tx_id).await?;
+
+    // good session is accepted
+    let service_request = ServiceRequest::new(&bundle.session_cookie, &psk_lp);
+    assert_eq!(
+        ServiceClient::request_service(&sp_address, &service_request).await?,
+        ServiceResponse::grant()
+    );
+
+    // replayed session is rejected
+    assert!(
+        !ServiceClient::request_service(&sp_address, &service_request)
+            .await?
+            .granted
+    );
+
+    // session which has never been created on-chain is rejected
+    let challenge = ServiceClient::get_challenge(&sp_address).await?;
+    let opening = CitadelInquirer::get_merkle_opening(&client, pos)
+        .await?
+        .expect("opening should be found");
+    let unsent_bundle = LicenseUser::create_use_license_bundle(
+        &ssk_user,
+        &psk_lp,
+        &psk_sp,
+        &prover,
+        &verifier,
+        &license,
+        opening,
+        rng,
+        &challenge.challenge()?,
+    )?;
+    let unknown_request =
+        ServiceRequest::new(&unsent_bundle.session_cookie, &psk_lp);
+    assert!(
+        !ServiceClient::request_service(&sp_address, &unknown_request)
+            .await?
+            .granted
+    );
+
+    let _ = shutdown.send(());
+    server
+        .await
+        .expect("Server task should not panic")
+        .expect("Serving should succeed");
+    Ok(())
+}
@@ -0,0 +1,108 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::mock_node::MockNode;
+use dusk_bls12_381::BlsScalar;
+use dusk_pki::SecretSpendKey;
+use dusk_wallet::RuskHttpClient;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::time::Duration;
+use zk_citadel_moat::{
+    AwaitConfig, Backoff, Error, RequestCreator, TxAwaiter, TxOutcome,
+    USE_LICENSE_METHOD_NAME,
+};
+
+fn short_await_config(confirmations: u64) -> AwaitConfig {
+    AwaitConfig {
+        timeout: Duration::from_millis(500),
+        poll_interval: Duration::from_millis(50),
+        backoff: Backoff::Exponential {
+            factor: 2,
+            max_interval: Duration::from_millis(200),
+        },
+        confirmations,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn offline_tx_outcomes() -> Result<(), Error> {
+    let rng = &mut StdRng::seed_from_u64(0xcafe);
+    let node = MockNode::start(Vec::new());
+    let client =
+        RuskHttpClient::new(node.blockchain_access_config().rusk_address);
+    let cfg = short_await_config(0);
+
+    let ssk_user = SecretSpendKey::random(rng);
+    let psk_lp = SecretSpendKey::random(rng).public_spend_key();
+    let request = RequestCreator::create(&ssk_user, &psk_lp, rng)?;
+    let tx_id = node.request_license(&request);
+    assert!(matches!(
+        TxAwaiter::wait_for_outcome(&client, tx_id, &cfg).await?,
+        TxOutcome::Included { height: 1, .. }
+    ));
+
+    let tx_id = node.issue_license(vec![1, 2, 3], BlsScalar::one());
+    node.advance(1);
+    let tx_id_2 = node.execute(USE_LICENSE_METHOD_NAME, vec![0xff]);
+    assert!(matches!(
+        TxAwaiter::wait_for_outcome(&client, tx_id, &cfg).await?,
+        TxOutcome::Included { height: 2, .. }
+    ));
+    assert!(matches!(
+        TxAwaiter::wait_for_outcome(&client, tx_id_2, &cfg).await?,
+        TxOutcome::Failed { height: 4, .. }
+    ));
+    assert!(TxAwaiter::wait_for_with(&client, tx_id_2, &cfg)
+        .await
+        .is_err());
+
+    let unknown_tx_id = BlsScalar::from(1000u64);
+    assert_eq!(
+        TxAwaiter::wait_for_outcome(&client, unknown_tx_id, &cfg).await?,
+        TxOutcome::Dropped
+    );
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn offline_tx_confirmations_and_cancel() -> Result<(), Error> {
+    let rng = &mut StdRng::seed_from_u64(0xcafe);
+    let node = MockNode::start(Vec::new());
+    let client =
+        RuskHttpClient::new(node.blockchain_access_config().rusk_address);
+
+    let ssk_user = SecretSpendKey::random(rng);
+    let psk_lp = SecretSpendKey::random(rng).public_spend_key();
+    let request = RequestCreator::create(&ssk_user, &psk_lp, rng)?;
+    let tx_id = node.request_license(&request);
+
+    // included but not confirmed
+    let cfg = short_await_config(2);
+    assert!(TxAwaiter::wait_for_outcome(&client, tx_id, &cfg)
+        .await
+        .is_err());
+
+    let cfg = AwaitConfig {
+        timeout: Duration::from_secs(60),
+        ..short_await_config(2)
+    };
+    let cancelled = TxAwaiter::wait_for_outcome_or_cancel(
+        &client,
+        tx_id,
+        &cfg,
+        tokio::time::sleep(Duration::from_millis(200)),
+    )
+    .await;
+    assert!(matches!(cancelled, Err(Error::Cancelled)));
+
+    node.advance(2);
+    assert!(matches!(
+        TxAwaiter::wait_for_outcome(&client, tx_id, &cfg).await?,
+        TxOutcome::Included { height: 1, .. }
+    ));
+    Ok(())
+}
@@ -0,0 +1,128 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::mock_node::MockNode;
+use dusk_jubjub::JubJubScalar;
+use dusk_pki::SecretSpendKey;
+use dusk_plonk::prelude::*;
+use dusk_wallet::RuskHttpClient;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::net::Ipv4Addr;
+use std::time::Duration;
+use zk_citadel_moat::license_provider::LicenseIssuer;
+use zk_citadel_moat::{
+    CitadelInquirer, CrsGetter, Error, LicenseCache, LicenseCircuit,
+    LicenseUser, ProofVerifier, RequestCreator, ServiceRequest,
+    ServiceResponse, ServiceVerifier, TxAwaiter, UseLicenseBundle,
+    UsedSessions,
+};
+
+static LABEL: &[u8] = b"dusk-network";
+const CAPACITY: usize = 17; // capacity required for the setup
+const CHALLENGE_TTL: Duration = Duration::from_secs(60);
+
+#[tokio::test(flavor = "multi_thread")]
+#[cfg_attr(not(feature = "exp_tests"), ignore)]
+async fn offline_use_license_and_verify_service() -> Result<(), Error> {
+    let rng = &mut StdRng::seed_from_u64(0xbeef);
+
+    let pp = PublicParameters::setup(1 << CAPACITY, rng)
+        .expect("Initializing public parameters should succeed");
+    let node = MockNode::start(pp.to_raw_var_bytes());
+    let config = node.blockchain_access_config();
+    let client = RuskHttpClient::new(config.rusk_address.clone());
+
+    let pp_vec = CrsGetter::get_crs(&client).await?;
+    let pp =
+        // SAFETY: CRS vector is checked by the hash check when it is received from the node
+        unsafe { PublicParameters::from_slice_unchecked(pp_vec.as_slice()) };
+    let (prover, verifier) = Compiler::compile::<LicenseCircuit>(&pp, LABEL)
+        .expect("Compiling circuit should succeed");
+
+    let ssk_user = SecretSpendKey::random(rng);
+    let ssk_lp = SecretSpendKey::random(rng);
+    let psk_lp = ssk_lp.public_spend_key();
+    let psk_sp = SecretSpendKey::random(rng).public_spend_key();
+
+    // request and issue license
+    let request = RequestCreator::create(&ssk_user, &psk_lp, rng)?;
+    node.request_license(&request);
+    let (license_blob, license_hash) = LicenseIssuer::license_payload(
+        rng,
+        &request,
+        &ssk_lp,
+        &JubJubScalar::from(1234u64),
+    );
+    let tx_id = node.issue_license(license_blob, license_hash);
+    TxAwaiter::wait_for(&client, tx_id).await?;
+
+    let mut license_cache = LicenseCache::new();
+    license_cache.sync(&client).await?;
+    let (pos, license) = license_cache
+        .owned_licenses(&ssk_user)
+        .pop()
+        .expect("license should be found");
+    let opening = CitadelInquirer::get_merkle_opening(&client, pos)
+        .await?
+        .expect("opening should be found");
+
+    // SP issues challenge, user proves the use of the license
+    let service_verifier = ServiceVerifier::new(
+        config,
+        psk_sp,
+        UsedSessions::new(),
+        CHALLENGE_TTL,
+    );
+    let challenge = service_verifier
+        .issue_challenge(rng, Ipv4Addr::LOCALHOST.into())
+        .expect("challenge should be issued")
+        .challenge()?;
+    let bundle = LicenseUser::create_use_license_bundle(
+        &ssk_user, &psk_lp, &psk_sp, &prover, &verifier, &license, opening,
+        rng, &challenge,
+    )?;
+
+    // bundle created offline is broadcast from elsewhere
+    let bundle_hex = bundle.to_hex();
+    let UseLicenseBundle {
+        use_license_arg,
+        session_cookie,
+    } = UseLicenseBundle::from_hex(bundle_hex)?;
+    assert_eq!(use_license_arg, bundle.use_license_arg);
+
+    // SP verifies the proof locally, before the transaction is final
+    let mut proof_verifier = ProofVerifier::new(verifier);
+    assert!(proof_verifier.verify(&use_license_arg).is_err());
+    proof_verifier
+        .verify_with_chain(&client, &use_license_arg)
+        .await?;
+    assert!(ProofVerifier::verify_session_cookie(
+        &use_license_arg,
+        &session_cookie,
+        &psk_lp,
+        &psk_sp
+    ));
+    let mut tampered_arg = use_license_arg.clone();
+    tampered_arg.public_inputs[0] += BlsScalar::one();
+    assert!(proof_verifier.verify(&tampered_arg).is_err());
+
+    let tx_id = node.use_license(&use_license_arg);
+    TxAwaiter::wait_for(&client, tx_id).await?;
+
+    // using the same license with the same challenge is rejected by contract
+    let tx_id = node.use_license(&use_license_arg);
+    assert!(TxAwaiter::wait_for(&client, tx_id).await.is_err());
+
+    // SP grants service once
+    let service_request = ServiceRequest::new(&session_cookie, &psk_lp);
+    assert_eq!(
+        service_verifier.verify(&service_request).await?,
+        ServiceResponse::grant()
+    );
+    assert!(!service_verifier.verify(&service_request).await?.granted);
+    Ok(())
+}
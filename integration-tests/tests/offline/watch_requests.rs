@@ -0,0 +1,82 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::mock_node::MockNode;
+use dusk_pki::SecretSpendKey;
+use dusk_wallet::RuskHttpClient;
+use futures::StreamExt;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::time::Duration;
+use tokio::time::timeout;
+use zk_citadel_moat::{
+    Error, MoatCoreUtils, RequestCreator, RequestScanner, TxAwaiter,
+    WatchConfig,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+const QUIET_PERIOD: Duration = Duration::from_millis(500);
+const ITEM_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[tokio::test(flavor = "multi_thread")]
+async fn offline_watch_requests() -> Result<(), Error> {
+    let rng = &mut StdRng::seed_from_u64(0xface);
+    let node = MockNode::start(Vec::new());
+    let config = node.blockchain_access_config();
+    let client = RuskHttpClient::new(config.rusk_address.clone());
+
+    let ssk_user = SecretSpendKey::random(rng);
+    let ssk_lp = SecretSpendKey::random(rng);
+    let psk_lp = ssk_lp.public_spend_key();
+    let other_psk_lp = SecretSpendKey::random(rng).public_spend_key();
+
+    // request submitted before watching starts
+    let request_1 = RequestCreator::create(&ssk_user, &psk_lp, rng)?;
+    let tx_id = node.request_license(&request_1);
+    TxAwaiter::wait_for(&client, tx_id).await?;
+
+    let watch_config = WatchConfig {
+        poll_interval: POLL_INTERVAL,
+        ..WatchConfig::default()
+    };
+    let stream =
+        RequestScanner::watch(ssk_lp.view_key(), &config, watch_config);
+    let mut stream = Box::pin(stream);
+
+    let found = timeout(ITEM_TIMEOUT, stream.next())
+        .await
+        .expect("request should be yielded")
+        .expect("stream should not end")?;
+    assert_eq!(
+        MoatCoreUtils::to_hash_hex(&found),
+        MoatCoreUtils::to_hash_hex(&request_1)
+    );
+
+    // requests submitted while watching, only one addressed to the LP
+    let request_2 = RequestCreator::create(&ssk_user, &psk_lp, rng)?;
+    node.request_license(&RequestCreator::create(
+        &ssk_user,
+        &other_psk_lp,
+        rng,
+    )?);
+    let tx_id = node.request_license(&request_2);
+    TxAwaiter::wait_for(&client, tx_id).await?;
+
+    let found = timeout(ITEM_TIMEOUT, stream.next())
+        .await
+        .expect("request should be yielded")
+        .expect("stream should not end")?;
+    assert_eq!(
+        MoatCoreUtils::to_hash_hex(&found),
+        MoatCoreUtils::to_hash_hex(&request_2)
+    );
+
+    // tip block is scanned again on each poll, but no request is yielded
+    // twice
+    node.advance(2);
+    assert!(timeout(QUIET_PERIOD, stream.next()).await.is_err());
+    Ok(())
+}
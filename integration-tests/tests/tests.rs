@@ -6,4 +6,6 @@
 
 mod blockchain;
 mod citadel;
+mod mock_node;
+mod offline;
 mod websocket;
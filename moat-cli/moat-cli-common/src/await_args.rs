@@ -0,0 +1,32 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::time::Duration;
+use zk_citadel_moat::AwaitConfig;
+
+/// Arguments of waiting for the submitted transactions, shared by the CLIs
+/// submitting transactions.
+#[derive(clap::Args, Debug, Clone)]
+pub struct AwaitArgs {
+    /// Time in seconds after which waiting for a transaction is abandoned
+    #[clap(long, default_value_t = 120)]
+    pub await_timeout: u64,
+
+    /// Number of blocks required on top of the block containing a
+    /// transaction before it is considered final
+    #[clap(long, default_value_t = 0)]
+    pub confirmations: u64,
+}
+
+impl AwaitArgs {
+    pub fn await_config(&self) -> AwaitConfig {
+        AwaitConfig {
+            timeout: Duration::from_secs(self.await_timeout),
+            confirmations: self.confirmations,
+            ..AwaitConfig::default()
+        }
+    }
+}
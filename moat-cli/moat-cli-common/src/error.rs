@@ -36,6 +36,9 @@ pub enum Error {
     /// Wallet error
     #[error(transparent)]
     Wallet(Arc<dusk_wallet::Error>),
+    /// Serialization error
+    #[error(transparent)]
+    Serialization(Arc<serde_json::Error>),
 }
 
 impl From<zk_citadel_moat::Error> for Error {
@@ -97,3 +100,9 @@ impl From<dusk_wallet::Error> for Error {
         Error::Wallet(Arc::from(e))
     }
 }
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Serialization(Arc::from(e))
+    }
+}
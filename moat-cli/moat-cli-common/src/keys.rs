@@ -0,0 +1,114 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::Error;
+use dusk_wallet::WalletPath;
+use std::path::PathBuf;
+use zk_citadel_moat::wallet_accessor::Password;
+use zk_citadel_moat::CitadelKeys;
+
+/// Arguments selecting the keys used by a CLI, shared by all CLIs.
+#[derive(clap::Args, Debug, Clone)]
+pub struct KeysArgs {
+    /// JSON file with hex encoded keys (`psk_lp`, `ssk_lp`), used instead of
+    /// the wallet keys
+    #[clap(long)]
+    pub keys_path: Option<PathBuf>,
+
+    /// Index of the wallet address to use keys of [default: default
+    /// address]
+    #[clap(long)]
+    pub address_index: Option<usize>,
+}
+
+impl KeysArgs {
+    /// Loads keys from the keys file if given, otherwise derives them from
+    /// the selected wallet address.
+    pub fn load_keys(
+        &self,
+        wallet_path: &WalletPath,
+        psw: &Password,
+    ) -> Result<CitadelKeys, Error> {
+        let keys = match &self.keys_path {
+            Some(keys_path) => CitadelKeys::load(keys_path)?,
+            None => {
+                CitadelKeys::from_wallet(wallet_path, psw, self.address_index)?
+            }
+        };
+        Ok(keys)
+    }
+}
+
+/// Format of an exported public spend key.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyFormat {
+    Bs58,
+    Hex,
+}
+
+/// Key management subcommands, shared by all CLIs.
+#[derive(clap::Subcommand, Debug, Clone)]
+pub enum KeysCommand {
+    /// Generate fresh keys and save them to a JSON file
+    Generate {
+        /// Path of the JSON file to create
+        #[clap(long)]
+        out: PathBuf,
+    },
+    /// Print public spend key of the keys in use
+    Export {
+        #[clap(long, value_enum, default_value_t = KeyFormat::Bs58)]
+        format: KeyFormat,
+    },
+    /// Import keys from a JSON file, verifying them and saving them in
+    /// normalized form
+    Import {
+        /// Path of the JSON file to import
+        path: PathBuf,
+        /// Path of the JSON file to create
+        #[clap(long)]
+        out: PathBuf,
+    },
+}
+
+impl KeysCommand {
+    /// Runs the subcommand, returns text to be printed.
+    pub fn run(
+        self,
+        keys_args: &KeysArgs,
+        wallet_path: &WalletPath,
+        psw: &Password,
+    ) -> Result<String, Error> {
+        let output = match self {
+            KeysCommand::Generate { out } => {
+                let keys = CitadelKeys::random(&mut rand::rngs::OsRng);
+                keys.save(&out)?;
+                format!(
+                    "keys saved to {}\npublic spend key: {}",
+                    out.display(),
+                    keys.psk_bs58()
+                )
+            }
+            KeysCommand::Export { format } => {
+                let keys = keys_args.load_keys(wallet_path, psw)?;
+                match format {
+                    KeyFormat::Bs58 => keys.psk_bs58(),
+                    KeyFormat::Hex => keys.psk_hex(),
+                }
+            }
+            KeysCommand::Import { path, out } => {
+                let keys = CitadelKeys::load(&path)?;
+                keys.save(&out)?;
+                format!(
+                    "keys imported to {}\npublic spend key: {}",
+                    out.display(),
+                    keys.psk_bs58()
+                )
+            }
+        };
+        Ok(output)
+    }
+}
@@ -4,6 +4,14 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+mod await_args;
 mod error;
+mod keys;
+mod notices;
+mod output;
 
+pub use await_args::AwaitArgs;
 pub use error::Error;
+pub use keys::{KeyFormat, KeysArgs, KeysCommand};
+pub use notices::{append_json_line, NoticeLog, NOTICES_FILE_NAME};
+pub use output::OutputFormat;
@@ -0,0 +1,49 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::Error;
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use zk_citadel_moat::RejectionNotice;
+
+/// Default name of the file of rejection notices.
+pub const NOTICES_FILE_NAME: &str = "moat_lp_notices.log";
+
+/// Append-only file of rejection notices, one JSON notice per line, to be
+/// published to the users off-chain.
+pub struct NoticeLog {
+    path: PathBuf,
+}
+
+impl NoticeLog {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Appends notice and flushes it to disk.
+    pub fn append(&self, notice: &RejectionNotice) -> Result<(), Error> {
+        append_json_line(&self.path, notice)
+    }
+}
+
+/// Appends a given value as a JSON line to a file at a given path, and
+/// flushes it to disk.
+pub fn append_json_line<T: Serialize>(
+    path: &Path,
+    value: &T,
+) -> Result<(), Error> {
+    let mut line =
+        serde_json::to_string(value).map_err(zk_citadel_moat::Error::from)?;
+    line.push('\n');
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(line.as_bytes())?;
+    file.sync_data()?;
+    Ok(())
+}
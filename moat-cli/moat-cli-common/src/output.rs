@@ -0,0 +1,31 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::Error;
+use serde::Serialize;
+use std::fmt;
+
+/// Format in which command results are printed.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human readable text
+    Text,
+    /// JSON with stable field names, for consumption by other tools
+    Json,
+}
+
+impl OutputFormat {
+    /// Renders a command result in this format.
+    pub fn render<T: fmt::Display + Serialize>(
+        &self,
+        result: &T,
+    ) -> Result<String, Error> {
+        Ok(match self {
+            OutputFormat::Text => result.to_string(),
+            OutputFormat::Json => serde_json::to_string_pretty(result)?,
+        })
+    }
+}
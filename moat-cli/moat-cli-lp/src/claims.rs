@@ -0,0 +1,71 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use dusk_jubjub::JubJubScalar;
+use moat_cli_common::Error;
+use std::path::Path;
+use zk_citadel_moat::{
+    AgeBracket, AttributeEncoder, DefaultAttributeEncoder, JsonLoader,
+    LicenseClaims,
+};
+
+/// Provides attribute data of a license, either given as a raw number, or
+/// encoded by the `DefaultAttributeEncoder` from claims loaded from a JSON
+/// file or given as `key=value` pairs.
+pub(crate) fn attr_data(
+    claims: &[String],
+    claims_path: Option<&Path>,
+    raw_attr_data: Option<&str>,
+) -> Result<JubJubScalar, Error> {
+    if let Some(raw_attr_data) = raw_attr_data {
+        return Ok(JubJubScalar::from(raw_attr_data.parse::<u64>()?));
+    }
+    let claims = match claims_path {
+        Some(claims_path) => LicenseClaims::from_file(claims_path)?,
+        None if claims.is_empty() => {
+            return Err(Error::InvalidEntry(
+                "either claims or raw attribute data are required".into(),
+            ))
+        }
+        None => parse_claims(claims)?,
+    };
+    Ok(DefaultAttributeEncoder.encode(&claims)?)
+}
+
+/// Parses claims given as `key=value` pairs, claims not given are
+/// unspecified.
+fn parse_claims(claims: &[String]) -> Result<LicenseClaims, Error> {
+    let mut license_claims = LicenseClaims::default();
+    for claim in claims {
+        let (key, value) = claim.split_once('=').ok_or_else(|| {
+            Error::InvalidEntry(
+                format!("claim {} is not key=value", claim).into(),
+            )
+        })?;
+        match key.trim() {
+            "age_bracket" => {
+                license_claims.age_bracket =
+                    serde_json::from_value::<AgeBracket>(value.trim().into())
+                        .map_err(|_| {
+                            Error::InvalidEntry(
+                                format!("unknown age bracket {}", value).into(),
+                            )
+                        })?
+            }
+            "jurisdiction" => {
+                license_claims.jurisdiction = value.trim().to_string()
+            }
+            "kyc_tier" => license_claims.kyc_tier = value.trim().parse()?,
+            "expiry" => license_claims.expiry = Some(value.trim().parse()?),
+            _ => {
+                return Err(Error::InvalidEntry(
+                    format!("unknown claim {}", key).into(),
+                ))
+            }
+        }
+    }
+    Ok(license_claims)
+}
@@ -4,28 +4,62 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+use crate::claims;
 use crate::run_result::{
-    IssueLicenseSummary, LicenseContractSummary, RequestsLPSummary, RunResult,
+    LicenseContractSummary, ListLicensesSummary, RejectRequestSummary,
+    RequestsLPSummary, RunResult,
 };
 use crate::SeedableRng;
+use clap::Subcommand;
 use dusk_jubjub::JubJubScalar;
 use dusk_pki::SecretSpendKey;
 use dusk_wallet::{RuskHttpClient, WalletPath};
-use moat_cli_common::Error;
-use rand::rngs::StdRng;
-use zk_citadel_moat::license_provider::{LicenseIssuer, ReferenceLP};
+use moat_cli_common::{Error, NoticeLog, NOTICES_FILE_NAME};
+use rand::rngs::{OsRng, StdRng};
+use std::path::PathBuf;
+use zk_citadel_moat::api::{MoatContext, MoatCore};
+use zk_citadel_moat::license_provider::{LPCheckpointStore, ReferenceLP};
 use zk_citadel_moat::wallet_accessor::{BlockchainAccessConfig, Password};
-use zk_citadel_moat::{BcInquirer, CitadelInquirer};
+use zk_citadel_moat::{
+    AwaitConfig, BcInquirer, CitadelInquirer, MoatCoreUtils, RejectionNotice,
+};
 
 /// Commands that can be run against the Moat
-#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Debug, Subcommand)]
 pub(crate) enum Command {
     /// List requests
+    #[command(name = "list-requests")]
     ListRequestsLP,
     /// Issue license
+    #[command(name = "issue-license")]
     IssueLicenseLP {
+        /// Hash of the request
+        #[arg(long)]
+        request_hash: String,
+        /// Claim attested by the license, as `key=value`, where key is one
+        /// of `age_bracket`, `jurisdiction`, `kyc_tier` or `expiry`
+        #[arg(long = "claim")]
+        claims: Vec<String>,
+        /// JSON file with the claims attested by the license
+        #[arg(long, conflicts_with = "claims")]
+        claims_path: Option<PathBuf>,
+        /// Raw attribute data of the license, used instead of claims
+        #[arg(long = "attr", conflicts_with_all = ["claims", "claims_path"])]
+        attr_data_bytes: Option<String>,
+    },
+    /// Reject request
+    #[command(name = "reject-request")]
+    RejectRequestLP {
+        /// Hash of the request
+        #[arg(long)]
         request_hash: String,
-        attr_data_bytes: String,
+        /// Reason of the rejection, readable only by the user
+        #[arg(long)]
+        reason: String,
+        /// File the rejection notice is appended to [default:
+        /// `moat_lp_notices.log` in the wallet directory]
+        #[arg(long)]
+        notices_path: Option<PathBuf>,
     },
     /// List licenses (User)
     ListLicenses,
@@ -43,15 +77,29 @@ impl Command {
         ssk: &SecretSpendKey,
         gas_limit: u64,
         gas_price: u64,
+        estimate_gas: bool,
+        await_config: &AwaitConfig,
     ) -> Result<RunResult, Error> {
         let run_result = match self {
             Command::ListRequestsLP => {
-                Self::list_requests_lp(blockchain_access_config, ssk).await?
+                Self::list_requests_lp(
+                    wallet_path,
+                    blockchain_access_config,
+                    ssk,
+                )
+                .await?
             }
             Command::IssueLicenseLP {
                 request_hash,
+                claims,
+                claims_path,
                 attr_data_bytes,
             } => {
+                let attr_data = claims::attr_data(
+                    &claims,
+                    claims_path.as_deref(),
+                    attr_data_bytes.as_deref(),
+                )?;
                 Self::issue_license_lp(
                     wallet_path,
                     psw,
@@ -59,8 +107,25 @@ impl Command {
                     ssk,
                     gas_limit,
                     gas_price,
+                    estimate_gas,
+                    await_config,
                     request_hash,
-                    attr_data_bytes,
+                    attr_data,
+                )
+                .await?
+            }
+            Command::RejectRequestLP {
+                request_hash,
+                reason,
+                notices_path,
+            } => {
+                Self::reject_request_lp(
+                    wallet_path,
+                    blockchain_access_config,
+                    ssk,
+                    request_hash,
+                    reason,
+                    notices_path,
                 )
                 .await?
             }
@@ -74,22 +139,54 @@ impl Command {
         Ok(run_result)
     }
 
+    /// Creates License Provider from the checkpoint in the wallet directory
+    /// and scans for new requests, saving the checkpoint.
+    /// Returns total number of requests found and number of requests
+    /// addressed to this LP, along with the License Provider.
+    async fn scan_requests(
+        wallet_path: &WalletPath,
+        blockchain_access_config: &BlockchainAccessConfig,
+        ssk: &SecretSpendKey,
+    ) -> Result<(ReferenceLP, usize, usize), Error> {
+        let client =
+            RuskHttpClient::new(blockchain_access_config.rusk_address.clone());
+        let store = LPCheckpointStore::from_wallet_dir(wallet_path);
+        let (reference_lp, (found_total, found_owned)) = match &store {
+            Some(store) => {
+                let mut reference_lp =
+                    ReferenceLP::create_with_checkpoint(ssk, store)?;
+                let found =
+                    reference_lp.scan_with_checkpoint(&client, store).await?;
+                (reference_lp, found)
+            }
+            None => {
+                let mut reference_lp = ReferenceLP::create_with_ssk(ssk)?;
+                let found = reference_lp.scan(&client).await?;
+                (reference_lp, found)
+            }
+        };
+        Ok((reference_lp, found_total, found_owned))
+    }
+
     /// Command: List Requests LP
     async fn list_requests_lp(
+        wallet_path: &WalletPath,
         blockchain_access_config: &BlockchainAccessConfig,
         ssk: &SecretSpendKey,
     ) -> Result<RunResult, Error> {
-        let mut reference_lp = ReferenceLP::create_with_ssk(ssk)?;
-        let (found_total, found_owned) =
-            reference_lp.scan(blockchain_access_config).await?;
+        let (reference_lp, found_total, found_owned) =
+            Self::scan_requests(wallet_path, blockchain_access_config, ssk)
+                .await?;
         let summary = RequestsLPSummary {
             found_total,
             found_owned,
+            request_hashes: reference_lp
+                .requests_to_process
+                .iter()
+                .map(MoatCoreUtils::to_hash_hex)
+                .collect(),
         };
-        Ok(RunResult::RequestsLP(
-            summary,
-            reference_lp.requests_to_process,
-        ))
+        Ok(RunResult::RequestsLP(summary))
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -101,45 +198,87 @@ impl Command {
         ssk: &SecretSpendKey,
         gas_limit: u64,
         gas_price: u64,
+        estimate_gas: bool,
+        await_config: &AwaitConfig,
         request_hash: String,
-        attr_data_bytes: String,
+        attr_data: JubJubScalar,
     ) -> Result<RunResult, Error> {
-        let attr_data = JubJubScalar::from(attr_data_bytes.parse::<u64>()?);
-
-        let mut rng = StdRng::from_entropy();
-        let mut reference_lp = ReferenceLP::create_with_ssk(ssk)?;
-        let (_total_count, _this_lp_count) =
-            reference_lp.scan(blockchain_access_config).await?;
-
-        let request = reference_lp.get_request(&request_hash);
+        let (reference_lp, _total_count, _this_lp_count) =
+            Self::scan_requests(wallet_path, blockchain_access_config, ssk)
+                .await?;
+        let request = reference_lp.requests_to_process.iter().find(|request| {
+            MoatCoreUtils::to_hash_hex(*request) == request_hash
+        });
         Ok(match request {
             Some(request) => {
-                let license_issuer = LicenseIssuer::new(
+                let moat_context = MoatContext::builder(
                     blockchain_access_config.clone(),
                     wallet_path.clone(),
                     psw.clone(),
-                    gas_limit,
-                    gas_price,
-                );
-                let (tx_id, license_blob) = license_issuer
-                    .issue_license(
-                        &mut rng,
-                        &request,
-                        &reference_lp.ssk_lp,
-                        &attr_data,
-                    )
-                    .await?;
-                let summary = IssueLicenseSummary {
+                )
+                .gas_limit(gas_limit)
+                .gas_price(gas_price)
+                .await_config(await_config.clone())
+                .gas_estimation(estimate_gas)
+                .build();
+                let receipt = MoatCore::issue_license(
                     request,
-                    tx_id: hex::encode(tx_id.to_bytes()),
-                    license_blob,
-                };
-                RunResult::IssueLicense(Some(summary))
+                    ssk,
+                    &moat_context,
+                    &attr_data,
+                    &mut OsRng,
+                )
+                .await?;
+                RunResult::IssueLicense(Some(receipt.into()))
             }
             _ => RunResult::IssueLicense(None),
         })
     }
 
+    /// Command: Reject Request LP
+    async fn reject_request_lp(
+        wallet_path: &WalletPath,
+        blockchain_access_config: &BlockchainAccessConfig,
+        ssk: &SecretSpendKey,
+        request_hash: String,
+        reason: String,
+        notices_path: Option<PathBuf>,
+    ) -> Result<RunResult, Error> {
+        let (mut reference_lp, _total_count, _this_lp_count) =
+            Self::scan_requests(wallet_path, blockchain_access_config, ssk)
+                .await?;
+        let request = reference_lp.reject_request(&request_hash, &reason);
+        Ok(match request {
+            Some(request) => {
+                if let Some(store) =
+                    LPCheckpointStore::from_wallet_dir(wallet_path)
+                {
+                    store.save(&reference_lp.checkpoint())?;
+                }
+                let notices_path = notices_path.unwrap_or_else(|| {
+                    wallet_path
+                        .dir()
+                        .map(|dir| dir.join(NOTICES_FILE_NAME))
+                        .unwrap_or_else(|| PathBuf::from(NOTICES_FILE_NAME))
+                });
+                let mut rng = StdRng::from_entropy();
+                let notice = RejectionNotice::create(
+                    &mut rng,
+                    &request,
+                    &reference_lp.ssk_lp,
+                    &reason,
+                );
+                NoticeLog::new(&notices_path).append(&notice)?;
+                RunResult::RejectRequest(Some(RejectRequestSummary {
+                    request_hash,
+                    reason,
+                    notices_path,
+                }))
+            }
+            _ => RunResult::RejectRequest(None),
+        })
+    }
+
     /// Command: List Licenses
     async fn list_licenses(
         blockchain_access_config: &BlockchainAccessConfig,
@@ -153,10 +292,13 @@ impl Command {
             CitadelInquirer::get_licenses(&client, block_range.clone()).await?;
 
         let pairs = CitadelInquirer::find_all_licenses(&mut licenses_stream)?;
-        Ok(RunResult::ListLicenses(
+        Ok(RunResult::ListLicenses(ListLicensesSummary {
             block_range,
-            pairs.into_iter().map(|(_, l)| l).collect(),
-        ))
+            license_hashes: pairs
+                .iter()
+                .map(|(_, license)| MoatCoreUtils::to_hash_hex(license))
+                .collect(),
+        }))
     }
 
     /// Command: Show State
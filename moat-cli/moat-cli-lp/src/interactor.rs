@@ -5,12 +5,14 @@
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
 use crate::prompt;
+use crate::run_result::RunResult;
 use crate::{Command, Menu};
 use dusk_pki::SecretSpendKey;
 use dusk_wallet::WalletPath;
-use moat_cli_common::Error;
+use moat_cli_common::{Error, OutputFormat};
 use requestty::{ErrorKind, Question};
 use zk_citadel_moat::wallet_accessor::{BlockchainAccessConfig, Password};
+use zk_citadel_moat::AwaitConfig;
 
 #[derive(PartialEq, Eq, Hash, Debug, Clone)]
 enum OpSelection {
@@ -22,6 +24,7 @@ enum OpSelection {
 enum CommandMenuItem {
     ListRequestsLP,
     IssueLicenseLP,
+    RejectRequestLP,
     ListLicenses,
     ShowState,
     Exit,
@@ -31,6 +34,7 @@ fn menu_operation() -> Result<OpSelection, ErrorKind> {
     let cmd_menu = Menu::new()
         .add(CommandMenuItem::ListRequestsLP, "List Requests")
         .add(CommandMenuItem::IssueLicenseLP, "Issue License")
+        .add(CommandMenuItem::RejectRequestLP, "Reject Request")
         .add(CommandMenuItem::ListLicenses, "List Licenses")
         .add(CommandMenuItem::ShowState, "Show state")
         .separator()
@@ -50,7 +54,16 @@ fn menu_operation() -> Result<OpSelection, ErrorKind> {
         CommandMenuItem::IssueLicenseLP => {
             OpSelection::Run(Box::from(Command::IssueLicenseLP {
                 request_hash: prompt::request_request_hash()?,
-                attr_data_bytes: prompt::request_attr_data()?,
+                claims: prompt::request_claims()?,
+                claims_path: None,
+                attr_data_bytes: None,
+            }))
+        }
+        CommandMenuItem::RejectRequestLP => {
+            OpSelection::Run(Box::from(Command::RejectRequestLP {
+                request_hash: prompt::request_request_hash()?,
+                reason: prompt::request_reason()?,
+                notices_path: None,
             }))
         }
         CommandMenuItem::ListLicenses => {
@@ -70,6 +83,9 @@ pub struct Interactor {
     pub ssk: SecretSpendKey,
     pub gas_limit: u64,
     pub gas_price: u64,
+    pub estimate_gas: bool,
+    pub await_config: AwaitConfig,
+    pub output: OutputFormat,
 }
 
 impl Interactor {
@@ -79,19 +95,13 @@ impl Interactor {
             match op {
                 OpSelection::Exit => return Ok(()),
                 OpSelection::Run(command) => {
-                    let result = command
-                        .run(
-                            &self.wallet_path,
-                            &self.psw,
-                            &self.blockchain_access_config,
-                            &self.ssk,
-                            self.gas_limit,
-                            self.gas_price,
-                        )
-                        .await;
+                    let result = self
+                        .run_command(*command)
+                        .await
+                        .and_then(|run_result| self.output.render(&run_result));
                     match result {
-                        Ok(run_result) => {
-                            println!("{}", run_result);
+                        Ok(output) => {
+                            println!("{}", output);
                         }
                         Err(error) => {
                             println!("{}", error);
@@ -102,4 +112,23 @@ impl Interactor {
             }
         }
     }
+
+    /// Runs a single command without prompting
+    pub async fn run_command(
+        &mut self,
+        command: Command,
+    ) -> Result<RunResult, Error> {
+        command
+            .run(
+                &self.wallet_path,
+                &self.psw,
+                &self.blockchain_access_config,
+                &self.ssk,
+                self.gas_limit,
+                self.gas_price,
+                self.estimate_gas,
+                &self.await_config,
+            )
+            .await
+    }
 }
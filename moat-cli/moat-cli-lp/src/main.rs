@@ -7,6 +7,7 @@
 #![feature(stmt_expr_attributes)]
 
 mod args;
+mod claims;
 mod command;
 mod config;
 mod interactor;
@@ -14,23 +15,21 @@ mod menu;
 mod prompt;
 mod run_result;
 
-use crate::args::Args;
+use crate::args::{Args, CliCommand};
 use crate::command::Command;
 use crate::menu::Menu;
-use std::fs;
+use std::{fs, process};
 
 use clap::Parser;
 
 use crate::config::LPCliConfig;
 use crate::interactor::Interactor;
-use dusk_wallet::{Wallet, WalletPath};
+use dusk_wallet::WalletPath;
 use moat_cli_common::Error;
 use rand::SeedableRng;
 use toml_base_config::BaseConfig;
+use zk_citadel_moat::wallet_accessor::BlockchainAccessConfig;
 use zk_citadel_moat::wallet_accessor::Password::{Pwd, PwdHash};
-use zk_citadel_moat::wallet_accessor::{
-    BlockchainAccessConfig, WalletAccessor,
-};
 
 #[tokio::main]
 async fn main() -> Result<(), Error> {
@@ -42,6 +41,25 @@ async fn main() -> Result<(), Error> {
     let pwd_hash = cli.pwd_hash;
     let gas_limit = cli.gas_limit;
     let gas_price = cli.gas_price;
+    let output = cli.output;
+    let estimate_gas = cli.estimate_gas;
+    let await_config = cli.await_args.await_config();
+
+    let wallet_path = WalletPath::from(wallet_path.join("wallet.dat"));
+    let psw = if pwd_hash.is_empty() {
+        Pwd(password)
+    } else {
+        PwdHash(pwd_hash)
+    };
+
+    let command = match cli.command {
+        Some(CliCommand::Keys(keys_command)) => {
+            println!("{}", keys_command.run(&cli.keys, &wallet_path, &psw)?);
+            return Ok(());
+        }
+        Some(CliCommand::Moat(command)) => Some(command),
+        None => None,
+    };
 
     let _ = fs::metadata(config_path).map_err(|_| {
         Error::NotFound(config_path.to_string_lossy().into_owned().into())
@@ -50,20 +68,10 @@ async fn main() -> Result<(), Error> {
     let blockchain_access_config = BlockchainAccessConfig {
         rusk_address: config.rusk_address.clone(),
         prover_address: config.prover_address.clone(),
+        ..Default::default()
     };
 
-    let wallet_path = WalletPath::from(wallet_path.join("wallet.dat"));
-    let psw = if pwd_hash.is_empty() {
-        Pwd(password)
-    } else {
-        PwdHash(pwd_hash)
-    };
-
-    let wallet_accessor =
-        WalletAccessor::create(wallet_path.clone(), psw.clone())?;
-    let wallet = Wallet::from_file(wallet_accessor)?;
-
-    let (_psk, ssk) = wallet.spending_keys(wallet.default_address())?;
+    let ssk = cli.keys.load_keys(&wallet_path, &psw)?.ssk;
 
     let mut interactor = Interactor {
         wallet_path,
@@ -72,9 +80,21 @@ async fn main() -> Result<(), Error> {
         ssk,
         gas_limit,
         gas_price,
+        estimate_gas,
+        await_config,
+        output,
     };
 
-    interactor.run_loop().await?;
+    match command {
+        Some(command) => {
+            let run_result = interactor.run_command(command).await?;
+            println!("{}", output.render(&run_result)?);
+            if !run_result.is_success() {
+                process::exit(1);
+            }
+        }
+        None => interactor.run_loop().await?,
+    }
 
     Ok(())
 }
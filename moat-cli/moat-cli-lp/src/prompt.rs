@@ -24,13 +24,34 @@ pub(crate) fn request_request_hash() -> Result<String, ErrorKind> {
     Ok(a_str)
 }
 
-pub(crate) fn request_attr_data() -> Result<String, ErrorKind> {
-    let q = Question::input("attr_data")
-        .message("Please enter the attribute data:".to_string())
+pub(crate) fn request_claims() -> Result<Vec<String>, ErrorKind> {
+    let q = Question::input("claims")
+        .message(
+            "Please enter the claims as key=value pairs separated by spaces:"
+                .to_string(),
+        )
         .validate_on_key(|_, _| true)
-        .validate(|attr_data, _| {
-            if attr_data.is_empty() {
-                Err("Please enter valid attribute data".to_string())
+        .validate(|claims, _| {
+            if claims.split_whitespace().all(|claim| claim.contains('=')) {
+                Ok(())
+            } else {
+                Err("Please enter claims as key=value pairs".to_string())
+            }
+        })
+        .build();
+
+    let a = requestty::prompt_one(q)?;
+    let a_str = a.as_string().expect("answer to be a string");
+    Ok(a_str.split_whitespace().map(String::from).collect())
+}
+
+pub(crate) fn request_reason() -> Result<String, ErrorKind> {
+    let q = Question::input("reason")
+        .message("Please enter the reason of the rejection:".to_string())
+        .validate_on_key(|_, _| true)
+        .validate(|reason, _| {
+            if reason.is_empty() {
+                Err("Please enter a reason".to_string())
             } else {
                 Ok(())
             }
@@ -4,73 +4,130 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+use serde::Serialize;
 use std::fmt;
 use std::ops::Range;
-use zk_citadel::license::{License, Request};
-use zk_citadel_moat::MoatCoreUtils;
+use std::path::PathBuf;
+use zk_citadel_moat::LicenseReceipt;
 
+#[derive(Serialize)]
 pub struct RequestsLPSummary {
     pub found_total: usize,
     pub found_owned: usize,
+    pub request_hashes: Vec<String>,
 }
 
+#[derive(Serialize)]
 pub struct IssueLicenseSummary {
-    pub request: Request,
+    pub request_hash: String,
     pub tx_id: String,
-    pub license_blob: Vec<u8>,
+    pub block_height: u64,
+    pub gas_spent: u64,
+    pub license_hash: String,
 }
 
+impl From<LicenseReceipt> for IssueLicenseSummary {
+    fn from(receipt: LicenseReceipt) -> Self {
+        Self {
+            request_hash: receipt.request_hash,
+            tx_id: receipt.tx_id,
+            block_height: receipt.block_height,
+            gas_spent: receipt.gas_spent,
+            license_hash: receipt.license_hash,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct RejectRequestSummary {
+    pub request_hash: String,
+    pub reason: String,
+    pub notices_path: PathBuf,
+}
+
+#[derive(Serialize)]
+pub struct ListLicensesSummary {
+    pub block_range: Range<u64>,
+    pub license_hashes: Vec<String>,
+}
+
+#[derive(Serialize)]
 pub struct LicenseContractSummary {
     pub num_licenses: u32,
     pub num_sessions: u32,
 }
 
 #[allow(clippy::large_enum_variant)]
-/// Possible results of running a command in interactive mode
+/// Possible results of running a command in interactive mode, serialized
+/// as the contained summary in JSON output
+#[derive(Serialize)]
+#[serde(untagged)]
 pub enum RunResult {
-    RequestsLP(RequestsLPSummary, Vec<Request>),
+    RequestsLP(RequestsLPSummary),
     IssueLicense(Option<IssueLicenseSummary>),
-    ListLicenses(Range<u64>, Vec<License>),
+    RejectRequest(Option<RejectRequestSummary>),
+    ListLicenses(ListLicensesSummary),
     ShowState(LicenseContractSummary),
 }
 
+impl RunResult {
+    /// Returns false if the command could not be carried out, e.g. when the
+    /// request to issue a license for was not found.
+    pub fn is_success(&self) -> bool {
+        !matches!(
+            self,
+            RunResult::IssueLicense(None) | RunResult::RejectRequest(None)
+        )
+    }
+}
+
 impl fmt::Display for RunResult {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use RunResult::*;
         match self {
-            RequestsLP(summary, requests) => {
+            RequestsLP(summary) => {
                 writeln!(
                     f,
                     "found {} requests total, {} requests for this LP:",
                     summary.found_total, summary.found_owned
                 )?;
-                for request in requests.iter() {
-                    writeln!(
-                        f,
-                        "request to process by LP: {}",
-                        MoatCoreUtils::to_hash_hex(request)
-                    )?;
+                for request_hash in summary.request_hashes.iter() {
+                    writeln!(f, "request to process by LP: {}", request_hash)?;
                 }
                 Ok(())
             }
-            IssueLicense(summary) => match summary {
-                Some(summary) => {
+            IssueLicense(receipt) => match receipt {
+                Some(receipt) => {
                     writeln!(
                         f,
                         "issuing license for request: {}",
-                        MoatCoreUtils::to_hash_hex(&summary.request)
+                        receipt.request_hash
                     )?;
                     writeln!(
                         f,
-                        "license issuing transaction {} confirmed",
-                        summary.tx_id
+                        "license issuing transaction {} confirmed at block height {}",
+                        receipt.tx_id, receipt.block_height
+                    )?;
+                    writeln!(f, "gas spent: {}", receipt.gas_spent)?;
+                    writeln!(f, "issued license: {}", receipt.license_hash)?;
+                    Ok(())
+                }
+                _ => {
+                    writeln!(f, "Request not found")?;
+                    Ok(())
+                }
+            },
+            RejectRequest(summary) => match summary {
+                Some(summary) => {
+                    writeln!(
+                        f,
+                        "rejected request: {}, reason: {}",
+                        summary.request_hash, summary.reason
                     )?;
                     writeln!(
                         f,
-                        "issued license: {}",
-                        MoatCoreUtils::blob_to_hash_hex(
-                            summary.license_blob.as_slice()
-                        )
+                        "rejection notice appended to: {}",
+                        summary.notices_path.display()
                     )?;
                     Ok(())
                 }
@@ -79,21 +136,17 @@ impl fmt::Display for RunResult {
                     Ok(())
                 }
             },
-            ListLicenses(block_range, licenses) => {
+            ListLicenses(summary) => {
                 writeln!(
                     f,
                     "getting licenses within the block height range {:?}:",
-                    block_range
+                    summary.block_range
                 )?;
-                if licenses.is_empty() {
+                if summary.license_hashes.is_empty() {
                     writeln!(f, "licenses not found")?;
                 } else {
-                    for license in licenses.iter() {
-                        writeln!(
-                            f,
-                            "license: {}",
-                            MoatCoreUtils::to_hash_hex(license),
-                        )?;
+                    for license_hash in summary.license_hashes.iter() {
+                        writeln!(f, "license: {}", license_hash)?;
                     }
                 }
                 Ok(())
@@ -8,6 +8,7 @@ use crate::run_result::{
     LicenseContractSummary, RunResult, ServiceRequestSummary, SessionSummary,
 };
 use crate::Error;
+use clap::Subcommand;
 use dusk_bls12_381::BlsScalar;
 use dusk_bytes::DeserializableSlice;
 use dusk_jubjub::JubJubAffine;
@@ -18,15 +19,23 @@ use zk_citadel_moat::wallet_accessor::BlockchainAccessConfig;
 use zk_citadel_moat::{CitadelInquirer, LicenseSessionId};
 
 /// Commands that can be run against the Moat
-#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Debug, Subcommand)]
 pub(crate) enum Command {
-    /// Request Service (User)
+    /// Verify requested service (SP)
     VerifyRequestedService {
+        /// Session cookie presented by the user
+        #[arg(long)]
         session_cookie: String,
+        /// Address of the LP
+        #[arg(long = "psk-lp")]
         psk_lp_bytes: String,
     },
     /// Get session (SP)
-    GetSession { session_id: String },
+    GetSession {
+        /// Id of the session
+        #[arg(long)]
+        session_id: String,
+    },
     /// Show state
     ShowState,
 }
@@ -6,10 +6,11 @@
 
 use crate::config::SPCliConfig;
 use crate::prompt;
+use crate::run_result::RunResult;
 use crate::{Command, Menu};
 use dusk_pki::PublicSpendKey;
 use dusk_wallet::WalletPath;
-use moat_cli_common::Error;
+use moat_cli_common::{Error, OutputFormat};
 use requestty::{ErrorKind, Question};
 use zk_citadel_moat::wallet_accessor::{BlockchainAccessConfig, Password};
 
@@ -72,6 +73,7 @@ pub struct Interactor {
     pub gas_limit: u64,
     pub gas_price: u64,
     pub psk_sp: PublicSpendKey,
+    pub output: OutputFormat,
 }
 
 impl Interactor {
@@ -81,12 +83,13 @@ impl Interactor {
             match op {
                 OpSelection::Exit => return Ok(()),
                 OpSelection::Run(command) => {
-                    let result = command
-                        .run(&self.blockchain_access_config, self.psk_sp)
-                        .await;
+                    let result = self
+                        .run_command(*command)
+                        .await
+                        .and_then(|run_result| self.output.render(&run_result));
                     match result {
-                        Ok(run_result) => {
-                            println!("{}", run_result);
+                        Ok(output) => {
+                            println!("{}", output);
                         }
                         Err(error) => {
                             println!("{}", error);
@@ -97,4 +100,14 @@ impl Interactor {
             }
         }
     }
+
+    /// Runs a single command without prompting
+    pub async fn run_command(
+        &mut self,
+        command: Command,
+    ) -> Result<RunResult, Error> {
+        command
+            .run(&self.blockchain_access_config, self.psk_sp)
+            .await
+    }
 }
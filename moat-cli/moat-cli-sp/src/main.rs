@@ -14,21 +14,20 @@ mod menu;
 mod prompt;
 mod run_result;
 
-use crate::args::Args;
+use crate::args::{Args, CliCommand};
 use crate::command::Command;
 use crate::menu::Menu;
+use std::process;
 
 use clap::Parser;
 
 use crate::config::SPCliConfig;
 use crate::interactor::Interactor;
-use dusk_wallet::{Wallet, WalletPath};
+use dusk_wallet::WalletPath;
 use moat_cli_common::Error;
 use toml_base_config::BaseConfig;
+use zk_citadel_moat::wallet_accessor::BlockchainAccessConfig;
 use zk_citadel_moat::wallet_accessor::Password::{Pwd, PwdHash};
-use zk_citadel_moat::wallet_accessor::{
-    BlockchainAccessConfig, WalletAccessor,
-};
 
 #[tokio::main]
 async fn main() -> Result<(), Error> {
@@ -40,10 +39,7 @@ async fn main() -> Result<(), Error> {
     let pwd_hash = cli.pwd_hash;
     let gas_limit = cli.gas_limit;
     let gas_price = cli.gas_price;
-
-    let config = SPCliConfig::load_path(config_path)?;
-    let blockchain_access_config =
-        BlockchainAccessConfig::load_path(config_path)?;
+    let output = cli.output;
 
     let wallet_path = WalletPath::from(wallet_path.join("wallet.dat"));
     let psw = if pwd_hash.is_empty() {
@@ -52,11 +48,20 @@ async fn main() -> Result<(), Error> {
         PwdHash(pwd_hash)
     };
 
-    let wallet_accessor =
-        WalletAccessor::create(wallet_path.clone(), psw.clone())?;
-    let wallet = Wallet::from_file(wallet_accessor)?;
+    let command = match cli.command {
+        Some(CliCommand::Keys(keys_command)) => {
+            println!("{}", keys_command.run(&cli.keys, &wallet_path, &psw)?);
+            return Ok(());
+        }
+        Some(CliCommand::Moat(command)) => Some(command),
+        None => None,
+    };
+
+    let config = SPCliConfig::load_path(config_path)?;
+    let blockchain_access_config =
+        BlockchainAccessConfig::load_path(config_path)?;
 
-    let (psk_sp, _ssk_sp) = wallet.spending_keys(wallet.default_address())?;
+    let psk_sp = cli.keys.load_keys(&wallet_path, &psw)?.psk;
 
     let mut interactor = Interactor {
         wallet_path,
@@ -66,9 +71,19 @@ async fn main() -> Result<(), Error> {
         gas_limit,
         gas_price,
         psk_sp,
+        output,
     };
 
-    interactor.run_loop().await?;
+    match command {
+        Some(command) => {
+            let run_result = interactor.run_command(command).await?;
+            println!("{}", output.render(&run_result)?);
+            if !run_result.is_success() {
+                process::exit(1);
+            }
+        }
+        None => interactor.run_loop().await?,
+    }
 
     Ok(())
 }
@@ -4,30 +4,49 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+use serde::Serialize;
 use std::fmt;
 
+#[derive(Serialize)]
 pub struct ServiceRequestSummary {
     pub service_granted: bool,
 }
 
+#[derive(Serialize)]
 pub struct SessionSummary {
     pub session_id: String,
     pub session: Vec<String>,
 }
 
+#[derive(Serialize)]
 pub struct LicenseContractSummary {
     pub num_licenses: u32,
     pub num_sessions: u32,
 }
 
 #[allow(clippy::large_enum_variant)]
-/// Possible results of running a command in interactive mode
+/// Possible results of running a command in interactive mode, serialized
+/// as the contained summary in JSON output
+#[derive(Serialize)]
+#[serde(untagged)]
 pub enum RunResult {
     RequestService(ServiceRequestSummary),
     GetSession(Option<SessionSummary>),
     ShowState(LicenseContractSummary),
 }
 
+impl RunResult {
+    /// Returns false if the command could not be carried out, e.g. when the
+    /// session was not found or the service cannot be granted.
+    pub fn is_success(&self) -> bool {
+        match self {
+            RunResult::RequestService(summary) => summary.service_granted,
+            RunResult::GetSession(summary) => summary.is_some(),
+            RunResult::ShowState(_) => true,
+        }
+    }
+}
+
 impl fmt::Display for RunResult {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use RunResult::*;
@@ -6,7 +6,9 @@
 
 use std::path::PathBuf;
 
-use clap::Parser;
+use crate::command::Command;
+use clap::{Parser, Subcommand};
+use moat_cli_common::{AwaitArgs, KeysArgs, KeysCommand, OutputFormat};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -35,4 +37,33 @@ pub struct Args {
     /// Gas price [default: `1`]
     #[clap(long, default_value_t = 1)]
     pub gas_price: u64,
+
+    /// Estimate gas limit from past calls, gas limit is used when no
+    /// estimate is available
+    #[clap(long)]
+    pub estimate_gas: bool,
+
+    /// Output format of command results
+    #[clap(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+
+    #[command(flatten)]
+    pub await_args: AwaitArgs,
+
+    #[command(flatten)]
+    pub keys: KeysArgs,
+
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+/// Commands run without entering the interactive mode, the process exits
+/// with a non-zero code if the command fails
+#[derive(Subcommand, Debug)]
+pub enum CliCommand {
+    /// Manage keys
+    #[command(subcommand)]
+    Keys(KeysCommand),
+    #[command(flatten)]
+    Moat(Command),
 }
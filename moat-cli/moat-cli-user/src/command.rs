@@ -4,50 +4,75 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
-use crate::interactor::SetupHolder;
 use crate::run_result::{
-    LicenseContractSummary, RunResult, SubmitRequestSummary, UseLicenseSummary,
+    LicenseContractSummary, LicenseSummary, ListLicensesSummary, RunResult,
+    SubmitRequestSummary, UseLicenseSummary,
 };
 use crate::SeedableRng;
-use dusk_bls12_381::BlsScalar;
-use dusk_bytes::DeserializableSlice;
+use clap::Subcommand;
+use dusk_bytes::{DeserializableSlice, Serializable};
 use dusk_pki::{PublicSpendKey, SecretSpendKey};
-use dusk_plonk::prelude::*;
 use dusk_wallet::{RuskHttpClient, WalletPath};
 use moat_cli_common::Error;
 use rand::rngs::{OsRng, StdRng};
-use zk_citadel::license::{License, SessionCookie};
+use zk_citadel::license::SessionCookie;
+use zk_citadel_moat::api::MoatCore;
 use zk_citadel_moat::wallet_accessor::{BlockchainAccessConfig, Password};
 use zk_citadel_moat::{
-    BcInquirer, CitadelInquirer, CrsGetter, LicenseCircuit, LicenseUser,
-    MoatCoreUtils, RequestCreator, RequestSender, TxAwaiter,
+    AwaitConfig, CircuitKeyStore, CitadelInquirer, GasEstimator, LicenseCache,
+    MoatCoreUtils, RequestCreator, RequestHistory, RequestReceipt,
+    RequestSender, RequestStatus, TxAwaiter, REQUEST_LICENSE_METHOD_NAME,
+    USE_LICENSE_METHOD_NAME,
 };
 
-use std::fs::File;
-use std::io::prelude::*;
-
 /// Commands that can be run against the Moat
-#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Debug, Subcommand)]
 pub(crate) enum Command {
     /// Submit request (User)
-    SubmitRequest { psk_lp_bytes: String },
+    SubmitRequest {
+        /// Address of the LP
+        #[arg(long = "psk-lp")]
+        psk_lp_bytes: String,
+    },
+    /// Request status (User), for all requests if request hash is empty
+    RequestStatus {
+        /// Hash of the request
+        #[arg(long, default_value_t = String::from(""))]
+        request_hash: String,
+    },
     /// List licenses (User)
     ListLicenses,
     /// Use license (User)
     UseLicense {
+        /// Hash of the license to use
+        #[arg(long)]
         license_hash: String,
+        /// Address of the LP
+        #[arg(long = "psk-lp")]
         psk_lp_bytes: String,
+        /// Address of the SP
+        #[arg(long = "psk-sp")]
         psk_sp_bytes: String,
-        challenge_bytes: String,
+        /// URL of the SP server
+        #[arg(long)]
+        sp_address: String,
     },
     /// Request Service (User)
-    RequestService { session_cookie: String },
+    RequestService {
+        /// Session cookie obtained when using the license
+        #[arg(long)]
+        session_cookie: String,
+        /// Address of the LP
+        #[arg(long = "psk-lp")]
+        psk_lp_bytes: String,
+        /// URL of the SP server
+        #[arg(long)]
+        sp_address: String,
+    },
     /// Show state
     ShowState,
 }
 
-static LABEL: &[u8] = b"dusk-network";
-
 impl Command {
     #[allow(clippy::too_many_arguments)]
     pub async fn run(
@@ -57,8 +82,11 @@ impl Command {
         blockchain_access_config: &BlockchainAccessConfig,
         gas_limit: u64,
         gas_price: u64,
+        estimate_gas: bool,
+        await_config: &AwaitConfig,
         ssk: SecretSpendKey,
-        setup_holder: &mut Option<SetupHolder>,
+        key_store: &mut CircuitKeyStore,
+        license_cache: &mut LicenseCache,
     ) -> Result<RunResult, Error> {
         let run_result = match self {
             Command::SubmitRequest { psk_lp_bytes } => {
@@ -68,19 +96,36 @@ impl Command {
                     blockchain_access_config,
                     gas_limit,
                     gas_price,
+                    estimate_gas,
+                    await_config,
                     ssk,
                     psk_lp_bytes,
                 )
                 .await?
             }
+            Command::RequestStatus { request_hash } => {
+                Self::request_status(
+                    wallet_path,
+                    blockchain_access_config,
+                    ssk,
+                    license_cache,
+                    request_hash,
+                )
+                .await?
+            }
             Command::ListLicenses => {
-                Self::list_licenses(blockchain_access_config, ssk).await?
+                Self::list_licenses(
+                    blockchain_access_config,
+                    ssk,
+                    license_cache,
+                )
+                .await?
             }
             Command::UseLicense {
                 license_hash,
                 psk_lp_bytes,
                 psk_sp_bytes,
-                challenge_bytes,
+                sp_address,
             } => {
                 Self::use_license(
                     wallet_path,
@@ -88,18 +133,25 @@ impl Command {
                     blockchain_access_config,
                     gas_limit,
                     gas_price,
+                    estimate_gas,
+                    await_config,
                     psk_lp_bytes,
                     psk_sp_bytes,
                     ssk,
-                    challenge_bytes,
-                    setup_holder,
+                    sp_address,
+                    key_store,
+                    license_cache,
                     license_hash,
                 )
                 .await?
             }
-            Command::RequestService { session_cookie: _ } => {
-                println!("Off-chain request service to be placed here");
-                RunResult::Empty
+            Command::RequestService {
+                session_cookie,
+                psk_lp_bytes,
+                sp_address,
+            } => {
+                Self::request_service(session_cookie, psk_lp_bytes, sp_address)
+                    .await?
             }
             Command::ShowState => {
                 Self::show_state(blockchain_access_config).await?
@@ -110,12 +162,15 @@ impl Command {
 
     /// Command: Submit Request
     #[allow(non_snake_case)]
+    #[allow(clippy::too_many_arguments)]
     async fn submit_request<T: AsRef<str>>(
         wallet_path: &WalletPath,
         psw: &Password,
         blockchain_access_config: &BlockchainAccessConfig,
         gas_limit: u64,
         gas_price: u64,
+        estimate_gas: bool,
+        await_config: &AwaitConfig,
         ssk: SecretSpendKey,
         psk_lp_bytes: T,
     ) -> Result<RunResult, Error> {
@@ -125,8 +180,19 @@ impl Command {
             PublicSpendKey::from_slice(psk_lp_bytes_formatted.as_slice())?;
 
         let rng = &mut StdRng::from_entropy();
-        let request = RequestCreator::create(&ssk, &psk_lp, rng)?;
-        let request_hash = MoatCoreUtils::to_hash_hex(&request);
+        let (request, lsa) =
+            RequestCreator::create_with_lsa(&ssk, &psk_lp, rng)?;
+        let request_blob = MoatCoreUtils::to_blob(&request);
+        let client =
+            RuskHttpClient::new(blockchain_access_config.rusk_address.clone());
+        let gas_limit = GasEstimator::new()
+            .gas_limit_for(
+                &client,
+                REQUEST_LICENSE_METHOD_NAME,
+                gas_limit,
+                estimate_gas,
+            )
+            .await?;
         let tx_id = RequestSender::send_request(
             request,
             blockchain_access_config,
@@ -136,41 +202,89 @@ impl Command {
             gas_price,
         )
         .await?;
-        let client =
-            RuskHttpClient::new(blockchain_access_config.rusk_address.clone());
-        TxAwaiter::wait_for(&client, tx_id).await?;
+        let (block_height, gas_spent) =
+            TxAwaiter::wait_for_outcome(&client, tx_id, await_config)
+                .await?
+                .into_result()?;
+        let receipt = RequestReceipt::new(
+            &tx_id,
+            block_height,
+            gas_spent,
+            request_blob.as_slice(),
+        )
+        .with_lsa(&lsa);
+        // request is already on chain, failing to record it locally only
+        // affects request status queries
+        if let Err(e) = RequestHistory::load_from_wallet_dir(wallet_path)
+            .and_then(|mut history| history.insert(receipt.clone()))
+        {
+            eprintln!(
+                "warning: recording request in request history failed: {}",
+                e
+            );
+        }
 
         let summary = SubmitRequestSummary {
             psk_lp: psk_lp_bytes.as_ref().to_string(),
-            tx_id: hex::encode(tx_id.to_bytes()),
-            request_hash,
+            tx_id: receipt.tx_id,
+            request_hash: receipt.request_hash,
+            gas_spent,
         };
         Ok(RunResult::SubmitRequest(summary))
     }
 
+    /// Command: Request Status
+    async fn request_status(
+        wallet_path: &WalletPath,
+        blockchain_access_config: &BlockchainAccessConfig,
+        ssk: SecretSpendKey,
+        license_cache: &mut LicenseCache,
+        request_hash: String,
+    ) -> Result<RunResult, Error> {
+        let client =
+            RuskHttpClient::new(blockchain_access_config.rusk_address.clone());
+        license_cache.sync(&client).await?;
+
+        let request_history =
+            RequestHistory::load_from_wallet_dir(wallet_path)?;
+        let receipts = if request_hash.is_empty() {
+            request_history.owned_receipts(&ssk.view_key())
+        } else {
+            request_history.get(&request_hash).into_iter().collect()
+        };
+        let mut statuses = vec![];
+        for receipt in receipts {
+            statuses.push(
+                RequestStatus::query(&client, receipt, license_cache).await?,
+            );
+        }
+        Ok(RunResult::RequestStatus(statuses))
+    }
+
     /// Command: List Licenses
     async fn list_licenses(
         blockchain_access_config: &BlockchainAccessConfig,
         ssk: SecretSpendKey,
+        license_cache: &mut LicenseCache,
     ) -> Result<RunResult, Error> {
         let client =
             RuskHttpClient::new(blockchain_access_config.rusk_address.clone());
-        let end_height = BcInquirer::block_height(&client).await?;
-        let block_range = 0..(end_height + 1);
-
-        let mut licenses_stream =
-            CitadelInquirer::get_licenses(&client, block_range.clone()).await?;
+        license_cache.sync(&client).await?;
 
         let ssk_user = ssk;
 
-        let pairs = CitadelInquirer::find_all_licenses(&mut licenses_stream)?;
         let vk = ssk_user.view_key();
         let mut licenses = vec![];
-        for (_pos, license) in pairs.into_iter() {
-            let is_owned = vk.owns(&license.lsa);
-            licenses.push((license, is_owned));
+        for (_pos, license) in license_cache.licenses() {
+            licenses.push(LicenseSummary {
+                license_hash: MoatCoreUtils::to_hash_hex(license),
+                is_owned: vk.owns(&license.lsa),
+            });
         }
-        Ok(RunResult::ListLicenses(block_range, licenses))
+        Ok(RunResult::ListLicenses(ListLicensesSummary {
+            block_range: license_cache.synced_range(),
+            licenses,
+        }))
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -182,28 +296,31 @@ impl Command {
         blockchain_access_config: &BlockchainAccessConfig,
         gas_limit: u64,
         gas_price: u64,
+        estimate_gas: bool,
+        await_config: &AwaitConfig,
         psk_lp_bytes: String,
         psk_sp_bytes: String,
         ssk: SecretSpendKey,
-        challenge_bytes: String,
-        setup_holder: &mut Option<SetupHolder>,
+        sp_address: String,
+        key_store: &mut CircuitKeyStore,
+        license_cache: &mut LicenseCache,
         license_hash: String,
     ) -> Result<RunResult, Error> {
-        let pos_license = Self::get_license_to_use(
-            blockchain_access_config,
-            ssk,
+        let client =
+            RuskHttpClient::new(blockchain_access_config.rusk_address.clone());
+        let pos_license = MoatCoreUtils::get_license_to_use(
+            &client,
+            license_cache,
+            &ssk,
             license_hash.clone(),
         )
         .await?;
         Ok(match pos_license {
             Some((pos, license)) => {
-                println!(
+                eprintln!(
                     "using license: {}",
                     MoatCoreUtils::to_hash_hex(&license)
                 );
-                let challenge =
-                    JubJubScalar::from(challenge_bytes.parse::<u64>()?);
-
                 let psk_lp_bytes = bs58::decode(&psk_lp_bytes).into_vec()?;
                 let psk_lp =
                     PublicSpendKey::from_slice(psk_lp_bytes.as_slice())?;
@@ -212,29 +329,46 @@ impl Command {
                 let psk_sp =
                     PublicSpendKey::from_slice(psk_sp_bytes.as_slice())?;
 
-                let (tx_id, session_cookie) = Self::prove_and_send_use_license(
+                let challenge =
+                    MoatCore::get_challenge(&sp_address, &psk_sp).await?;
+
+                let gas_limit = GasEstimator::new()
+                    .gas_limit_for(
+                        &client,
+                        USE_LICENSE_METHOD_NAME,
+                        gas_limit,
+                        estimate_gas,
+                    )
+                    .await?;
+                eprintln!(
+                    "calculating proof and calling license contract's use_license"
+                );
+                let receipt = MoatCoreUtils::prove_and_send_use_license(
+                    &client,
                     blockchain_access_config,
                     wallet_path,
                     psw,
-                    psk_lp,
-                    psk_sp,
-                    ssk,
-                    challenge,
+                    &psk_lp,
+                    &psk_sp,
+                    &ssk,
+                    &challenge,
                     &license,
                     pos,
                     gas_limit,
                     gas_price,
-                    setup_holder,
+                    await_config,
+                    key_store,
+                    &mut OsRng,
                 )
                 .await?;
+                let session_cookie = receipt.session_cookie()?;
                 let summary = UseLicenseSummary {
-                    license_blob: MoatCoreUtils::to_blob(&license),
-                    tx_id: hex::encode(tx_id.to_bytes()),
+                    license_hash: receipt.license_hash,
+                    tx_id: receipt.tx_id,
+                    gas_spent: receipt.gas_spent,
                     user_attr: hex::encode(session_cookie.attr_data.to_bytes()),
-                    session_id: hex::encode(
-                        session_cookie.session_id.to_bytes(),
-                    ),
-                    session_cookie: MoatCoreUtils::to_blob_hex(&session_cookie),
+                    session_id: receipt.session_id,
+                    session_cookie: receipt.session_cookie,
                 };
                 RunResult::UseLicense(Some(summary))
             }
@@ -242,6 +376,26 @@ impl Command {
         })
     }
 
+    /// Command: Request Service
+    async fn request_service(
+        session_cookie: String,
+        psk_lp_bytes: String,
+        sp_address: String,
+    ) -> Result<RunResult, Error> {
+        let bytes = hex::decode(session_cookie)
+            .map_err(|_| Error::InvalidEntry("session cookie".into()))?;
+        let session_cookie: SessionCookie = rkyv::from_bytes(bytes.as_slice())
+            .map_err(|_| Error::InvalidEntry("session cookie".into()))?;
+
+        let psk_lp_bytes = bs58::decode(&psk_lp_bytes).into_vec()?;
+        let psk_lp = PublicSpendKey::from_slice(psk_lp_bytes.as_slice())?;
+
+        let service_response =
+            MoatCore::request_service(&sp_address, &psk_lp, &session_cookie)
+                .await?;
+        Ok(RunResult::RequestService(service_response))
+    }
+
     /// Command: Show State
     async fn show_state(
         blockchain_access_config: &BlockchainAccessConfig,
@@ -256,128 +410,4 @@ impl Command {
         };
         Ok(RunResult::ShowState(summary))
     }
-
-    async fn get_license_to_use(
-        blockchain_access_config: &BlockchainAccessConfig,
-        ssk: SecretSpendKey,
-        license_hash: String,
-    ) -> Result<Option<(u64, License)>, Error> {
-        let client =
-            RuskHttpClient::new(blockchain_access_config.rusk_address.clone());
-        let end_height = BcInquirer::block_height(&client).await?;
-        let block_heights = 0..(end_height + 1);
-
-        let mut licenses_stream =
-            CitadelInquirer::get_licenses(&client, block_heights).await?;
-
-        let pairs =
-            CitadelInquirer::find_owned_licenses(ssk, &mut licenses_stream)?;
-        Ok(if pairs.is_empty() {
-            None
-        } else {
-            for (pos, license) in pairs.iter() {
-                if license_hash == MoatCoreUtils::to_hash_hex(license) {
-                    return Ok(Some((*pos, license.clone())));
-                }
-            }
-            None
-        })
-    }
-
-    #[allow(clippy::too_many_arguments)]
-    async fn prove_and_send_use_license(
-        blockchain_access_config: &BlockchainAccessConfig,
-        wallet_path: &WalletPath,
-        psw: &Password,
-        psk_lp: PublicSpendKey,
-        psk_sp: PublicSpendKey,
-        ssk_user: SecretSpendKey,
-        challenge: JubJubScalar,
-        license: &License,
-        pos: u64,
-        gas_limit: u64,
-        gas_price: u64,
-        sh_opt: &mut Option<SetupHolder>,
-    ) -> Result<(BlsScalar, SessionCookie), Error> {
-        let client =
-            RuskHttpClient::new(blockchain_access_config.rusk_address.clone());
-        // let (_, _, num_sessions) = CitadelInquirer::get_info(&client).await?;
-        // let challenge = JubJubScalar::from(num_sessions as u64 + 1);
-
-        let setup_holder = match sh_opt {
-            Some(sh) => sh,
-            _ => {
-                let wallet_dir_path = match wallet_path.dir() {
-                    Some(path) => path,
-                    None => panic!(),
-                };
-
-                let prover_path = &wallet_dir_path.join("moat_prover.dat");
-                let verifier_path = &wallet_dir_path.join("moat_verifier.dat");
-
-                if prover_path.exists() && verifier_path.exists() {
-                    let mut file = File::open(prover_path)?;
-                    let mut prover_bytes = vec![];
-                    file.read_to_end(&mut prover_bytes)?;
-                    let prover = Prover::try_from_bytes(prover_bytes)?;
-
-                    file = File::open(verifier_path)?;
-                    let mut verifier_bytes = vec![];
-                    file.read_to_end(&mut verifier_bytes)?;
-                    let verifier = Verifier::try_from_bytes(verifier_bytes)?;
-
-                    let sh = SetupHolder { prover, verifier };
-                    *sh_opt = Some(sh);
-                    sh_opt.as_ref().expect("setup holder is not empty")
-                } else {
-                    println!("obtaining setup");
-                    let pp_vec = CrsGetter::get_crs(&client).await?;
-                    let pp =
-                        // SAFETY: CRS vector is checked by the hash check when it is received from the node
-                        unsafe { PublicParameters::from_slice_unchecked(pp_vec.as_slice()) };
-                    println!("compiling circuit");
-                    let (prover, verifier) =
-                        Compiler::compile::<LicenseCircuit>(&pp, LABEL)
-                            .expect("Compiling circuit should succeed");
-
-                    let mut file = File::create(prover_path)?;
-                    file.write_all(prover.to_bytes().as_slice())?;
-
-                    file = File::create(verifier_path)?;
-                    file.write_all(verifier.to_bytes().as_slice())?;
-
-                    let sh = SetupHolder { prover, verifier };
-                    *sh_opt = Some(sh);
-                    sh_opt.as_ref().expect("setup holder is not empty")
-                }
-            }
-        };
-
-        let opening = CitadelInquirer::get_merkle_opening(&client, pos)
-            .await?
-            .expect("Opening obtained successfully");
-
-        println!(
-            "calculating proof and calling license contract's use_license"
-        );
-        let (tx_id, session_cookie) = LicenseUser::prove_and_use_license(
-            blockchain_access_config,
-            wallet_path,
-            psw,
-            &ssk_user,
-            &psk_lp,
-            &psk_sp,
-            &setup_holder.prover,
-            &setup_holder.verifier,
-            license,
-            opening,
-            &mut OsRng,
-            &challenge,
-            gas_limit,
-            gas_price,
-        )
-        .await?;
-        TxAwaiter::wait_for(&client, tx_id).await?;
-        Ok((tx_id, session_cookie))
-    }
 }
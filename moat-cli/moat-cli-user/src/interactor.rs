@@ -5,13 +5,14 @@
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
 use crate::prompt;
+use crate::run_result::RunResult;
 use crate::{Command, Menu};
 use dusk_pki::SecretSpendKey;
-use dusk_plonk::prelude::{Prover, Verifier};
 use dusk_wallet::WalletPath;
-use moat_cli_common::Error;
+use moat_cli_common::{Error, OutputFormat};
 use requestty::{ErrorKind, Question};
 use zk_citadel_moat::wallet_accessor::{BlockchainAccessConfig, Password};
+use zk_citadel_moat::{AwaitConfig, CircuitKeyStore, LicenseCache};
 
 #[derive(PartialEq, Eq, Hash, Debug, Clone)]
 enum OpSelection {
@@ -22,6 +23,7 @@ enum OpSelection {
 #[derive(PartialEq, Eq, Hash, Clone, Debug)]
 enum CommandMenuItem {
     SubmitRequest,
+    RequestStatus,
     ListLicenses,
     UseLicense,
     RequestService,
@@ -32,6 +34,7 @@ enum CommandMenuItem {
 fn menu_operation() -> Result<OpSelection, ErrorKind> {
     let cmd_menu = Menu::new()
         .add(CommandMenuItem::SubmitRequest, "Submit Request")
+        .add(CommandMenuItem::RequestStatus, "Request Status")
         .add(CommandMenuItem::ListLicenses, "List Licenses")
         .add(CommandMenuItem::UseLicense, "Use License")
         .add(
@@ -55,6 +58,11 @@ fn menu_operation() -> Result<OpSelection, ErrorKind> {
                 psk_lp_bytes: prompt::request_psk_lp_bytes()?,
             }))
         }
+        CommandMenuItem::RequestStatus => {
+            OpSelection::Run(Box::from(Command::RequestStatus {
+                request_hash: prompt::request_request_hash()?,
+            }))
+        }
         CommandMenuItem::ListLicenses => {
             OpSelection::Run(Box::from(Command::ListLicenses))
         }
@@ -63,12 +71,14 @@ fn menu_operation() -> Result<OpSelection, ErrorKind> {
                 license_hash: prompt::request_license_hash()?,
                 psk_lp_bytes: prompt::request_psk_lp_bytes()?,
                 psk_sp_bytes: prompt::request_psk_sp_bytes()?,
-                challenge_bytes: prompt::request_challenge_bytes()?,
+                sp_address: prompt::request_sp_address()?,
             }))
         }
         CommandMenuItem::RequestService => {
             OpSelection::Run(Box::from(Command::RequestService {
                 session_cookie: prompt::request_session_cookie()?,
+                psk_lp_bytes: prompt::request_psk_lp_bytes()?,
+                sp_address: prompt::request_sp_address()?,
             }))
         }
         CommandMenuItem::ShowState => {
@@ -78,19 +88,18 @@ fn menu_operation() -> Result<OpSelection, ErrorKind> {
     })
 }
 
-pub struct SetupHolder {
-    pub prover: Prover,
-    pub verifier: Verifier,
-}
-
 pub struct Interactor {
     pub wallet_path: WalletPath,
     pub psw: Password,
     pub blockchain_access_config: BlockchainAccessConfig,
     pub gas_limit: u64,
     pub gas_price: u64,
+    pub estimate_gas: bool,
+    pub await_config: AwaitConfig,
     pub ssk: SecretSpendKey,
-    pub setup_holder: Option<SetupHolder>,
+    pub key_store: CircuitKeyStore,
+    pub license_cache: LicenseCache,
+    pub output: OutputFormat,
 }
 
 impl Interactor {
@@ -100,20 +109,13 @@ impl Interactor {
             match op {
                 OpSelection::Exit => return Ok(()),
                 OpSelection::Run(command) => {
-                    let result = command
-                        .run(
-                            &self.wallet_path,
-                            &self.psw,
-                            &self.blockchain_access_config,
-                            self.gas_limit,
-                            self.gas_price,
-                            self.ssk,
-                            &mut self.setup_holder,
-                        )
-                        .await;
+                    let result = self
+                        .run_command(*command)
+                        .await
+                        .and_then(|run_result| self.output.render(&run_result));
                     match result {
-                        Ok(run_result) => {
-                            println!("{}", run_result);
+                        Ok(output) => {
+                            println!("{}", output);
                         }
                         Err(error) => {
                             println!("{}", error);
@@ -124,4 +126,25 @@ impl Interactor {
             }
         }
     }
+
+    /// Runs a single command without prompting
+    pub async fn run_command(
+        &mut self,
+        command: Command,
+    ) -> Result<RunResult, Error> {
+        command
+            .run(
+                &self.wallet_path,
+                &self.psw,
+                &self.blockchain_access_config,
+                self.gas_limit,
+                self.gas_price,
+                self.estimate_gas,
+                &self.await_config,
+                self.ssk,
+                &mut self.key_store,
+                &mut self.license_cache,
+            )
+            .await
+    }
 }
@@ -13,22 +13,21 @@ mod menu;
 mod prompt;
 mod run_result;
 
-use crate::args::Args;
+use crate::args::{Args, CliCommand};
 use crate::command::Command;
 use crate::menu::Menu;
-use std::fs;
+use std::{fs, process};
 
 use clap::Parser;
 
 use crate::interactor::Interactor;
-use dusk_wallet::{Wallet, WalletPath};
+use dusk_wallet::WalletPath;
 use moat_cli_common::Error;
 use rand::SeedableRng;
 use toml_base_config::BaseConfig;
+use zk_citadel_moat::wallet_accessor::BlockchainAccessConfig;
 use zk_citadel_moat::wallet_accessor::Password::{Pwd, PwdHash};
-use zk_citadel_moat::wallet_accessor::{
-    BlockchainAccessConfig, WalletAccessor,
-};
+use zk_citadel_moat::{CircuitKeyStore, LicenseCache};
 
 #[tokio::main]
 #[allow(non_snake_case)]
@@ -41,24 +40,37 @@ async fn main() -> Result<(), Error> {
     let pwd_hash = cli.pwd_hash;
     let gas_limit = cli.gas_limit;
     let gas_price = cli.gas_price;
+    let output = cli.output;
+    let estimate_gas = cli.estimate_gas;
+    let await_config = cli.await_args.await_config();
 
     let wallet_path = WalletPath::from(wallet_path.join("wallet.dat"));
-    let _ = fs::metadata(config_path).map_err(|_| {
-        Error::NotFound(config_path.to_string_lossy().into_owned().into())
-    })?;
-    let blockchain_access_config =
-        BlockchainAccessConfig::load_path(config_path)?;
     let psw = if pwd_hash.is_empty() {
         Pwd(password)
     } else {
         PwdHash(pwd_hash)
     };
 
-    let wallet_accessor =
-        WalletAccessor::create(wallet_path.clone(), psw.clone())?;
-    let wallet = Wallet::from_file(wallet_accessor)?;
+    let command = match cli.command {
+        Some(CliCommand::Keys(keys_command)) => {
+            println!("{}", keys_command.run(&cli.keys, &wallet_path, &psw)?);
+            return Ok(());
+        }
+        Some(CliCommand::Moat(command)) => Some(command),
+        None => None,
+    };
+
+    let _ = fs::metadata(config_path).map_err(|_| {
+        Error::NotFound(config_path.to_string_lossy().into_owned().into())
+    })?;
+    let blockchain_access_config =
+        BlockchainAccessConfig::load_path(config_path)?;
+
+    let ssk = cli.keys.load_keys(&wallet_path, &psw)?.ssk;
 
-    let (_psk, ssk) = wallet.spending_keys(wallet.default_address())?;
+    let license_cache = LicenseCache::load_from_wallet_dir(&wallet_path)?;
+    let key_store = CircuitKeyStore::from_wallet_dir(&wallet_path)
+        .with_config(&blockchain_access_config)?;
 
     let mut interactor = Interactor {
         wallet_path,
@@ -66,11 +78,24 @@ async fn main() -> Result<(), Error> {
         blockchain_access_config,
         gas_limit,
         gas_price,
+        estimate_gas,
+        await_config,
         ssk,
-        setup_holder: None,
+        key_store,
+        license_cache,
+        output,
     };
 
-    interactor.run_loop().await?;
+    match command {
+        Some(command) => {
+            let run_result = interactor.run_command(command).await?;
+            println!("{}", output.render(&run_result)?);
+            if !run_result.is_success() {
+                process::exit(1);
+            }
+        }
+        None => interactor.run_loop().await?,
+    }
 
     Ok(())
 }
@@ -24,6 +24,19 @@ pub(crate) fn request_session_cookie() -> Result<String, ErrorKind> {
     Ok(a_str)
 }
 
+pub(crate) fn request_request_hash() -> Result<String, ErrorKind> {
+    let q = Question::input("request_hash")
+        .message(
+            "Please enter request hash (leave empty for all requests):"
+                .to_string(),
+        )
+        .build();
+
+    let a = requestty::prompt_one(q)?;
+    let a_str = a.as_string().expect("answer to be a string").to_string();
+    Ok(a_str)
+}
+
 pub(crate) fn request_license_hash() -> Result<String, ErrorKind> {
     let q = Question::input("license_hash")
         .message("Please enter license hash:".to_string())
@@ -78,13 +91,13 @@ pub(crate) fn request_psk_sp_bytes() -> Result<String, ErrorKind> {
     Ok(a_str)
 }
 
-pub(crate) fn request_challenge_bytes() -> Result<String, ErrorKind> {
-    let q = Question::input("challenge_bytes")
-        .message("Please enter the challenge:".to_string())
+pub(crate) fn request_sp_address() -> Result<String, ErrorKind> {
+    let q = Question::input("sp_address")
+        .message("Please enter the URL of the SP server:".to_string())
         .validate_on_key(|_, _| true)
-        .validate(|challenge_bytes, _| {
-            if challenge_bytes.is_empty() {
-                Err("Please enter a valid challenge".to_string())
+        .validate(|sp_address, _| {
+            if sp_address.is_empty() {
+                Err("Please enter a valid SP server URL".to_string())
             } else {
                 Ok(())
             }
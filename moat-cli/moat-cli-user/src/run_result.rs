@@ -4,38 +4,67 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+use serde::Serialize;
 use std::fmt;
 use std::ops::Range;
-use zk_citadel::license::License;
-use zk_citadel_moat::MoatCoreUtils;
+use zk_citadel_moat::{RequestStatus, ServiceResponse};
 
+#[derive(Serialize)]
 pub struct SubmitRequestSummary {
     pub psk_lp: String,
     pub tx_id: String,
     pub request_hash: String,
+    pub gas_spent: u64,
 }
 
+#[derive(Serialize)]
+pub struct LicenseSummary {
+    pub license_hash: String,
+    pub is_owned: bool,
+}
+
+#[derive(Serialize)]
+pub struct ListLicensesSummary {
+    pub block_range: Range<u64>,
+    pub licenses: Vec<LicenseSummary>,
+}
+
+#[derive(Serialize)]
 pub struct UseLicenseSummary {
-    pub license_blob: Vec<u8>,
+    pub license_hash: String,
     pub tx_id: String,
+    pub gas_spent: u64,
     pub session_cookie: String,
     pub user_attr: String,
     pub session_id: String,
 }
 
+#[derive(Serialize)]
 pub struct LicenseContractSummary {
     pub num_licenses: u32,
     pub num_sessions: u32,
 }
 
 #[allow(clippy::large_enum_variant)]
-/// Possible results of running a command in interactive mode
+/// Possible results of running a command in interactive mode, serialized
+/// as the contained summary in JSON output
+#[derive(Serialize)]
+#[serde(untagged)]
 pub enum RunResult {
     SubmitRequest(SubmitRequestSummary),
-    ListLicenses(Range<u64>, Vec<(License, bool)>),
+    RequestStatus(Vec<RequestStatus>),
+    ListLicenses(ListLicensesSummary),
     UseLicense(Option<UseLicenseSummary>),
+    RequestService(ServiceResponse),
     ShowState(LicenseContractSummary),
-    Empty,
+}
+
+impl RunResult {
+    /// Returns false if the command could not be carried out, e.g. when the
+    /// license to use was not found.
+    pub fn is_success(&self) -> bool {
+        !matches!(self, RunResult::UseLicense(None))
+    }
 }
 
 impl fmt::Display for RunResult {
@@ -53,24 +82,54 @@ impl fmt::Display for RunResult {
                     "request submitting transaction {} confirmed",
                     summary.tx_id
                 )?;
+                writeln!(f, "gas spent: {}", summary.gas_spent)?;
                 writeln!(f, "request submitted: {}", summary.request_hash)?;
                 Ok(())
             }
-            ListLicenses(block_range, licenses) => {
+            RequestStatus(statuses) => {
+                if statuses.is_empty() {
+                    writeln!(f, "requests not found")?;
+                }
+                for status in statuses.iter() {
+                    writeln!(f, "request: {}", status.request_hash)?;
+                    match status.block_height {
+                        Some(block_height) => writeln!(
+                            f,
+                            "  transaction {} included at height {}",
+                            status.tx_id, block_height
+                        )?,
+                        None => writeln!(
+                            f,
+                            "  transaction {} not found on-chain",
+                            status.tx_id
+                        )?,
+                    }
+                    match (&status.license_hash, status.license_pos) {
+                        (Some(license_hash), Some(pos)) => writeln!(
+                            f,
+                            "  license issued: {} at position {}",
+                            license_hash, pos
+                        )?,
+                        _ => writeln!(f, "  license not issued yet")?,
+                    }
+                }
+                Ok(())
+            }
+            ListLicenses(summary) => {
                 writeln!(
                     f,
                     "getting licenses within the block height range {:?}:",
-                    block_range
+                    summary.block_range
                 )?;
-                if licenses.is_empty() {
+                if summary.licenses.is_empty() {
                     writeln!(f, "licenses not found")?;
                 } else {
-                    for (license, is_owned) in licenses.iter() {
+                    for license in summary.licenses.iter() {
                         writeln!(
                             f,
                             "license: {} {}",
-                            MoatCoreUtils::to_hash_hex(license),
-                            if *is_owned { "owned" } else { "" }
+                            license.license_hash,
+                            if license.is_owned { "owned" } else { "" }
                         )?;
                     }
                 }
@@ -79,26 +138,15 @@ impl fmt::Display for RunResult {
             UseLicense(summary) => {
                 match summary {
                     Some(summary) => {
-                        writeln!(
-                            f,
-                            "using license: {}",
-                            MoatCoreUtils::blob_to_hash_hex(
-                                summary.license_blob.as_slice()
-                            )
-                        )?;
+                        writeln!(f, "using license: {}", summary.license_hash)?;
                         writeln!(
                             f,
                             "use license executing transaction {} confirmed",
                             summary.tx_id
                         )?;
+                        writeln!(f, "gas spent: {}", summary.gas_spent)?;
                         writeln!(f)?;
-                        writeln!(
-                            f,
-                            "license {} used",
-                            MoatCoreUtils::blob_to_hash_hex(
-                                summary.license_blob.as_slice()
-                            ),
-                        )?;
+                        writeln!(f, "license {} used", summary.license_hash,)?;
                         writeln!(f)?;
                         writeln!(
                             f,
@@ -115,6 +163,18 @@ impl fmt::Display for RunResult {
                 }
                 Ok(())
             }
+            RequestService(service_response) => {
+                if service_response.granted {
+                    writeln!(f, "service granted")?;
+                } else {
+                    writeln!(
+                        f,
+                        "service denied: {}",
+                        service_response.reason.as_deref().unwrap_or("")
+                    )?;
+                }
+                Ok(())
+            }
             ShowState(summary) => {
                 writeln!(
                     f,
@@ -123,7 +183,6 @@ impl fmt::Display for RunResult {
                 )?;
                 Ok(())
             }
-            Empty => Ok(()),
         }
     }
 }
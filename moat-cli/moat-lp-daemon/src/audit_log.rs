@@ -0,0 +1,129 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use dusk_bytes::Serializable;
+use dusk_jubjub::JubJubScalar;
+use moat_cli_common::{append_json_line, Error};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use zk_citadel_moat::license_provider::LicenseSubmission;
+use zk_citadel_moat::LicenseReceipt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditDecision {
+    Approved,
+    Rejected,
+    Failed,
+    /// License submitted, but its transaction has not been confirmed yet.
+    Pending,
+}
+
+/// Single line of the audit log.
+/// Attribute data, transaction id and license hash are hex encoded.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AuditEntry {
+    pub timestamp: u64,
+    pub request_hash: String,
+    pub decision: AuditDecision,
+    pub attr_data: Option<String>,
+    pub reason: Option<String>,
+    pub tx_id: Option<String>,
+    pub license_hash: Option<String>,
+    pub block_height: Option<u64>,
+    pub gas_spent: Option<u64>,
+}
+
+impl AuditEntry {
+    pub fn approved(attr_data: &JubJubScalar, receipt: LicenseReceipt) -> Self {
+        Self {
+            attr_data: Some(hex::encode(attr_data.to_bytes())),
+            ..Self::issued(receipt)
+        }
+    }
+
+    /// Entry of a license whose attribute data are no longer known, as its
+    /// transaction has been confirmed after the poll which submitted it.
+    pub fn issued(receipt: LicenseReceipt) -> Self {
+        Self {
+            tx_id: Some(receipt.tx_id),
+            license_hash: Some(receipt.license_hash),
+            block_height: Some(receipt.block_height),
+            gas_spent: Some(receipt.gas_spent),
+            ..Self::new(receipt.request_hash, AuditDecision::Approved)
+        }
+    }
+
+    pub fn rejected(request_hash: String, reason: String) -> Self {
+        Self {
+            reason: Some(reason),
+            ..Self::new(request_hash, AuditDecision::Rejected)
+        }
+    }
+
+    pub fn failed(
+        request_hash: String,
+        attr_data: Option<&JubJubScalar>,
+        reason: String,
+    ) -> Self {
+        Self {
+            attr_data: attr_data.map(|a| hex::encode(a.to_bytes())),
+            reason: Some(reason),
+            ..Self::new(request_hash, AuditDecision::Failed)
+        }
+    }
+
+    pub fn pending(
+        request_hash: String,
+        attr_data: &JubJubScalar,
+        submission: &LicenseSubmission,
+        reason: String,
+    ) -> Self {
+        Self {
+            attr_data: Some(hex::encode(attr_data.to_bytes())),
+            reason: Some(reason),
+            tx_id: Some(hex::encode(submission.tx_id.to_bytes())),
+            ..Self::new(request_hash, AuditDecision::Pending)
+        }
+    }
+
+    fn new(request_hash: String, decision: AuditDecision) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        Self {
+            timestamp,
+            request_hash,
+            decision,
+            attr_data: None,
+            reason: None,
+            tx_id: None,
+            license_hash: None,
+            block_height: None,
+            gas_spent: None,
+        }
+    }
+}
+
+/// Append-only log of the daemon's decisions, one JSON entry per line.
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Appends entry and flushes it to disk.
+    pub fn append(&self, entry: &AuditEntry) -> Result<(), Error> {
+        append_json_line(&self.path, entry)
+    }
+}
@@ -0,0 +1,108 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::policy::PolicyConfig;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+use toml_base_config::BaseConfig;
+use zk_citadel_moat::wallet_accessor::BlockchainAccessConfig;
+use zk_citadel_moat::{
+    AwaitConfig, GasEstimator, DEFAULT_GAS_ESTIMATE_TTL, GAS_ESTIMATION_BLOCKS,
+};
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LPDaemonConfig {
+    pub rusk_address: String,
+    pub prover_address: String,
+    #[serde(default = "default_gas_limit")]
+    pub gas_limit: u64,
+    #[serde(default = "default_gas_price")]
+    pub gas_price: u64,
+    #[serde(default)]
+    pub estimate_gas: bool,
+    #[serde(default = "default_gas_estimation_blocks")]
+    pub gas_estimation_blocks: usize,
+    #[serde(default = "default_gas_estimate_ttl_secs")]
+    pub gas_estimate_ttl_secs: u64,
+    #[serde(default = "default_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    #[serde(default = "default_checkpoint_path")]
+    pub checkpoint_path: PathBuf,
+    #[serde(default = "default_audit_log_path")]
+    pub audit_log_path: PathBuf,
+    #[serde(default)]
+    pub notices_path: Option<PathBuf>,
+    #[serde(default = "default_tx_timeout_secs")]
+    pub tx_timeout_secs: u64,
+    #[serde(default)]
+    pub confirmations: u64,
+    pub policy: PolicyConfig,
+}
+
+impl LPDaemonConfig {
+    pub fn blockchain_access_config(&self) -> BlockchainAccessConfig {
+        BlockchainAccessConfig {
+            rusk_address: self.rusk_address.clone(),
+            prover_address: self.prover_address.clone(),
+            ..Default::default()
+        }
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_secs(self.poll_interval_secs)
+    }
+
+    pub fn await_config(&self) -> AwaitConfig {
+        AwaitConfig {
+            timeout: Duration::from_secs(self.tx_timeout_secs),
+            confirmations: self.confirmations,
+            ..AwaitConfig::default()
+        }
+    }
+
+    pub fn gas_estimator(&self) -> GasEstimator {
+        GasEstimator::new()
+            .with_blocks(self.gas_estimation_blocks)
+            .with_ttl(Duration::from_secs(self.gas_estimate_ttl_secs))
+    }
+}
+
+impl BaseConfig for LPDaemonConfig {
+    const PACKAGE: &'static str = env!("CARGO_PKG_NAME");
+}
+
+fn default_gas_limit() -> u64 {
+    500000000
+}
+
+fn default_gas_price() -> u64 {
+    1
+}
+
+fn default_gas_estimation_blocks() -> usize {
+    GAS_ESTIMATION_BLOCKS
+}
+
+fn default_gas_estimate_ttl_secs() -> u64 {
+    DEFAULT_GAS_ESTIMATE_TTL.as_secs()
+}
+
+fn default_poll_interval_secs() -> u64 {
+    10
+}
+
+fn default_tx_timeout_secs() -> u64 {
+    120
+}
+
+fn default_checkpoint_path() -> PathBuf {
+    PathBuf::from("moat_lp_checkpoint.json")
+}
+
+fn default_audit_log_path() -> PathBuf {
+    PathBuf::from("moat_lp_audit.log")
+}
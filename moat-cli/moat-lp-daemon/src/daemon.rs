@@ -0,0 +1,275 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::audit_log::{AuditEntry, AuditLog};
+use dusk_jubjub::JubJubScalar;
+use dusk_wallet::RuskHttpClient;
+use futures::future::join_all;
+use moat_cli_common::{Error, NoticeLog};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::time::Duration;
+use tracing::{info, warn};
+use zk_citadel::license::Request;
+use zk_citadel_moat::license_provider::{
+    ApprovalPolicy, Decision, LPCheckpointStore, LicenseIssuer, ReferenceLP,
+};
+use zk_citadel_moat::wallet_accessor::WalletSession;
+use zk_citadel_moat::{MoatCoreUtils, RejectionNotice, TxOutcome};
+
+/// License Provider processing requests without user interaction.
+pub struct LPDaemon {
+    pub reference_lp: ReferenceLP,
+    pub checkpoint_store: LPCheckpointStore,
+    pub license_issuer: LicenseIssuer,
+    /// Wallet session reused across issued licenses, opened on first use.
+    pub wallet_session: Option<WalletSession>,
+    pub policy: Box<dyn ApprovalPolicy>,
+    pub audit_log: AuditLog,
+    /// Rejection notices for the users, not written if not configured.
+    pub notice_log: Option<NoticeLog>,
+    pub client: RuskHttpClient,
+    pub poll_interval: Duration,
+}
+
+impl LPDaemon {
+    /// Processes requests in a loop, until interrupted.
+    /// Errors are logged and processing is retried after the poll interval.
+    pub async fn run(&mut self) -> Result<(), Error> {
+        let mut rng = StdRng::from_entropy();
+        loop {
+            if let Err(e) = self.process_new_requests(&mut rng).await {
+                warn!("processing requests failed: {}", e);
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(self.poll_interval) => {}
+                _ = tokio::signal::ctrl_c() => {
+                    info!("shutting down");
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Scans for new requests and processes all pending requests.
+    /// Request is marked as in flight in the checkpoint before it is
+    /// processed, so that no license is issued twice for the same request,
+    /// even if its block is scanned again. Requests submitted in previous
+    /// polls are settled first.
+    /// Licenses for all approved requests are issued as a batch.
+    async fn process_new_requests(
+        &mut self,
+        rng: &mut StdRng,
+    ) -> Result<(), Error> {
+        let (total, owned) = self
+            .reference_lp
+            .scan_with_checkpoint(&self.client, &self.checkpoint_store)
+            .await?;
+        if total > 0 {
+            info!("found {} requests, {} relevant", total, owned);
+        }
+        self.settle_in_flight().await?;
+        let mut approved = vec![];
+        while let Some(request) = self.reference_lp.take_request() {
+            self.checkpoint_store
+                .save(&self.reference_lp.checkpoint())?;
+            match self.policy.decide(&request) {
+                Decision::Approve { attr_data } => {
+                    approved.push((request, attr_data))
+                }
+                Decision::Reject { reason } => {
+                    self.reject_request(rng, &request, reason.into_owned())?;
+                }
+            }
+        }
+        if !approved.is_empty() {
+            self.issue_licenses(rng, &approved).await?;
+        }
+        Ok(())
+    }
+
+    /// Records rejection in the checkpoint, so that the request is not
+    /// processed again, and notifies the user if notices are configured.
+    fn reject_request(
+        &mut self,
+        rng: &mut StdRng,
+        request: &Request,
+        reason: String,
+    ) -> Result<(), Error> {
+        let request_hash = MoatCoreUtils::to_hash_hex(request);
+        info!("rejected request {}: {}", request_hash, reason);
+        self.reference_lp.mark_rejected(request, reason.clone());
+        self.checkpoint_store
+            .save(&self.reference_lp.checkpoint())?;
+        if let Some(notice_log) = &self.notice_log {
+            let notice = RejectionNotice::create(
+                rng,
+                request,
+                &self.reference_lp.ssk_lp,
+                &reason,
+            );
+            notice_log.append(&notice)?;
+        }
+        self.audit_log
+            .append(&AuditEntry::rejected(request_hash, reason))
+    }
+
+    /// Settles requests whose licenses have been submitted in previous
+    /// polls, but not confirmed in time. License is issued again only if its
+    /// transaction has failed, or has not been included at all.
+    async fn settle_in_flight(&mut self) -> Result<(), Error> {
+        let settled = self.reference_lp.settle_in_flight(&self.client).await?;
+        for (request, submission, outcome) in settled {
+            let request_hash = MoatCoreUtils::to_hash_hex(&request);
+            let entry = match outcome {
+                TxOutcome::Included { height, gas_spent } => {
+                    info!("issued license for request {}", request_hash);
+                    let receipt =
+                        submission.receipt(&request, height, gas_spent);
+                    AuditEntry::issued(receipt)
+                }
+                TxOutcome::Failed { error, .. } => {
+                    warn!(
+                        "issuing license for request {} failed: {}",
+                        request_hash, error
+                    );
+                    AuditEntry::failed(request_hash, None, error)
+                }
+                TxOutcome::Dropped => {
+                    warn!(
+                        "issue license transaction for request {} lost",
+                        request_hash
+                    );
+                    let reason = "Transaction not included".to_string();
+                    AuditEntry::failed(request_hash, None, reason)
+                }
+            };
+            self.audit_log.append(&entry)?;
+        }
+        self.checkpoint_store
+            .save(&self.reference_lp.checkpoint())?;
+        Ok(())
+    }
+
+    /// Issues licenses for approved requests within the wallet session, so
+    /// that the wallet is connected and fully synced only once.
+    /// Outcome for each request is recorded in the audit log. Submitted
+    /// transactions are recorded in the checkpoint before they are awaited.
+    /// Requests for which issuing has failed are put back to the requests to
+    /// process, so that they are retried on the next poll. Requests whose
+    /// transactions have not been confirmed in time stay in flight, as their
+    /// transactions might still be included, and are settled on the next
+    /// poll.
+    async fn issue_licenses(
+        &mut self,
+        rng: &mut StdRng,
+        approved: &[(Request, JubJubScalar)],
+    ) -> Result<(), Error> {
+        let session = match self.wallet_session.take() {
+            Some(session) => Ok(session),
+            None => self.license_issuer.open_session().await,
+        };
+        let submissions = match session {
+            Ok(mut session) => {
+                let submissions = self
+                    .license_issuer
+                    .submit_licenses_in_session(
+                        &mut session,
+                        rng,
+                        approved,
+                        &self.reference_lp.ssk_lp,
+                    )
+                    .await;
+                self.wallet_session = Some(session);
+                submissions
+            }
+            Err(e) => approved.iter().map(|_| Err(e.clone())).collect(),
+        };
+        let mut submitted = vec![];
+        for ((request, attr_data), submission) in
+            approved.iter().zip(submissions)
+        {
+            match submission {
+                Ok(submission) => {
+                    self.reference_lp
+                        .record_submission(request, submission.clone());
+                    submitted.push((request, attr_data, submission));
+                }
+                Err(e) => {
+                    let request_hash = MoatCoreUtils::to_hash_hex(request);
+                    warn!(
+                        "issuing license for request {} failed: {}",
+                        request_hash, e
+                    );
+                    self.reference_lp.return_request(request.clone());
+                    self.audit_log.append(&AuditEntry::failed(
+                        request_hash,
+                        Some(attr_data),
+                        e.to_string(),
+                    ))?;
+                }
+            }
+        }
+        self.checkpoint_store
+            .save(&self.reference_lp.checkpoint())?;
+
+        let outcomes = join_all(submitted.iter().map(|(_, _, submission)| {
+            self.license_issuer.wait_for_submission(submission)
+        }))
+        .await;
+        for ((request, attr_data, submission), outcome) in
+            submitted.into_iter().zip(outcomes)
+        {
+            let request_hash = MoatCoreUtils::to_hash_hex(request);
+            let entry = match outcome {
+                Ok(TxOutcome::Included { height, gas_spent }) => {
+                    info!("issued license for request {}", request_hash);
+                    self.reference_lp.mark_processed(request);
+                    let receipt =
+                        submission.receipt(request, height, gas_spent);
+                    AuditEntry::approved(attr_data, receipt)
+                }
+                Ok(TxOutcome::Failed { error, .. }) => {
+                    warn!(
+                        "issuing license for request {} failed: {}",
+                        request_hash, error
+                    );
+                    self.reference_lp.return_request(request.clone());
+                    AuditEntry::failed(request_hash, Some(attr_data), error)
+                }
+                Ok(TxOutcome::Dropped) => {
+                    warn!(
+                        "license for request {} not confirmed in time",
+                        request_hash
+                    );
+                    let reason = "Confirmation timed out".to_string();
+                    AuditEntry::pending(
+                        request_hash,
+                        attr_data,
+                        &submission,
+                        reason,
+                    )
+                }
+                Err(e) => {
+                    warn!(
+                        "awaiting license for request {} failed: {}",
+                        request_hash, e
+                    );
+                    AuditEntry::pending(
+                        request_hash,
+                        attr_data,
+                        &submission,
+                        e.to_string(),
+                    )
+                }
+            };
+            self.audit_log.append(&entry)?;
+        }
+        self.checkpoint_store
+            .save(&self.reference_lp.checkpoint())?;
+        Ok(())
+    }
+}
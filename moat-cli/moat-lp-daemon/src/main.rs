@@ -0,0 +1,86 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+mod args;
+mod audit_log;
+mod config;
+mod daemon;
+mod policy;
+
+use crate::args::Args;
+use crate::audit_log::AuditLog;
+use crate::config::LPDaemonConfig;
+use crate::daemon::LPDaemon;
+use std::fs;
+
+use clap::Parser;
+
+use dusk_wallet::{RuskHttpClient, WalletPath};
+use moat_cli_common::{Error, NoticeLog};
+use toml_base_config::BaseConfig;
+use zk_citadel_moat::license_provider::{
+    LPCheckpointStore, LicenseIssuer, ReferenceLP,
+};
+use zk_citadel_moat::wallet_accessor::Password::{Pwd, PwdHash};
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    tracing_subscriber::fmt::init();
+
+    let cli = Args::parse();
+
+    let config_path = cli.config_path.as_path();
+    let wallet_path = cli.wallet_path.as_path();
+    let password = cli.wallet_pass;
+    let pwd_hash = cli.pwd_hash;
+
+    let _ = fs::metadata(config_path).map_err(|_| {
+        Error::NotFound(config_path.to_string_lossy().into_owned().into())
+    })?;
+    let config = LPDaemonConfig::load_path(config_path)?;
+    let blockchain_access_config = config.blockchain_access_config();
+
+    let wallet_path = WalletPath::from(wallet_path.join("wallet.dat"));
+    let psw = if pwd_hash.is_empty() {
+        Pwd(password)
+    } else {
+        PwdHash(pwd_hash)
+    };
+
+    let ssk = cli.keys.load_keys(&wallet_path, &psw)?.ssk;
+
+    let checkpoint_store = LPCheckpointStore::new(&config.checkpoint_path);
+    let reference_lp =
+        ReferenceLP::create_with_checkpoint(&ssk, &checkpoint_store)?;
+    let client =
+        RuskHttpClient::new(blockchain_access_config.rusk_address.clone());
+    let license_issuer = LicenseIssuer::new(
+        blockchain_access_config,
+        wallet_path,
+        psw,
+        config.gas_limit,
+        config.gas_price,
+    )
+    .with_client(client.clone())
+    .with_await_config(config.await_config())
+    .with_gas_estimation(config.estimate_gas)
+    .with_gas_estimator(config.gas_estimator())
+    .with_address_index(cli.keys.address_index);
+
+    let mut daemon = LPDaemon {
+        reference_lp,
+        checkpoint_store,
+        license_issuer,
+        wallet_session: None,
+        policy: config.policy.to_policy()?,
+        audit_log: AuditLog::new(&config.audit_log_path),
+        notice_log: config.notices_path.as_ref().map(NoticeLog::new),
+        client,
+        poll_interval: config.poll_interval(),
+    };
+
+    daemon.run().await
+}
@@ -0,0 +1,40 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use dusk_jubjub::JubJubScalar;
+use moat_cli_common::Error;
+use serde::{Deserialize, Serialize};
+use zk_citadel_moat::license_provider::{
+    ApprovalPolicy, ApproveAll, RejectAll,
+};
+use zk_citadel_moat::{
+    AttributeEncoder, DefaultAttributeEncoder, LicenseClaims,
+};
+
+/// Approval policy selected in the daemon config.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PolicyConfig {
+    ApproveAll { attr_data: u64 },
+    ApproveAllWithClaims { claims: LicenseClaims },
+    RejectAll { reason: String },
+}
+
+impl PolicyConfig {
+    pub fn to_policy(&self) -> Result<Box<dyn ApprovalPolicy>, Error> {
+        Ok(match self {
+            PolicyConfig::ApproveAll { attr_data } => {
+                Box::new(ApproveAll::new(JubJubScalar::from(*attr_data)))
+            }
+            PolicyConfig::ApproveAllWithClaims { claims } => Box::new(
+                ApproveAll::new(DefaultAttributeEncoder.encode(claims)?),
+            ),
+            PolicyConfig::RejectAll { reason } => {
+                Box::new(RejectAll::new(reason.clone()))
+            }
+        })
+    }
+}
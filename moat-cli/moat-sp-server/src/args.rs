@@ -0,0 +1,34 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use moat_cli_common::KeysArgs;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+#[command(propagate_version = true)]
+pub struct Args {
+    /// Wallet directory [default: `$HOME/.dusk/rusk-wallet`]
+    #[clap(short, long, default_value = concat!(env!("HOME"), "/.dusk/rusk-wallet"))]
+    pub wallet_path: PathBuf,
+
+    /// Server config file, including blockchain access config
+    #[clap(short, long, default_value = "moat-cli/moat-sp-server/config.toml")]
+    pub config_path: PathBuf,
+
+    /// Password for the wallet
+    #[clap(long, default_value_t = String::from(""), env = "RUSK_WALLET_PWD")]
+    pub wallet_pass: String,
+
+    /// Hash of the password for the wallet [default: ``]
+    #[clap(short, long, default_value_t = String::from(""))]
+    pub pwd_hash: String,
+
+    #[command(flatten)]
+    pub keys: KeysArgs,
+}
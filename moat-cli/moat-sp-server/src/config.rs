@@ -0,0 +1,54 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+use toml_base_config::BaseConfig;
+use zk_citadel_moat::wallet_accessor::BlockchainAccessConfig;
+use zk_citadel_moat::DEFAULT_CHALLENGE_TTL;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SPServerConfig {
+    pub rusk_address: String,
+    pub prover_address: String,
+    #[serde(default = "default_listen_address")]
+    pub listen_address: String,
+    #[serde(default = "default_used_sessions_path")]
+    pub used_sessions_path: PathBuf,
+    #[serde(default = "default_challenge_ttl_secs")]
+    pub challenge_ttl_secs: u64,
+}
+
+impl SPServerConfig {
+    pub fn blockchain_access_config(&self) -> BlockchainAccessConfig {
+        BlockchainAccessConfig {
+            rusk_address: self.rusk_address.clone(),
+            prover_address: self.prover_address.clone(),
+            ..Default::default()
+        }
+    }
+
+    pub fn challenge_ttl(&self) -> Duration {
+        Duration::from_secs(self.challenge_ttl_secs)
+    }
+}
+
+impl BaseConfig for SPServerConfig {
+    const PACKAGE: &'static str = env!("CARGO_PKG_NAME");
+}
+
+fn default_listen_address() -> String {
+    "127.0.0.1:9090".to_string()
+}
+
+fn default_used_sessions_path() -> PathBuf {
+    PathBuf::from("moat_sp_sessions.json")
+}
+
+fn default_challenge_ttl_secs() -> u64 {
+    DEFAULT_CHALLENGE_TTL.as_secs()
+}
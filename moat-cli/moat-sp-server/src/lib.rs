@@ -0,0 +1,13 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! HTTP server verifying service requests on behalf of a Service Provider.
+
+mod config;
+mod server;
+
+pub use config::SPServerConfig;
+pub use server::{SPServer, MAX_REQUEST_SIZE};
@@ -0,0 +1,63 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+mod args;
+
+use crate::args::Args;
+use std::fs;
+use std::net::SocketAddr;
+
+use clap::Parser;
+
+use dusk_wallet::WalletPath;
+use moat_cli_common::Error;
+use moat_sp_server::{SPServer, SPServerConfig};
+use toml_base_config::BaseConfig;
+use zk_citadel_moat::wallet_accessor::Password::{Pwd, PwdHash};
+use zk_citadel_moat::{ServiceVerifier, UsedSessions};
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    tracing_subscriber::fmt::init();
+
+    let cli = Args::parse();
+
+    let config_path = cli.config_path.as_path();
+    let wallet_path = cli.wallet_path.as_path();
+    let password = cli.wallet_pass;
+    let pwd_hash = cli.pwd_hash;
+
+    let _ = fs::metadata(config_path).map_err(|_| {
+        Error::NotFound(config_path.to_string_lossy().into_owned().into())
+    })?;
+    let config = SPServerConfig::load_path(config_path)?;
+    let listen_address: SocketAddr =
+        config.listen_address.parse().map_err(|_| {
+            Error::InvalidConfigValue(config.listen_address.clone().into())
+        })?;
+
+    let wallet_path = WalletPath::from(wallet_path.join("wallet.dat"));
+    let psw = if pwd_hash.is_empty() {
+        Pwd(password)
+    } else {
+        PwdHash(pwd_hash)
+    };
+
+    let psk_sp = cli.keys.load_keys(&wallet_path, &psw)?.psk;
+
+    let verifier = ServiceVerifier::new(
+        config.blockchain_access_config(),
+        psk_sp,
+        UsedSessions::load(&config.used_sessions_path)?,
+        config.challenge_ttl(),
+    );
+
+    let (_addr, serving) =
+        SPServer::new(verifier).bind(&listen_address, async {
+            let _ = tokio::signal::ctrl_c().await;
+        })?;
+    serving.await
+}
@@ -0,0 +1,173 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use hyper::body::HttpBody;
+use hyper::header::{HeaderValue, CONTENT_TYPE};
+use hyper::server::conn::AddrStream;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use moat_cli_common::Error;
+use rand::rngs::OsRng;
+use serde::Serialize;
+use std::convert::Infallible;
+use std::future::Future;
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use tracing::{info, warn};
+use zk_citadel_moat::{
+    ServiceRequest, ServiceResponse, ServiceVerifier, CHALLENGE_ENDPOINT,
+    SERVICE_ENDPOINT,
+};
+
+/// Maximum size of a service request body.
+pub const MAX_REQUEST_SIZE: usize = 16 * 1024;
+
+/// HTTP server accepting service requests.
+/// Requests are verified concurrently, the verifier makes sure that a
+/// session cannot be used twice by concurrent requests.
+pub struct SPServer {
+    verifier: Arc<ServiceVerifier>,
+}
+
+impl SPServer {
+    pub fn new(verifier: ServiceVerifier) -> Self {
+        Self {
+            verifier: Arc::new(verifier),
+        }
+    }
+
+    /// Binds server to a given address. Returns the bound address, useful
+    /// when binding to port 0, and the future serving requests until the
+    /// shutdown signal completes.
+    pub fn bind(
+        self,
+        addr: &SocketAddr,
+        shutdown: impl Future<Output = ()>,
+    ) -> Result<(SocketAddr, impl Future<Output = Result<(), Error>>), Error>
+    {
+        let verifier = self.verifier;
+        let make_service = make_service_fn(move |conn: &AddrStream| {
+            let verifier = verifier.clone();
+            let peer = conn.remote_addr().ip();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    Self::handle(verifier.clone(), peer, request)
+                }))
+            }
+        });
+        let server = Server::try_bind(addr).map_err(Self::to_io_error)?;
+        let server = server.serve(make_service);
+        let local_addr = server.local_addr();
+        info!("listening on {}", local_addr);
+        let serving = async move {
+            server
+                .with_graceful_shutdown(shutdown)
+                .await
+                .map_err(Self::to_io_error)?;
+            Ok(())
+        };
+        Ok((local_addr, serving))
+    }
+
+    async fn handle(
+        verifier: Arc<ServiceVerifier>,
+        peer: IpAddr,
+        request: Request<Body>,
+    ) -> Result<Response<Body>, Infallible> {
+        if request.uri().path() == CHALLENGE_ENDPOINT {
+            if request.method() != Method::GET {
+                return Ok(Self::response(
+                    StatusCode::METHOD_NOT_ALLOWED,
+                    &ServiceResponse::deny("method not allowed"),
+                ));
+            }
+            return Ok(match verifier.issue_challenge(&mut OsRng, peer) {
+                Some(challenge) => Self::response(StatusCode::OK, &challenge),
+                None => {
+                    warn!("too many pending challenges for {}", peer);
+                    Self::response(
+                        StatusCode::TOO_MANY_REQUESTS,
+                        &ServiceResponse::deny("too many pending challenges"),
+                    )
+                }
+            });
+        }
+        if request.uri().path() != SERVICE_ENDPOINT {
+            return Ok(Self::response(
+                StatusCode::NOT_FOUND,
+                &ServiceResponse::deny("not found"),
+            ));
+        }
+        if request.method() != Method::POST {
+            return Ok(Self::response(
+                StatusCode::METHOD_NOT_ALLOWED,
+                &ServiceResponse::deny("method not allowed"),
+            ));
+        }
+        let service_request = match Self::read_request(request).await {
+            Ok(service_request) => service_request,
+            Err(reason) => {
+                return Ok(Self::response(
+                    StatusCode::BAD_REQUEST,
+                    &ServiceResponse::deny(reason),
+                ))
+            }
+        };
+        let result = verifier.verify(&service_request).await;
+        Ok(match result {
+            Ok(service_response) => {
+                info!(
+                    "service {} for session cookie {}",
+                    if service_response.granted {
+                        "granted"
+                    } else {
+                        "denied"
+                    },
+                    service_request.session_cookie
+                );
+                Self::response(StatusCode::OK, &service_response)
+            }
+            Err(e) => {
+                warn!("verification failed: {}", e);
+                Self::response(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    &ServiceResponse::deny("verification unavailable"),
+                )
+            }
+        })
+    }
+
+    async fn read_request(
+        request: Request<Body>,
+    ) -> Result<ServiceRequest, &'static str> {
+        let mut body = request.into_body();
+        let mut bytes = Vec::new();
+        while let Some(chunk) = body.data().await {
+            let chunk = chunk.map_err(|_| "invalid body")?;
+            if bytes.len() + chunk.len() > MAX_REQUEST_SIZE {
+                return Err("request too large");
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        serde_json::from_slice(&bytes).map_err(|_| "invalid service request")
+    }
+
+    fn response<T: Serialize>(status: StatusCode, body: &T) -> Response<Body> {
+        let json =
+            serde_json::to_vec(body).expect("Serializing should be infallible");
+        let mut response = Response::new(Body::from(json));
+        *response.status_mut() = status;
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        response
+    }
+
+    fn to_io_error(e: hyper::Error) -> Error {
+        Error::from(io::Error::new(io::ErrorKind::Other, e))
+    }
+}
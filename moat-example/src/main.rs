@@ -5,9 +5,14 @@
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
 use dusk_jubjub::JubJubScalar;
+use dusk_wallet::WalletPath;
 use rand::rngs::OsRng;
+use std::path::Path;
+use toml_base_config::BaseConfig;
 
 use zk_citadel_moat::api::{Error, MoatContext, MoatCore};
+use zk_citadel_moat::wallet_accessor::BlockchainAccessConfig;
+use zk_citadel_moat::wallet_accessor::Password::Pwd;
 
 #[tokio::main]
 #[allow(non_snake_case)]
@@ -23,28 +28,31 @@ async fn main() -> Result<(), Error> {
     let gas_limit = 500000000;
     let gas_price = 1;
 
-    // Build a configuration object with the previously set information
-    let moat_context = MoatContext::create(
-        config_path,
-        wallet_path,
-        wallet_password,
-        gas_limit,
-        gas_price,
-    )?;
+    // Build a configuration object with the previously set information,
+    // gas limits are estimated from past calls when available
+    let moat_context = MoatContext::builder(
+        BlockchainAccessConfig::load_path(config_path)?,
+        WalletPath::from(Path::new(wallet_path)),
+        Pwd(wallet_password.to_string()),
+    )
+    .gas_limit(gas_limit)
+    .gas_price(gas_price)
+    .gas_estimation(true)
+    .build();
 
     // Retrieve the keypair from the installed wallet
     let (psk_user, ssk_user) = MoatCore::get_wallet_keypair(&moat_context)?;
 
     // Submit a request to the Blockchain
     let psk_lp = psk_user; // we specify the same key just for testing
-    let request_hash = MoatCore::request_license(
+    let request_receipt = MoatCore::request_license(
         &ssk_user,
         &psk_lp,
         &moat_context,
         &mut OsRng,
     )
     .await?;
-    println!("Request transacted: {:?}", request_hash);
+    println!("Request transacted: {:?}", request_receipt);
 
     // Get owned requests
     let ssk_lp = ssk_user; // we set the same key just for testing
@@ -53,7 +61,7 @@ async fn main() -> Result<(), Error> {
     // Issue a license
     let attr_data = JubJubScalar::from(1234u64);
     let rng = &mut OsRng;
-    let license_hash = MoatCore::issue_license(
+    let license_receipt = MoatCore::issue_license(
         requests.get(0).expect("A request was owned."),
         &ssk_lp,
         &moat_context,
@@ -61,7 +69,7 @@ async fn main() -> Result<(), Error> {
         rng,
     )
     .await?;
-    println!("License issued: {:?}", license_hash);
+    println!("License issued: {:?}", license_receipt);
 
     // Get owned licenses
     let licenses =
@@ -70,7 +78,7 @@ async fn main() -> Result<(), Error> {
     // Use a license
     let psk_sp = psk_lp; // we set the same key as the one for LP just for testing
     let challenge = JubJubScalar::from(1234u64);
-    let session_cookie = MoatCore::use_license(
+    let use_license_receipt = MoatCore::use_license(
         &moat_context,
         &psk_lp,
         &psk_sp,
@@ -81,7 +89,8 @@ async fn main() -> Result<(), Error> {
     )
     .await?
     .expect("session cookie has been obtained");
-    println!("Session cookie for the used license: {:?}", session_cookie);
+    println!("License used: {:?}", use_license_receipt);
+    let session_cookie = use_license_receipt.session_cookie()?;
 
     // Verify a session cookie
     if MoatCore::verify_requested_service(
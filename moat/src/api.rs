@@ -4,28 +4,39 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
-use dusk_jubjub::{JubJubAffine, JubJubScalar};
+use dusk_jubjub::JubJubScalar;
 use dusk_pki::{PublicSpendKey, SecretSpendKey};
 use dusk_wallet::{RuskHttpClient, Wallet, WalletPath};
 
-use zk_citadel::license::{License, Request, Session, SessionCookie};
+use zk_citadel::license::{License, Request, SessionCookie};
 
-use crate::license_provider::{LicenseIssuer, ReferenceLP};
+use crate::license_provider::{LPCheckpointStore, LicenseIssuer, ReferenceLP};
 use crate::utils::MoatCoreUtils;
 use crate::wallet_accessor::Password::{self, Pwd};
 use crate::wallet_accessor::{BlockchainAccessConfig, WalletAccessor};
 use crate::{
-    BcInquirer, CitadelInquirer, LicenseSessionId, RequestCreator,
-    RequestSender, TxAwaiter,
+    AwaitConfig, CircuitKeyStore, GasEstimate, GasEstimator, LicenseCache,
+    LicenseReceipt, RequestCreator, RequestHistory, RequestReceipt,
+    RequestSender, RequestStatus, ServiceClient, ServiceRequest,
+    ServiceResponse, ServiceVerifier, TxAwaiter, TxOutcome, UseLicenseReceipt,
+    REQUEST_LICENSE_METHOD_NAME, USE_LICENSE_METHOD_NAME,
 };
 
 use rand::rngs::OsRng;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 use toml_base_config::BaseConfig;
+use tracing::warn;
 
 pub use crate::Error;
 
+/// Default gas limit of the transactions.
+pub const DEFAULT_GAS_LIMIT: u64 = 500000000;
+
+/// Default gas price of the transactions.
+pub const DEFAULT_GAS_PRICE: u64 = 1;
+
 pub struct MoatCore {}
 
 impl MoatCore {
@@ -51,26 +62,116 @@ impl MoatCore {
         psk_lp: &PublicSpendKey,
         moat_context: &MoatContext,
         rng: &mut OsRng,
-    ) -> Result<String, Error> {
-        let request = RequestCreator::create(ssk_user, psk_lp, rng)?;
-        let request_hash = MoatCoreUtils::to_hash_hex(&request);
-
+    ) -> Result<RequestReceipt, Error> {
+        let (request, lsa) =
+            RequestCreator::create_with_lsa(ssk_user, psk_lp, rng)?;
+        let request_blob = MoatCoreUtils::to_blob(&request);
+
+        let client = &moat_context.client;
+        let gas_limit = moat_context
+            .gas_limit_for(REQUEST_LICENSE_METHOD_NAME)
+            .await?;
         let tx_id = RequestSender::send_request(
             request,
             &moat_context.blockchain_access_config,
             &moat_context.wallet_path,
             &moat_context.wallet_password,
-            moat_context.gas_limit,
+            gas_limit,
             moat_context.gas_price,
         )
         .await?;
 
-        let client = RuskHttpClient::new(
-            moat_context.blockchain_access_config.rusk_address.clone(),
-        );
-        TxAwaiter::wait_for(&client, tx_id).await?;
+        let (block_height, gas_spent) = TxAwaiter::wait_for_outcome(
+            client,
+            tx_id,
+            &moat_context.await_config,
+        )
+        .await?
+        .into_result()?;
+
+        let receipt = RequestReceipt::new(
+            &tx_id,
+            block_height,
+            gas_spent,
+            request_blob.as_slice(),
+        )
+        .with_lsa(&lsa);
+        // request is already on chain, failing to record it locally only
+        // affects request status queries
+        if let Err(e) =
+            RequestHistory::load_from_wallet_dir(&moat_context.wallet_path)
+                .and_then(|mut history| history.insert(receipt.clone()))
+        {
+            warn!("recording request in request history failed: {}", e);
+        }
+        Ok(receipt)
+    }
 
-        Ok(request_hash)
+    /// Retrieve status of a license request with a given request hash,
+    /// submitted from the wallet of the Moat Context. Returns None if the
+    /// request has not been submitted from the wallet.
+    pub async fn request_status(
+        moat_context: &MoatContext,
+        request_hash: &str,
+    ) -> Result<Option<RequestStatus>, Error> {
+        let request_history =
+            RequestHistory::load_from_wallet_dir(&moat_context.wallet_path)?;
+        match request_history.get(request_hash) {
+            Some(receipt) => {
+                let license_cache =
+                    Self::synced_license_cache(moat_context).await?;
+                let status = RequestStatus::query(
+                    &moat_context.client,
+                    receipt,
+                    &license_cache,
+                )
+                .await?;
+                Ok(Some(status))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Retrieve statuses of all license requests created by a given secret
+    /// key, submitted from the wallet of the Moat Context
+    pub async fn request_statuses(
+        ssk_user: &SecretSpendKey,
+        moat_context: &MoatContext,
+    ) -> Result<Vec<RequestStatus>, Error> {
+        let request_history =
+            RequestHistory::load_from_wallet_dir(&moat_context.wallet_path)?;
+        let license_cache = Self::synced_license_cache(moat_context).await?;
+        let mut statuses = vec![];
+        for receipt in request_history.owned_receipts(&ssk_user.view_key()) {
+            statuses.push(
+                RequestStatus::query(
+                    &moat_context.client,
+                    receipt,
+                    &license_cache,
+                )
+                .await?,
+            );
+        }
+        Ok(statuses)
+    }
+
+    async fn synced_license_cache(
+        moat_context: &MoatContext,
+    ) -> Result<LicenseCache, Error> {
+        let mut license_cache =
+            LicenseCache::load_from_wallet_dir(&moat_context.wallet_path)?;
+        license_cache.sync(&moat_context.client).await?;
+        Ok(license_cache)
+    }
+
+    /// Estimate gas of a given license contract method from past calls,
+    /// returns None if no past calls have been found
+    pub async fn estimate_gas(
+        moat_context: &MoatContext,
+        fn_name: &str,
+    ) -> Result<Option<GasEstimate>, Error> {
+        let client = &moat_context.client;
+        moat_context.gas_estimator.estimate(client, fn_name).await
     }
 
     /// Retrieve a vector containing all the licenses owned by a given secret
@@ -79,54 +180,80 @@ impl MoatCore {
         ssk_user: &SecretSpendKey,
         moat_context: &MoatContext,
     ) -> Result<Vec<License>, Error> {
-        let client = RuskHttpClient::new(
-            moat_context.blockchain_access_config.rusk_address.clone(),
-        );
-        let end_height = BcInquirer::block_height(&client).await?;
-        let block_range = 0..(end_height + 1);
-
-        let mut licenses_stream =
-            CitadelInquirer::get_licenses(&client, block_range.clone()).await?;
-
-        let pairs = CitadelInquirer::find_all_licenses(&mut licenses_stream)?;
-        let vk = ssk_user.view_key();
-        let mut licenses = vec![];
-
-        for (_pos, license) in pairs.into_iter() {
-            if vk.owns(&license.lsa) {
-                licenses.push(license);
-            }
-        }
+        let license_cache = Self::synced_license_cache(moat_context).await?;
 
-        Ok(licenses)
+        Ok(license_cache
+            .owned_licenses(ssk_user)
+            .into_iter()
+            .map(|(_pos, license)| license)
+            .collect())
     }
 
-    /// Retrieve all the requests owned by the LP
+    /// Retrieve all the requests owned by the LP which have not been
+    /// processed yet. Scanning resumes from the LP checkpoint of the Moat
+    /// Context, and the checkpoint is updated
     pub async fn get_owned_requests(
         ssk_lp: &SecretSpendKey,
         moat_context: &MoatContext,
     ) -> Result<Vec<Request>, Error> {
-        let mut reference_lp = ReferenceLP::create_with_ssk(ssk_lp)?;
-        reference_lp
-            .scan(&moat_context.blockchain_access_config)
-            .await?;
+        let store = moat_context.lp_checkpoint_store();
+        let mut reference_lp = match &store {
+            Some(store) => ReferenceLP::create_with_checkpoint(ssk_lp, store)?,
+            None => ReferenceLP::create_with_ssk(ssk_lp)?,
+        };
+        match &store {
+            Some(store) => {
+                reference_lp
+                    .scan_with_checkpoint(&moat_context.client, store)
+                    .await?
+            }
+            None => reference_lp.scan(&moat_context.client).await?,
+        };
 
         Ok(reference_lp.requests_to_process)
     }
 
-    /// Create and send a transaction containing a license for a given request
+    /// Create and send a transaction containing a license for a given request.
+    /// The request is kept in flight in the LP checkpoint of the Moat Context
+    /// until the transaction is confirmed, and marked as processed
+    /// afterwards. Transaction which is not confirmed in time stays in
+    /// flight, it is settled when issuing the next license, so that the
+    /// license is issued again only if the transaction has failed or has
+    /// been lost.
     pub async fn issue_license(
         request: &Request,
         ssk_lp: &SecretSpendKey,
         moat_context: &MoatContext,
         attr_data: &JubJubScalar,
         rng: &mut OsRng,
-    ) -> Result<String, Error> {
-        let mut reference_lp = ReferenceLP::create_with_ssk(ssk_lp)?;
-
-        let (_total_count, _this_lp_count) = reference_lp
-            .scan(&moat_context.blockchain_access_config)
-            .await?;
+    ) -> Result<LicenseReceipt, Error> {
+        let store = moat_context.lp_checkpoint_store();
+        let save = |reference_lp: &ReferenceLP| match &store {
+            Some(store) => store.save(&reference_lp.checkpoint()),
+            None => Ok(()),
+        };
+        let mut reference_lp = match &store {
+            Some(store) => ReferenceLP::create_with_checkpoint(ssk_lp, store)?,
+            None => ReferenceLP::create_with_ssk(ssk_lp)?,
+        };
+        reference_lp.settle_in_flight(&moat_context.client).await?;
+        save(&reference_lp)?;
+
+        let request_hash = MoatCoreUtils::to_hash_hex(request);
+        if reference_lp.is_processed(&request_hash) {
+            return Err(Error::LocalState(
+                "license already issued for the request".into(),
+            ));
+        }
+        if reference_lp.is_in_flight(&request_hash) {
+            return Err(Error::LocalState(
+                "license for the request not confirmed yet".into(),
+            ));
+        }
+        if reference_lp.get_request(&request_hash).is_none() {
+            reference_lp.mark_in_flight(request);
+        }
+        save(&reference_lp)?;
 
         let license_issuer = LicenseIssuer::new(
             moat_context.blockchain_access_config.clone(),
@@ -134,16 +261,47 @@ impl MoatCore {
             moat_context.wallet_password.clone(),
             moat_context.gas_limit,
             moat_context.gas_price,
-        );
-
-        let (_tx_id, license_blob) = license_issuer
-            .issue_license(rng, request, &reference_lp.ssk_lp, attr_data)
-            .await?;
-        Ok(MoatCoreUtils::blob_to_hash_hex(&license_blob))
+        )
+        .with_client(moat_context.client.clone())
+        .with_await_config(moat_context.await_config.clone())
+        .with_gas_estimation(moat_context.estimate_gas)
+        .with_gas_estimator(moat_context.gas_estimator.clone());
+
+        let submission = match license_issuer
+            .submit_license(rng, request, &reference_lp.ssk_lp, attr_data)
+            .await
+        {
+            Ok(submission) => submission,
+            Err(e) => {
+                reference_lp.return_request(request.clone());
+                save(&reference_lp)?;
+                return Err(e);
+            }
+        };
+        reference_lp.record_submission(request, submission.clone());
+        save(&reference_lp)?;
+
+        let outcome = license_issuer.wait_for_submission(&submission).await;
+        let receipt = match outcome {
+            Ok(TxOutcome::Included { height, gas_spent }) => {
+                reference_lp.mark_processed(request);
+                Ok(submission.receipt(request, height, gas_spent))
+            }
+            Ok(TxOutcome::Failed { error, .. }) => {
+                reference_lp.return_request(request.clone());
+                Err(Error::Transaction(error.into()))
+            }
+            Ok(TxOutcome::Dropped) => Err(Error::Transaction(
+                "Confirmation timed out, license stays pending".into(),
+            )),
+            Err(e) => Err(e),
+        };
+        save(&reference_lp)?;
+        receipt
     }
 
     /// Create and send a transaction containing a proof that uses a given
-    /// license
+    /// license, returns None if the license is not found
     pub async fn use_license(
         moat_context: &MoatContext,
         psk_lp: &PublicSpendKey,
@@ -152,10 +310,13 @@ impl MoatCore {
         challenge: &JubJubScalar,
         license: &License,
         rng: &mut OsRng,
-    ) -> Result<Option<SessionCookie>, Error> {
+    ) -> Result<Option<UseLicenseReceipt>, Error> {
         let license_hash = MoatCoreUtils::to_hash_hex(license);
+        let mut license_cache =
+            LicenseCache::load_from_wallet_dir(&moat_context.wallet_path)?;
         let pos_license = MoatCoreUtils::get_license_to_use(
-            &moat_context.blockchain_access_config,
+            &moat_context.client,
+            &mut license_cache,
             ssk,
             license_hash.to_owned(),
         )
@@ -163,30 +324,67 @@ impl MoatCore {
 
         match pos_license {
             Some((pos, license)) => {
-                let (_tx_id, session_cookie) =
-                    MoatCoreUtils::prove_and_send_use_license(
-                        &moat_context.blockchain_access_config,
-                        &moat_context.wallet_path,
-                        &moat_context.wallet_password,
-                        psk_lp,
-                        psk_sp,
-                        ssk,
-                        challenge,
-                        &license,
-                        pos,
-                        moat_context.gas_limit,
-                        moat_context.gas_price,
-                        &mut None,
-                        rng,
-                    )
-                    .await?;
-
-                Ok(Some(session_cookie))
+                let gas_limit =
+                    moat_context.gas_limit_for(USE_LICENSE_METHOD_NAME).await?;
+                let mut key_store =
+                    CircuitKeyStore::from_wallet_dir(&moat_context.wallet_path)
+                        .with_config(&moat_context.blockchain_access_config)?;
+                let receipt = MoatCoreUtils::prove_and_send_use_license(
+                    &moat_context.client,
+                    &moat_context.blockchain_access_config,
+                    &moat_context.wallet_path,
+                    &moat_context.wallet_password,
+                    psk_lp,
+                    psk_sp,
+                    ssk,
+                    challenge,
+                    &license,
+                    pos,
+                    gas_limit,
+                    moat_context.gas_price,
+                    &moat_context.await_config,
+                    &mut key_store,
+                    rng,
+                )
+                .await?;
+
+                Ok(Some(receipt))
             }
             _ => Ok(None),
         }
     }
 
+    /// Fetch a challenge from the Service Provider at a given address, to be
+    /// used when proving the use of a license. Fails if the challenge has
+    /// not been issued for the given Service Provider or has expired
+    pub async fn get_challenge(
+        sp_address: &str,
+        psk_sp: &PublicSpendKey,
+    ) -> Result<JubJubScalar, Error> {
+        let challenge = ServiceClient::get_challenge(sp_address).await?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        if !challenge.is_bound_to(psk_sp) || challenge.is_expired(now) {
+            return Err(Error::InvalidQueryResponse(
+                "challenge not valid for the service provider".into(),
+            ));
+        }
+        challenge.challenge()
+    }
+
+    /// Request a service from the Service Provider at a given address,
+    /// presenting a session cookie obtained by using a license
+    pub async fn request_service(
+        sp_address: &str,
+        psk_lp: &PublicSpendKey,
+        session_cookie: &SessionCookie,
+    ) -> Result<ServiceResponse, Error> {
+        let request = ServiceRequest::new(session_cookie, psk_lp);
+        ServiceClient::request_service(sp_address, &request).await
+    }
+
     /// Given a session cookie, verify that it corresponds to an existing
     /// session in the Blockchain
     pub async fn verify_requested_service(
@@ -195,22 +393,14 @@ impl MoatCore {
         psk_sp: &PublicSpendKey,
         session_cookie: &SessionCookie,
     ) -> Result<bool, Error> {
-        let client = RuskHttpClient::new(
-            moat_context.blockchain_access_config.rusk_address.clone(),
-        );
-
-        let pk_lp = JubJubAffine::from(*psk_lp.A());
-        let pk_sp = JubJubAffine::from(*psk_sp.A());
-
-        let session_id = LicenseSessionId {
-            id: session_cookie.session_id,
-        };
-        let session = CitadelInquirer::get_session(&client, session_id)
-            .await?
-            .ok_or(Error::SessionNotFound)?;
-
-        let session = Session::from(&session.public_inputs);
-        Ok(session.verifies_ok(*session_cookie, pk_lp, pk_sp))
+        let client = &moat_context.client;
+        ServiceVerifier::verify_session_cookie(
+            client,
+            session_cookie,
+            psk_lp,
+            psk_sp,
+        )
+        .await
     }
 }
 
@@ -218,8 +408,13 @@ pub struct MoatContext {
     blockchain_access_config: BlockchainAccessConfig,
     wallet_path: WalletPath,
     wallet_password: Password,
+    client: RuskHttpClient,
     gas_limit: u64,
     gas_price: u64,
+    await_config: AwaitConfig,
+    estimate_gas: bool,
+    gas_estimator: GasEstimator,
+    lp_checkpoint_path: Option<PathBuf>,
 }
 
 impl MoatContext {
@@ -237,12 +432,186 @@ impl MoatContext {
         let blockchain_access_config =
             BlockchainAccessConfig::load_path(config_path.as_ref())?;
 
-        Ok(Self {
+        Ok(MoatContextBuilder::new(
             blockchain_access_config,
             wallet_path,
             wallet_password,
-            gas_limit,
-            gas_price,
-        })
+        )
+        .gas_limit(gas_limit)
+        .gas_price(gas_price)
+        .build())
+    }
+
+    /// Start building a Moat Context from already loaded configuration and
+    /// wallet password, which can be given as a hash
+    pub fn builder(
+        blockchain_access_config: BlockchainAccessConfig,
+        wallet_path: WalletPath,
+        wallet_password: Password,
+    ) -> MoatContextBuilder {
+        MoatContextBuilder::new(
+            blockchain_access_config,
+            wallet_path,
+            wallet_password,
+        )
+    }
+
+    pub fn blockchain_access_config(&self) -> &BlockchainAccessConfig {
+        &self.blockchain_access_config
+    }
+
+    pub fn wallet_path(&self) -> &WalletPath {
+        &self.wallet_path
+    }
+
+    pub fn wallet_password(&self) -> &Password {
+        &self.wallet_password
+    }
+
+    /// Client used for querying the blockchain
+    pub fn client(&self) -> &RuskHttpClient {
+        &self.client
+    }
+
+    pub fn gas_limit(&self) -> u64 {
+        self.gas_limit
+    }
+
+    pub fn gas_price(&self) -> u64 {
+        self.gas_price
+    }
+
+    pub fn await_config(&self) -> &AwaitConfig {
+        &self.await_config
+    }
+
+    pub fn estimate_gas(&self) -> bool {
+        self.estimate_gas
+    }
+
+    pub fn gas_estimator(&self) -> &GasEstimator {
+        &self.gas_estimator
+    }
+
+    /// Store of the License Provider's checkpoint, placed in the wallet
+    /// directory unless set otherwise, or None if the wallet path has no
+    /// directory
+    pub fn lp_checkpoint_store(&self) -> Option<LPCheckpointStore> {
+        match &self.lp_checkpoint_path {
+            Some(path) => Some(LPCheckpointStore::new(path)),
+            None => LPCheckpointStore::from_wallet_dir(&self.wallet_path),
+        }
+    }
+
+    async fn gas_limit_for(&self, fn_name: &str) -> Result<u64, Error> {
+        self.gas_estimator
+            .gas_limit_for(
+                &self.client,
+                fn_name,
+                self.gas_limit,
+                self.estimate_gas,
+            )
+            .await
+    }
+}
+
+/// Builder of the Moat Context.
+/// Unless set, the blockchain is queried by a client created from the
+/// configured Rusk address, and the default gas settings are used.
+pub struct MoatContextBuilder {
+    blockchain_access_config: BlockchainAccessConfig,
+    wallet_path: WalletPath,
+    wallet_password: Password,
+    client: Option<RuskHttpClient>,
+    gas_limit: u64,
+    gas_price: u64,
+    await_config: AwaitConfig,
+    estimate_gas: bool,
+    gas_estimator: GasEstimator,
+    lp_checkpoint_path: Option<PathBuf>,
+}
+
+impl MoatContextBuilder {
+    pub fn new(
+        blockchain_access_config: BlockchainAccessConfig,
+        wallet_path: WalletPath,
+        wallet_password: Password,
+    ) -> Self {
+        Self {
+            blockchain_access_config,
+            wallet_path,
+            wallet_password,
+            client: None,
+            gas_limit: DEFAULT_GAS_LIMIT,
+            gas_price: DEFAULT_GAS_PRICE,
+            await_config: AwaitConfig::default(),
+            estimate_gas: false,
+            gas_estimator: GasEstimator::new(),
+            lp_checkpoint_path: None,
+        }
+    }
+
+    /// Set client used for querying the blockchain
+    pub fn client(mut self, client: RuskHttpClient) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    pub fn gas_limit(mut self, gas_limit: u64) -> Self {
+        self.gas_limit = gas_limit;
+        self
+    }
+
+    pub fn gas_price(mut self, gas_price: u64) -> Self {
+        self.gas_price = gas_price;
+        self
+    }
+
+    /// Set configuration of waiting for the submitted transactions
+    pub fn await_config(mut self, await_config: AwaitConfig) -> Self {
+        self.await_config = await_config;
+        self
+    }
+
+    /// Make transactions use gas limits estimated from past calls, the
+    /// configured gas limit is used when no estimate is available
+    pub fn gas_estimation(mut self, estimate_gas: bool) -> Self {
+        self.estimate_gas = estimate_gas;
+        self
+    }
+
+    /// Set estimator of the gas limits, by default the most recent
+    /// `GAS_ESTIMATION_BLOCKS` blocks are searched and estimates are reused
+    /// for `DEFAULT_GAS_ESTIMATE_TTL`
+    pub fn gas_estimator(mut self, gas_estimator: GasEstimator) -> Self {
+        self.gas_estimator = gas_estimator;
+        self
+    }
+
+    /// Set path of the License Provider's checkpoint, by default it is
+    /// placed in the wallet directory
+    pub fn lp_checkpoint_path<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.lp_checkpoint_path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn build(self) -> MoatContext {
+        let client = self.client.unwrap_or_else(|| {
+            RuskHttpClient::new(
+                self.blockchain_access_config.rusk_address.clone(),
+            )
+        });
+        MoatContext {
+            blockchain_access_config: self.blockchain_access_config,
+            wallet_path: self.wallet_path,
+            wallet_password: self.wallet_password,
+            client,
+            gas_limit: self.gas_limit,
+            gas_price: self.gas_price,
+            await_config: self.await_config,
+            estimate_gas: self.estimate_gas,
+            gas_estimator: self.gas_estimator,
+            lp_checkpoint_path: self.lp_checkpoint_path,
+        }
     }
 }
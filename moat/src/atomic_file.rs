@@ -0,0 +1,70 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::error::Error;
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+
+pub(crate) struct AtomicFile;
+
+impl AtomicFile {
+    /// Writes bytes to a given path so that readers observe either the old
+    /// or the new content, never a partially written file.
+    /// Content is written to a temporary sibling file, flushed to disk,
+    /// and then renamed over the destination. On unix, the file is readable
+    /// and writable by the owner only, as it may hold wallet related state.
+    pub fn write<P: AsRef<Path>>(
+        path: P,
+        bytes: impl AsRef<[u8]>,
+    ) -> Result<(), Error> {
+        let path = path.as_ref();
+        if let Some(dir) = Self::parent_dir(path) {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp_path = Self::tmp_path(path);
+        {
+            // a stale temporary file would keep its permissions
+            match fs::remove_file(&tmp_path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => {
+                    return Err(e.into())
+                }
+                _ => {}
+            }
+            let mut options = OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            options.mode(0o600);
+            let mut file = options.open(&tmp_path)?;
+            file.write_all(bytes.as_ref())?;
+            file.sync_all()?;
+        }
+        fs::rename(&tmp_path, path)?;
+        if let Some(dir) = Self::parent_dir(path) {
+            // persist the rename itself, not supported on all platforms
+            if let Ok(dir) = File::open(dir) {
+                let _ = dir.sync_all();
+            }
+        }
+        Ok(())
+    }
+
+    fn parent_dir(path: &Path) -> Option<&Path> {
+        path.parent().filter(|dir| !dir.as_os_str().is_empty())
+    }
+
+    fn tmp_path(path: &Path) -> PathBuf {
+        let mut file_name = path
+            .file_name()
+            .map(|name| name.to_os_string())
+            .unwrap_or_else(|| OsString::from("moat"));
+        file_name.push(".tmp");
+        path.with_file_name(file_name)
+    }
+}
@@ -37,6 +37,8 @@ pub struct CallInfoJson {
 #[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
 pub struct Header {
     pub height: u64,
+    #[serde(default)]
+    pub hash: String,
 }
 
 #[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
@@ -55,6 +57,11 @@ pub struct QueryResult2 {
     pub block: Block,
 }
 
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct QueryResult3 {
+    pub block: Option<Block>,
+}
+
 #[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
 pub struct SpentTx {
     pub err: Option<String>,
@@ -62,6 +69,8 @@ pub struct SpentTx {
     pub gas_spent: u64,
     #[serde(alias = "blockHeight", default)]
     pub block_height: u64,
+    #[serde(alias = "blockHash", default)]
+    pub block_hash: String,
     pub tx: Tx,
 }
 
@@ -70,6 +79,8 @@ pub struct SpentTx2 {
     pub err: Option<String>,
     #[serde(alias = "gasSpent", default)]
     pub gas_spent: u64,
+    #[serde(alias = "blockHeight", default)]
+    pub block_height: u64,
 }
 
 #[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
@@ -21,9 +21,25 @@ impl BcInquirer {
     }
 
     pub async fn block_height(client: &RuskHttpClient) -> Result<u64, Error> {
-        let query = "query { block(height: -1) {header { height}} }";
+        let query = "query { block(height: -1) {header { height, hash}} }";
         let response = Self::gql_query(client, query).await?;
         let result = serde_json::from_slice::<QueryResult2>(&response)?;
         Ok(result.block.header.height)
     }
+
+    /// Provides hash of the block at a given height, or None if there is no
+    /// such block.
+    pub async fn block_hash(
+        client: &RuskHttpClient,
+        height: u64,
+    ) -> Result<Option<String>, Error> {
+        let query = "query { block(height: ####) {header { height, hash}} }"
+            .replace("####", height.to_string().as_str());
+        let response = Self::gql_query(client, query).await?;
+        let result = serde_json::from_slice::<QueryResult3>(&response)?;
+        Ok(result
+            .block
+            .map(|block| block.header.hash)
+            .filter(|hash| !hash.is_empty()))
+    }
 }
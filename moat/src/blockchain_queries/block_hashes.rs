@@ -0,0 +1,115 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::error::Error;
+use crate::BcInquirer;
+use dusk_wallet::RuskHttpClient;
+use std::collections::BTreeMap;
+use tracing::{trace, warn};
+
+/// Maximum number of block hashes tracked, older hashes are discarded.
+pub const MAX_TRACKED_BLOCKS: usize = 1000;
+
+/// Outcome of checking the tracked block hashes against the blockchain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForkCheck {
+    /// The last tracked block is still part of the chain.
+    NoFork,
+    /// The chain has been reorganized. Common ancestor is the highest tracked
+    /// block still part of the chain, or None if no tracked block is.
+    Fork { common_ancestor: Option<u64> },
+}
+
+/// Hashes of scanned blocks, keyed by block height.
+#[derive(
+    Debug, Default, Clone, PartialEq, serde::Deserialize, serde::Serialize,
+)]
+pub struct BlockHashes {
+    hashes: BTreeMap<u64, String>,
+}
+
+impl BlockHashes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records hash of a block at a given height.
+    pub fn record(&mut self, height: u64, hash: impl Into<String>) {
+        let hash = hash.into();
+        if hash.is_empty() {
+            return;
+        }
+        self.hashes.insert(height, hash);
+        while self.hashes.len() > MAX_TRACKED_BLOCKS {
+            self.hashes.pop_first();
+        }
+    }
+
+    /// Hash of a block at a given height, or None if not tracked.
+    pub fn get(&self, height: u64) -> Option<&str> {
+        self.hashes.get(&height).map(|hash| hash.as_str())
+    }
+
+    /// Lowest tracked block height along with its hash.
+    pub fn first(&self) -> Option<(u64, &str)> {
+        self.hashes
+            .first_key_value()
+            .map(|(height, hash)| (*height, hash.as_str()))
+    }
+
+    /// Highest tracked block height along with its hash.
+    pub fn last(&self) -> Option<(u64, &str)> {
+        self.hashes
+            .last_key_value()
+            .map(|(height, hash)| (*height, hash.as_str()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Discards hashes of blocks above a given height.
+    pub fn rollback(&mut self, height: u64) {
+        self.hashes.split_off(&(height + 1));
+    }
+
+    pub fn clear(&mut self) {
+        self.hashes.clear();
+    }
+
+    /// Compares tracked hashes with the blockchain, starting from the
+    /// highest tracked block and walking back until a block still being part
+    /// of the chain is found.
+    pub async fn check_fork(
+        &self,
+        client: &RuskHttpClient,
+    ) -> Result<ForkCheck, Error> {
+        let mut forked = false;
+        for (height, hash) in self.hashes.iter().rev() {
+            let chain_hash = BcInquirer::block_hash(client, *height).await?;
+            if chain_hash.as_deref() == Some(hash.as_str()) {
+                return Ok(if forked {
+                    warn!("fork detected, common ancestor at {}", height);
+                    ForkCheck::Fork {
+                        common_ancestor: Some(*height),
+                    }
+                } else {
+                    ForkCheck::NoFork
+                });
+            }
+            trace!("block {} at height {} no longer in chain", hash, height);
+            forked = true;
+        }
+        Ok(if forked {
+            warn!("fork detected, no common ancestor among tracked blocks");
+            ForkCheck::Fork {
+                common_ancestor: None,
+            }
+        } else {
+            ForkCheck::NoFork
+        })
+    }
+}
@@ -4,10 +4,13 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+use crate::wallet_accessor::BlockchainAccessConfig;
 use crate::Error;
 use dusk_wallet::{RuskHttpClient, RuskRequest};
 use reqwest::Response;
 use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
 
 pub struct CrsGetter;
 
@@ -32,6 +35,54 @@ impl CrsGetter {
         }
     }
 
+    /// Provides hash of the CRS of the node, as sent in the `crs-hash`
+    /// header, without downloading the CRS.
+    pub async fn get_crs_hash(
+        client: &RuskHttpClient,
+    ) -> Result<CRSHash, Error> {
+        let crs_request = RuskRequest::new("crs", vec![]);
+        let response = client.call_raw(2, "rusk", &crs_request, false).await?;
+        Self::hash_from_header(&response)
+    }
+
+    /// Loads the CRS from a local file, without contacting a node.
+    /// The CRS should be verified against a known-good hash.
+    pub fn load_crs<P: AsRef<Path>>(path: P) -> Result<Vec<u8>, Error> {
+        let path = path.as_ref();
+        fs::read(path).map_err(|_| {
+            Error::CRS(
+                format!("CRS file {} not readable", path.display()).into(),
+            )
+        })
+    }
+
+    /// Checks that the CRS matches a known-good hash.
+    pub fn verify_crs(crs: &[u8], pinned_hash: &CRSHash) -> Result<(), Error> {
+        if Self::hash_of_bytes(crs) != *pinned_hash {
+            return Err(Error::CRS(
+                "CRS does not match the pinned hash".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Provides the CRS loaded from the local CRS file if one is configured,
+    /// or downloaded from the node otherwise. The CRS is verified against the
+    /// pinned CRS hash if one is configured.
+    pub async fn get_configured_crs(
+        client: &RuskHttpClient,
+        config: &BlockchainAccessConfig,
+    ) -> Result<Vec<u8>, Error> {
+        let crs = match &config.crs_path {
+            Some(crs_path) => Self::load_crs(crs_path)?,
+            None => Self::get_crs(client).await?,
+        };
+        if let Some(pinned_hash) = config.pinned_crs_hash()? {
+            Self::verify_crs(crs.as_slice(), &pinned_hash)?;
+        }
+        Ok(crs)
+    }
+
     fn hash_from_header(response: &Response) -> Result<CRSHash, Error> {
         let crs_hash = response
             .headers()
@@ -45,7 +96,7 @@ impl CrsGetter {
         Ok(h)
     }
 
-    fn hash_of_bytes<T: AsRef<[u8]>>(bytes: T) -> CRSHash {
+    pub fn hash_of_bytes<T: AsRef<[u8]>>(bytes: T) -> CRSHash {
         let mut hasher = Sha256::new();
         hasher.update(bytes.as_ref());
         hasher.finalize().into()
@@ -0,0 +1,192 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::bc_types::SpentTx;
+use crate::{Error, TxInquirer, LICENSE_CONTRACT_ID};
+use dusk_wallet::RuskHttpClient;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tracing::trace;
+
+/// Default number of most recent blocks searched for license contract
+/// calls.
+pub const GAS_ESTIMATION_BLOCKS: usize = 10000;
+
+/// Default time for which an estimate is reused before the recent blocks
+/// are searched again.
+pub const DEFAULT_GAS_ESTIMATE_TTL: Duration = Duration::from_secs(600);
+
+/// Margin added to the highest gas spent by past calls, in percent.
+pub const GAS_LIMIT_MARGIN_PERCENT: u64 = 20;
+
+/// Gas estimate of a license contract method, based on the gas spent by
+/// past successful calls of the method.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct GasEstimate {
+    pub fn_name: String,
+    /// Number of past calls the estimate is based on.
+    pub samples: usize,
+    pub max_gas_spent: u64,
+    pub avg_gas_spent: u64,
+    /// Suggested gas limit, highest gas spent increased by the margin.
+    pub gas_limit: u64,
+}
+
+/// Estimates gas limits of license contract methods from the calls found
+/// in a window of the most recent blocks.
+/// Estimates are cached per method for a time-to-live, so that issuing or
+/// using licenses does not search the window for every transaction.
+/// Clones share the cache.
+#[derive(Debug, Clone)]
+pub struct GasEstimator {
+    blocks: usize,
+    ttl: Duration,
+    cache: Arc<Mutex<HashMap<String, CachedEstimate>>>,
+}
+
+#[derive(Debug)]
+struct CachedEstimate {
+    estimated_at: Instant,
+    estimate: Option<GasEstimate>,
+}
+
+impl Default for GasEstimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GasEstimator {
+    pub fn new() -> Self {
+        Self {
+            blocks: GAS_ESTIMATION_BLOCKS,
+            ttl: DEFAULT_GAS_ESTIMATE_TTL,
+            cache: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Sets number of most recent blocks searched for past calls.
+    pub fn with_blocks(mut self, blocks: usize) -> Self {
+        self.blocks = blocks;
+        self
+    }
+
+    /// Sets time for which an estimate is reused, zero disables caching.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn blocks(&self) -> usize {
+        self.blocks
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Estimates gas of a given license contract method from the calls
+    /// found in the most recent blocks, or provides the cached estimate if
+    /// it has not expired yet.
+    /// Returns None if no successful call has been found.
+    pub async fn estimate(
+        &self,
+        client: &RuskHttpClient,
+        fn_name: impl AsRef<str>,
+    ) -> Result<Option<GasEstimate>, Error> {
+        let fn_name = fn_name.as_ref();
+        if let Some(cached) = self.cache().get(fn_name) {
+            if cached.estimated_at.elapsed() < self.ttl {
+                return Ok(cached.estimate.clone());
+            }
+        }
+        let spent_txs =
+            TxInquirer::spent_txs_from_last_n_blocks(client, self.blocks)
+                .await?;
+        let estimate = Self::estimate_from_txs(&spent_txs, fn_name);
+        trace!("gas estimate={:?}", estimate);
+        self.cache().insert(
+            fn_name.to_string(),
+            CachedEstimate {
+                estimated_at: Instant::now(),
+                estimate: estimate.clone(),
+            },
+        );
+        Ok(estimate)
+    }
+
+    fn cache(&self) -> MutexGuard<'_, HashMap<String, CachedEstimate>> {
+        self.cache
+            .lock()
+            .expect("Gas estimate cache lock should not be poisoned")
+    }
+
+    /// Estimates gas of a given license contract method from given
+    /// transactions, failed calls and calls of other contracts are skipped.
+    pub fn estimate_from_txs(
+        spent_txs: &[SpentTx],
+        fn_name: impl AsRef<str>,
+    ) -> Option<GasEstimate> {
+        let contract_id = hex::encode(LICENSE_CONTRACT_ID);
+        let gas_spent: Vec<u64> = spent_txs
+            .iter()
+            .filter(|spent_tx| spent_tx.err.is_none())
+            .filter(|spent_tx| match spent_tx.tx.call_data.as_ref() {
+                Some(call_data) => {
+                    call_data.contract_id == contract_id
+                        && call_data.fn_name == fn_name.as_ref()
+                }
+                None => false,
+            })
+            .map(|spent_tx| spent_tx.gas_spent)
+            .collect();
+        let max_gas_spent = *gas_spent.iter().max()?;
+        let samples = gas_spent.len();
+        let avg_gas_spent = gas_spent.iter().sum::<u64>() / samples as u64;
+        Some(GasEstimate {
+            fn_name: fn_name.as_ref().to_string(),
+            samples,
+            max_gas_spent,
+            avg_gas_spent,
+            gas_limit: max_gas_spent
+                .saturating_mul(100 + GAS_LIMIT_MARGIN_PERCENT)
+                / 100,
+        })
+    }
+
+    /// Provides estimated gas limit for a given license contract method, or
+    /// a given default gas limit if no estimate is available.
+    pub async fn gas_limit(
+        &self,
+        client: &RuskHttpClient,
+        fn_name: impl AsRef<str>,
+        default_gas_limit: u64,
+    ) -> Result<u64, Error> {
+        Ok(self
+            .estimate(client, fn_name)
+            .await?
+            .map(|estimate| estimate.gas_limit)
+            .unwrap_or(default_gas_limit))
+    }
+
+    /// Provides gas limit estimated from past calls of a given license
+    /// contract method if estimation is requested, or a given gas limit
+    /// otherwise.
+    pub async fn gas_limit_for(
+        &self,
+        client: &RuskHttpClient,
+        fn_name: impl AsRef<str>,
+        gas_limit: u64,
+        estimate_gas: bool,
+    ) -> Result<u64, Error> {
+        if estimate_gas {
+            self.gas_limit(client, fn_name, gas_limit).await
+        } else {
+            Ok(gas_limit)
+        }
+    }
+}
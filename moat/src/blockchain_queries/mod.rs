@@ -5,11 +5,18 @@
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
 mod bc_inquirer;
+mod block_hashes;
 mod crs_getter;
+mod gas_estimator;
 mod tx_awaiter;
 mod tx_inquirer;
 
 pub use bc_inquirer::BcInquirer;
+pub use block_hashes::{BlockHashes, ForkCheck, MAX_TRACKED_BLOCKS};
 pub use crs_getter::CrsGetter;
-pub use tx_awaiter::TxAwaiter;
+pub use gas_estimator::{
+    GasEstimate, GasEstimator, DEFAULT_GAS_ESTIMATE_TTL, GAS_ESTIMATION_BLOCKS,
+    GAS_LIMIT_MARGIN_PERCENT,
+};
+pub use tx_awaiter::{AwaitConfig, Backoff, TxAwaiter, TxOutcome};
 pub use tx_inquirer::TxInquirer;
@@ -4,15 +4,98 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
-use crate::bc_types::{SpentTx2, SpentTxResponse2, TxStatus};
-use crate::Error;
+use crate::bc_types::{SpentTx2, SpentTxResponse2};
 use crate::Error::Transaction;
+use crate::{BcInquirer, Error};
 use dusk_bls12_381::BlsScalar;
 use dusk_wallet::{RuskHttpClient, RuskRequest};
+use std::future::Future;
 use std::time::Duration;
-use tokio::time::sleep;
+use tokio::time::{sleep, Instant};
 use tracing::trace;
 
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+const DEFAULT_POLL_INTERVAL_MILLIS: u64 = 1000;
+
+/// Strategy of increasing the interval between transaction status queries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backoff {
+    /// Interval stays the same.
+    Fixed,
+    /// Interval is multiplied by a given factor after each query, up to a
+    /// given maximum.
+    Exponential { factor: u32, max_interval: Duration },
+}
+
+impl Backoff {
+    fn next_interval(&self, interval: Duration) -> Duration {
+        match self {
+            Backoff::Fixed => interval,
+            Backoff::Exponential {
+                factor,
+                max_interval,
+            } => interval.saturating_mul(*factor).min(*max_interval),
+        }
+    }
+}
+
+/// Configuration of waiting for a transaction.
+#[derive(Debug, Clone)]
+pub struct AwaitConfig {
+    /// Time after which waiting is abandoned.
+    pub timeout: Duration,
+    /// Interval before the first repeated status query.
+    pub poll_interval: Duration,
+    pub backoff: Backoff,
+    /// Number of blocks required on top of the block containing the
+    /// transaction, zero means that inclusion is sufficient.
+    pub confirmations: u64,
+}
+
+impl Default for AwaitConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
+            poll_interval: Duration::from_millis(DEFAULT_POLL_INTERVAL_MILLIS),
+            backoff: Backoff::Fixed,
+            confirmations: 0,
+        }
+    }
+}
+
+/// Final state of an awaited transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxOutcome {
+    /// Transaction executed successfully in a block at a given height.
+    Included { height: u64, gas_spent: u64 },
+    /// Transaction included in a block at a given height, but its contract
+    /// call failed with a given error.
+    Failed {
+        height: u64,
+        gas_spent: u64,
+        error: String,
+    },
+    /// Transaction not included in any block within the timeout.
+    Dropped,
+}
+
+impl TxOutcome {
+    /// Converts outcome into an error unless the transaction has been
+    /// executed successfully, returns the block height and gas spent
+    /// otherwise.
+    pub fn into_result(self) -> Result<(u64, u64), Error> {
+        match self {
+            TxOutcome::Included { height, gas_spent } => {
+                Ok((height, gas_spent))
+            }
+            TxOutcome::Failed { error, .. } => Err(Transaction(error.into())),
+            TxOutcome::Dropped => {
+                Err(Transaction("Confirmation timed out".into()))
+            }
+        }
+    }
+}
+
 pub struct TxAwaiter;
 
 impl TxAwaiter {
@@ -31,60 +114,136 @@ impl TxAwaiter {
     async fn tx_status(
         client: &RuskHttpClient,
         tx_id: impl AsRef<str>,
-    ) -> Result<TxStatus, Error> {
-        let query = "query { tx(hash: \"####\") { err }}"
-            .replace("####", tx_id.as_ref());
+    ) -> Result<Option<SpentTx2>, Error> {
+        let query =
+            "query { tx(hash: \"####\") { err, gasSpent, blockHeight }}"
+                .replace("####", tx_id.as_ref());
         let response = Self::query(client, &query).await?;
         trace!("response={:x?}", std::str::from_utf8(response.as_slice()));
-        let response =
-            serde_json::from_slice::<SpentTxResponse2>(&response)?.tx;
-        match response {
-            Some(SpentTx2 { err: Some(err), .. }) => {
-                trace!("status ERR={}", err);
-                Ok(TxStatus::Error(err))
-            }
-            Some(_) => {
-                trace!("status OK");
-                Ok(TxStatus::Ok)
-            }
-            None => {
-                trace!("status NOT_FOUND");
-                Ok(TxStatus::NotFound)
-            }
-        }
+        Ok(serde_json::from_slice::<SpentTxResponse2>(&response)?.tx)
+    }
+
+    /// Provides outcome of a transaction included in a block, without
+    /// waiting. Returns None if the transaction has not been included yet.
+    pub async fn query_outcome(
+        client: &RuskHttpClient,
+        tx_id: BlsScalar,
+    ) -> Result<Option<TxOutcome>, Error> {
+        let tx_id = hex::encode(tx_id.to_bytes());
+        Ok(Self::tx_status(client, tx_id).await?.map(Self::outcome))
     }
 
+    /// Waits for a transaction with the default configuration, returns an
+    /// error unless the transaction has been executed successfully.
     pub async fn wait_for(
         client: &RuskHttpClient,
         tx_id: BlsScalar,
     ) -> Result<(), Error> {
+        Self::wait_for_with(client, tx_id, &AwaitConfig::default()).await
+    }
+
+    /// Waits for a transaction with a given configuration, returns an error
+    /// unless the transaction has been executed successfully.
+    pub async fn wait_for_with(
+        client: &RuskHttpClient,
+        tx_id: BlsScalar,
+        cfg: &AwaitConfig,
+    ) -> Result<(), Error> {
+        Self::wait_for_outcome(client, tx_id, cfg)
+            .await?
+            .into_result()
+            .map(|_| ())
+    }
+
+    /// Waits for a transaction until it reaches the required confirmation
+    /// depth or until timeout.
+    /// Errors are returned only when the blockchain cannot be queried, or
+    /// when the transaction is included but does not reach the required
+    /// depth before timeout.
+    pub async fn wait_for_outcome(
+        client: &RuskHttpClient,
+        tx_id: BlsScalar,
+        cfg: &AwaitConfig,
+    ) -> Result<TxOutcome, Error> {
         let tx_id = hex::encode(tx_id.to_bytes());
-        Self::wait_for_tx(client, tx_id).await
+        Self::wait_for_tx(client, tx_id, cfg).await
+    }
+
+    /// Waits like `wait_for_outcome`, returns `Error::Cancelled` as soon as
+    /// a given cancellation future completes.
+    pub async fn wait_for_outcome_or_cancel(
+        client: &RuskHttpClient,
+        tx_id: BlsScalar,
+        cfg: &AwaitConfig,
+        cancel: impl Future<Output = ()>,
+    ) -> Result<TxOutcome, Error> {
+        tokio::select! {
+            outcome = Self::wait_for_outcome(client, tx_id, cfg) => outcome,
+            _ = cancel => {
+                trace!("waiting for transaction cancelled");
+                Err(Error::Cancelled)
+            }
+        }
     }
 
     async fn wait_for_tx(
         client: &RuskHttpClient,
         tx_id: impl AsRef<str>,
-    ) -> Result<(), Error> {
-        const TIMEOUT_SECS: i32 = 30;
-        let mut i = 1;
-        while i <= TIMEOUT_SECS {
-            let status = Self::tx_status(client, tx_id.as_ref()).await?;
-
-            match status {
-                TxStatus::Ok => break,
-                TxStatus::Error(err) => return Err(Transaction(err.into()))?,
-                TxStatus::NotFound => {
-                    trace!("Awaiting ({}) for {}", i, tx_id.as_ref());
-                    sleep(Duration::from_millis(1000)).await;
-                    i += 1;
+        cfg: &AwaitConfig,
+    ) -> Result<TxOutcome, Error> {
+        let deadline = Instant::now() + cfg.timeout;
+        let mut interval = cfg.poll_interval;
+        loop {
+            // transaction can disappear while awaiting confirmations, in
+            // case of a fork
+            let included = match Self::tx_status(client, tx_id.as_ref()).await?
+            {
+                Some(tx) => {
+                    let top = BcInquirer::block_height(client).await?;
+                    if top >= tx.block_height + cfg.confirmations {
+                        return Ok(Self::outcome(tx));
+                    }
+                    trace!(
+                        "{} included at {}, top is {}",
+                        tx_id.as_ref(),
+                        tx.block_height,
+                        top
+                    );
+                    true
                 }
+                None => false,
+            };
+            let now = Instant::now();
+            if now >= deadline {
+                return if included {
+                    Err(Transaction("Confirmation depth not reached".into()))
+                } else {
+                    Ok(TxOutcome::Dropped)
+                };
             }
+            trace!("Awaiting {:?} for {}", interval, tx_id.as_ref());
+            sleep(interval.min(deadline - now)).await;
+            interval = cfg.backoff.next_interval(interval);
         }
-        if i > TIMEOUT_SECS {
-            Err(Transaction("Confirmation timed out".into()))
-        } else {
-            Ok(())
+    }
+
+    fn outcome(tx: SpentTx2) -> TxOutcome {
+        match tx.err {
+            Some(error) => {
+                trace!("status ERR={}", error);
+                TxOutcome::Failed {
+                    height: tx.block_height,
+                    gas_spent: tx.gas_spent,
+                    error,
+                }
+            }
+            None => {
+                trace!("status OK");
+                TxOutcome::Included {
+                    height: tx.block_height,
+                    gas_spent: tx.gas_spent,
+                }
+            }
         }
     }
 }
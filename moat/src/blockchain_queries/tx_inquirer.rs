@@ -29,16 +29,30 @@ impl TxInquirer {
         height_end: u64,
     ) -> Result<(Transactions, u64), Error> {
         let mut transactions = Transactions::default();
+        let (spent_txs, height) = TxInquirer::spent_txs_from_block_range(
+            client, height_beg, height_end,
+        )
+        .await?;
+        transactions
+            .transactions
+            .extend(spent_txs.into_iter().map(|t| t.tx));
+        Ok((transactions, height))
+    }
+
+    /// returns transactions in a range along with heights and hashes of
+    /// the blocks containing them, and the current top block
+    pub async fn spent_txs_from_block_range(
+        client: &RuskHttpClient,
+        height_beg: u64,
+        height_end: u64,
+    ) -> Result<(Vec<SpentTx>, u64), Error> {
         let range_str = format!("{},{}", height_beg, height_end);
-        let tx_query = "query { blockTxs(range: [####] ) { tx { id, raw, callData {contractId, fnName, data} } } }".replace("####", range_str.as_str());
+        let tx_query = "query { blockTxs(range: [####] ) { blockHeight, blockHash, tx { id, raw, callData {contractId, fnName, data} } } }".replace("####", range_str.as_str());
         let tx_response =
             BcInquirer::gql_query(client, tx_query.as_str()).await?;
         let tx_result = serde_json::from_slice::<QueryResult>(&tx_response)?;
-        transactions
-            .transactions
-            .extend(tx_result.block_txs.into_iter().map(|t| t.tx));
         let height = BcInquirer::block_height(client).await?;
-        Ok((transactions, height))
+        Ok((tx_result.block_txs, height))
     }
 
     pub async fn txs_from_last_n_blocks(
@@ -57,6 +71,20 @@ impl TxInquirer {
         Ok(transactions)
     }
 
+    /// returns transactions from the last n blocks along with their
+    /// execution errors and gas spent
+    pub async fn spent_txs_from_last_n_blocks(
+        client: &RuskHttpClient,
+        n: usize,
+    ) -> Result<Vec<SpentTx>, Error> {
+        let n_str = format!("{}", n);
+        let tx_query = "query { blockTxs(last:####) { err, gasSpent, blockHeight, tx { id, raw, callData {contractId, fnName, data} } } }".replace("####", n_str.as_str());
+        let tx_response =
+            BcInquirer::gql_query(client, tx_query.as_str()).await?;
+        let tx_result = serde_json::from_slice::<QueryResult>(&tx_response)?;
+        Ok(tx_result.block_txs)
+    }
+
     pub async fn retrieve_tx<S>(
         txid: S,
         client: &RuskHttpClient,
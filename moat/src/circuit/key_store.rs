@@ -0,0 +1,286 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::atomic_file::AtomicFile;
+use crate::wallet_accessor::BlockchainAccessConfig;
+use crate::{CrsGetter, Error, LicenseCircuit};
+use dusk_plonk::prelude::*;
+use dusk_wallet::{RuskHttpClient, WalletPath};
+use sha3::{Digest, Sha3_256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const LICENSE_CIRCUIT_LABEL: &[u8] = b"dusk-network";
+pub const PROVER_KEY_FILE_NAME: &str = "moat_prover.dat";
+pub const VERIFIER_KEY_FILE_NAME: &str = "moat_verifier.dat";
+
+const HASH_SIZE: usize = 32;
+const HEADER_SIZE: usize = 3 * HASH_SIZE;
+
+type Hash = [u8; HASH_SIZE];
+
+/// Prover and verifier keys of the license circuit.
+pub struct CircuitKeys {
+    pub prover: Prover,
+    pub verifier: Verifier,
+}
+
+/// Store of the license circuit keys, compiled once and kept both in memory
+/// and in a cache directory.
+/// Each key file starts with the hash of the CRS and of the circuit label the
+/// key was compiled from, followed by the hash of the key bytes.
+/// Key files which are corrupted or compiled from a different CRS or label
+/// are ignored, and the keys are compiled again. The CRS in use is the one
+/// with the pinned hash if set, otherwise the local CRS file if set, or the
+/// CRS of the node.
+pub struct CircuitKeyStore {
+    dir: Option<PathBuf>,
+    label: Vec<u8>,
+    /// Pinned CRS hash and local CRS file, other fields are not used.
+    crs_config: BlockchainAccessConfig,
+    keys: Option<CircuitKeys>,
+}
+
+impl CircuitKeyStore {
+    /// Creates a store keeping key files in a given directory.
+    pub fn new<P: AsRef<Path>>(dir: P) -> Self {
+        Self {
+            dir: Some(dir.as_ref().to_path_buf()),
+            label: LICENSE_CIRCUIT_LABEL.to_vec(),
+            crs_config: BlockchainAccessConfig::default(),
+            keys: None,
+        }
+    }
+
+    /// Creates a store keeping key files in the wallet directory.
+    /// If the wallet path has no directory, keys are kept in memory only.
+    pub fn from_wallet_dir(wallet_path: &WalletPath) -> Self {
+        match wallet_path.dir() {
+            Some(dir) => Self::new(dir),
+            None => Self {
+                dir: None,
+                label: LICENSE_CIRCUIT_LABEL.to_vec(),
+                crs_config: BlockchainAccessConfig::default(),
+                keys: None,
+            },
+        }
+    }
+
+    /// Sets the circuit label, license circuit label is used by default.
+    pub fn with_label(mut self, label: impl AsRef<[u8]>) -> Self {
+        self.label = label.as_ref().to_vec();
+        self
+    }
+
+    /// Sets the pinned hash of the CRS, as sent by the node in the
+    /// `crs-hash` header. Keys are compiled only from a matching CRS, and key
+    /// files compiled from a different CRS are not used. Without the pinned
+    /// hash, key files are used only if compiled from the local CRS file, or
+    /// from the CRS of the node if no local file is set.
+    pub fn with_crs_hash(mut self, crs_hash: [u8; 32]) -> Self {
+        self.crs_config.crs_hash = Some(hex::encode(crs_hash));
+        self
+    }
+
+    /// Sets the local CRS file, from which the keys are compiled instead of
+    /// from the CRS obtained from the node.
+    pub fn with_crs_path<P: AsRef<Path>>(mut self, crs_path: P) -> Self {
+        self.crs_config.crs_path = Some(crs_path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sets the pinned CRS hash and the local CRS file given in the config.
+    pub fn with_config(
+        mut self,
+        config: &BlockchainAccessConfig,
+    ) -> Result<Self, Error> {
+        if let Some(crs_hash) = config.pinned_crs_hash()? {
+            self = self.with_crs_hash(crs_hash);
+        }
+        if let Some(crs_path) = &config.crs_path {
+            self = self.with_crs_path(crs_path);
+        }
+        Ok(self)
+    }
+
+    pub fn dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
+    pub fn prover_path(&self) -> Option<PathBuf> {
+        self.dir.as_ref().map(|dir| dir.join(PROVER_KEY_FILE_NAME))
+    }
+
+    pub fn verifier_path(&self) -> Option<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(VERIFIER_KEY_FILE_NAME))
+    }
+
+    /// Provides the circuit keys, loading them from the cache directory or,
+    /// if they are missing or not valid, compiling them from the local CRS
+    /// file if one is set, or from the CRS obtained from the node.
+    pub async fn get(
+        &mut self,
+        client: &RuskHttpClient,
+    ) -> Result<&CircuitKeys, Error> {
+        if self.keys.is_none() && self.dir.is_some() {
+            let crs_hash = self.crs_hash_in_use(client).await?;
+            self.keys = self.load(&crs_hash);
+        }
+        if self.keys.is_none() {
+            let crs =
+                CrsGetter::get_configured_crs(client, &self.crs_config).await?;
+            self.compile(crs.as_slice())?;
+        }
+        Ok(self.keys.as_ref().expect("circuit keys are not empty"))
+    }
+
+    /// Provides the circuit keys without contacting a node, loading them from
+    /// the cache directory or compiling them from the local CRS file.
+    pub fn get_offline(&mut self) -> Result<&CircuitKeys, Error> {
+        if self.keys.is_none() {
+            let crs = match &self.crs_config.crs_path {
+                Some(crs_path) => Some(CrsGetter::load_crs(crs_path)?),
+                None => None,
+            };
+            let crs_hash = match (self.pinned_crs_hash(), &crs) {
+                (Some(pinned_hash), _) => pinned_hash,
+                (None, Some(crs)) => CrsGetter::hash_of_bytes(crs),
+                (None, None) => {
+                    return Err(Error::CRS("local CRS file not set".into()))
+                }
+            };
+            self.keys = self.load(&crs_hash);
+            if self.keys.is_none() {
+                let crs =
+                    crs.ok_or(Error::CRS("local CRS file not set".into()))?;
+                self.compile(crs.as_slice())?;
+            }
+        }
+        Ok(self.keys.as_ref().expect("circuit keys are not empty"))
+    }
+
+    /// Compiles the circuit keys from given CRS bytes, and saves them in the
+    /// cache directory. Fails if the CRS does not match the pinned hash, or
+    /// if no hash is pinned and the CRS is not valid.
+    pub fn compile(&mut self, crs: &[u8]) -> Result<&CircuitKeys, Error> {
+        let crs_hash = CrsGetter::hash_of_bytes(crs);
+        let pp = match self.pinned_crs_hash() {
+            Some(pinned_hash) => {
+                CrsGetter::verify_crs(crs, &pinned_hash)?;
+                // SAFETY: CRS bytes match the pinned known-good hash
+                unsafe { PublicParameters::from_slice_unchecked(crs) }
+            }
+            // CRS which is not pinned is checked while deserializing
+            None => PublicParameters::from_slice(crs)
+                .map_err(|_| Error::CRS("invalid CRS".into()))?,
+        };
+        let (prover, verifier) =
+            Compiler::compile::<LicenseCircuit>(&pp, &self.label)?;
+
+        if let (Some(prover_path), Some(verifier_path)) =
+            (self.prover_path(), self.verifier_path())
+        {
+            let prover_bytes = prover.to_bytes();
+            let verifier_bytes = verifier.to_bytes();
+            AtomicFile::write(
+                prover_path,
+                self.key_file_bytes(&crs_hash, &prover_bytes),
+            )?;
+            AtomicFile::write(
+                verifier_path,
+                self.key_file_bytes(&crs_hash, &verifier_bytes),
+            )?;
+        }
+        self.keys = Some(CircuitKeys { prover, verifier });
+        Ok(self.keys.as_ref().expect("circuit keys are not empty"))
+    }
+
+    /// Loads the circuit keys compiled from the CRS with a given hash from
+    /// the cache directory, returns None if they are missing, corrupted, or
+    /// compiled from a different CRS or label.
+    pub fn load(&self, crs_hash: &[u8; 32]) -> Option<CircuitKeys> {
+        let prover_bytes =
+            self.read_key_file(&self.prover_path()?, crs_hash)?;
+        let verifier_bytes =
+            self.read_key_file(&self.verifier_path()?, crs_hash)?;
+        let prover = Prover::try_from_bytes(prover_bytes).ok()?;
+        let verifier = Verifier::try_from_bytes(verifier_bytes).ok()?;
+        Some(CircuitKeys { prover, verifier })
+    }
+
+    /// Removes circuit keys from memory and from the cache directory.
+    pub fn clear(&mut self) -> Result<(), Error> {
+        self.keys = None;
+        for path in [self.prover_path(), self.verifier_path()]
+            .into_iter()
+            .flatten()
+        {
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn key_file_bytes(&self, crs_hash: &Hash, key_bytes: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_SIZE + key_bytes.len());
+        bytes.extend_from_slice(crs_hash);
+        bytes.extend_from_slice(&Self::hash(&self.label));
+        bytes.extend_from_slice(&Self::hash(key_bytes));
+        bytes.extend_from_slice(key_bytes);
+        bytes
+    }
+
+    fn read_key_file(&self, path: &Path, crs_hash: &Hash) -> Option<Vec<u8>> {
+        let mut bytes = fs::read(path).ok()?;
+        if bytes.len() < HEADER_SIZE {
+            return None;
+        }
+        let key_bytes = bytes.split_off(HEADER_SIZE);
+        let (file_crs_hash, rest) = bytes.split_at(HASH_SIZE);
+        let (label_hash, key_hash) = rest.split_at(HASH_SIZE);
+        if file_crs_hash == crs_hash
+            && label_hash == Self::hash(&self.label)
+            && key_hash == Self::hash(&key_bytes)
+        {
+            Some(key_bytes)
+        } else {
+            None
+        }
+    }
+
+    fn pinned_crs_hash(&self) -> Option<Hash> {
+        self.crs_config
+            .pinned_crs_hash()
+            .expect("pinned CRS hash is validated when set")
+    }
+
+    /// Provides hash of the CRS in use, the pinned hash if set, otherwise
+    /// the hash of the local CRS file if set, or the hash of the CRS of the
+    /// node.
+    async fn crs_hash_in_use(
+        &self,
+        client: &RuskHttpClient,
+    ) -> Result<Hash, Error> {
+        if let Some(pinned_hash) = self.pinned_crs_hash() {
+            return Ok(pinned_hash);
+        }
+        match &self.crs_config.crs_path {
+            Some(crs_path) => {
+                Ok(CrsGetter::hash_of_bytes(CrsGetter::load_crs(crs_path)?))
+            }
+            None => CrsGetter::get_crs_hash(client).await,
+        }
+    }
+
+    fn hash(bytes: &[u8]) -> Hash {
+        let mut hasher = Sha3_256::new();
+        hasher.update(bytes);
+        hasher.finalize().into()
+    }
+}
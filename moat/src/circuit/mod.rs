@@ -4,6 +4,11 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+mod key_store;
 mod license_circuit;
 
+pub use key_store::{
+    CircuitKeyStore, CircuitKeys, LICENSE_CIRCUIT_LABEL, PROVER_KEY_FILE_NAME,
+    VERIFIER_KEY_FILE_NAME,
+};
 pub use license_circuit::{LicenseCircuit, ARITY, DEPTH};
@@ -0,0 +1,98 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::atomic_file::AtomicFile;
+use crate::license_provider::LPConfig;
+use crate::wallet_accessor::{Password, WalletAccessor};
+use crate::{Error, JsonLoader};
+use dusk_bytes::{DeserializableSlice, Serializable};
+use dusk_pki::{PublicSpendKey, SecretSpendKey};
+use dusk_wallet::{Wallet, WalletPath};
+use rand::{CryptoRng, RngCore};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Keypair of a Citadel role, such as License Provider or Service
+/// Provider, which does not need to be the wallet's default address.
+/// Keys are stored in JSON files in the `LPConfig` format, with hex encoded
+/// public and secret spend keys.
+#[derive(Debug, Clone, Copy)]
+pub struct CitadelKeys {
+    pub psk: PublicSpendKey,
+    pub ssk: SecretSpendKey,
+}
+
+impl CitadelKeys {
+    pub fn from_ssk(ssk: &SecretSpendKey) -> Self {
+        Self {
+            psk: ssk.public_spend_key(),
+            ssk: *ssk,
+        }
+    }
+
+    /// Generates fresh keys, unrelated to any wallet.
+    pub fn random<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        Self::from_ssk(&SecretSpendKey::random(rng))
+    }
+
+    /// Derives keys from the wallet address with a given index, or from the
+    /// default address if no index is given.
+    pub fn from_wallet(
+        wallet_path: &WalletPath,
+        password: &Password,
+        address_index: Option<usize>,
+    ) -> Result<Self, Error> {
+        let wallet_accessor =
+            WalletAccessor::create(wallet_path.clone(), password.clone())?;
+        let wallet = Wallet::from_file(wallet_accessor)?;
+        let address = WalletAccessor::address(&wallet, address_index)?;
+        let (_psk, ssk) = wallet
+            .spending_keys(address)
+            .map_err(|e| Error::DuskWallet(Arc::from(e)))?;
+        Ok(Self::from_ssk(&ssk))
+    }
+
+    /// Loads keys from a JSON file. The public spend key may be left empty,
+    /// otherwise it needs to match the secret spend key.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let config: LPConfig = LPConfig::from_file(path)?;
+        Self::from_config(&config)
+    }
+
+    pub fn from_config(config: &LPConfig) -> Result<Self, Error> {
+        let ssk_bytes = hex::decode(&config.ssk_lp)?;
+        let keys =
+            Self::from_ssk(&SecretSpendKey::from_slice(ssk_bytes.as_slice())?);
+        if !config.psk_lp.is_empty() && config.psk_lp != keys.psk_hex() {
+            return Err(Error::LocalState(
+                "public spend key does not match secret spend key".into(),
+            ));
+        }
+        Ok(keys)
+    }
+
+    pub fn to_config(&self) -> LPConfig {
+        LPConfig {
+            psk_lp: self.psk_hex(),
+            ssk_lp: hex::encode(self.ssk.to_bytes()),
+        }
+    }
+
+    /// Saves keys to a JSON file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let json = serde_json::to_vec_pretty(&self.to_config())?;
+        AtomicFile::write(path, json)
+    }
+
+    pub fn psk_hex(&self) -> String {
+        hex::encode(self.psk.to_bytes())
+    }
+
+    /// Public spend key in the bs58 format used for addresses.
+    pub fn psk_bs58(&self) -> String {
+        bs58::encode(self.psk.to_bytes()).into_string()
+    }
+}
@@ -0,0 +1,169 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::error::Error;
+use crate::JsonLoader;
+use dusk_bytes::Serializable;
+use dusk_jubjub::JubJubScalar;
+
+/// Version of the encoding used by `DefaultAttributeEncoder`.
+pub const ATTRIBUTES_ENCODING_VERSION: u8 = 1;
+
+/// Maps structured claims into the attribute data committed in a license,
+/// and back.
+/// License Providers encode claims when issuing licenses, Service Providers
+/// decode them to learn what has been attested.
+pub trait AttributeEncoder {
+    type Claims;
+
+    fn encode(&self, claims: &Self::Claims) -> Result<JubJubScalar, Error>;
+
+    fn decode(&self, attr_data: &JubJubScalar) -> Result<Self::Claims, Error>;
+}
+
+/// Age bracket of the license holder.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    serde::Deserialize,
+    serde::Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+#[repr(u8)]
+pub enum AgeBracket {
+    #[default]
+    Unspecified = 0,
+    Under18 = 1,
+    From18To20 = 2,
+    From21To64 = 3,
+    From65 = 4,
+}
+
+impl TryFrom<u8> for AgeBracket {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Ok(match value {
+            0 => AgeBracket::Unspecified,
+            1 => AgeBracket::Under18,
+            2 => AgeBracket::From18To20,
+            3 => AgeBracket::From21To64,
+            4 => AgeBracket::From65,
+            _ => {
+                return Err(Error::AttributeEncoding(
+                    "unknown age bracket".into(),
+                ))
+            }
+        })
+    }
+}
+
+/// Claims attested by the License Provider.
+/// Jurisdiction is an ISO 3166-1 alpha-2 country code, or empty if not
+/// specified. Expiry is a unix timestamp in seconds, or None if the license
+/// does not expire.
+#[derive(
+    Debug, Default, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize,
+)]
+pub struct LicenseClaims {
+    pub age_bracket: AgeBracket,
+    pub jurisdiction: String,
+    pub kyc_tier: u8,
+    pub expiry: Option<u64>,
+}
+
+impl JsonLoader for LicenseClaims {}
+
+impl LicenseClaims {
+    /// Returns true if the claims have expired at a given unix timestamp.
+    pub fn is_expired(&self, now: u64) -> bool {
+        matches!(self.expiry, Some(expiry) if expiry <= now)
+    }
+}
+
+/// Encoder packing `LicenseClaims` into the lower 128 bits of the scalar,
+/// as little endian bytes:
+/// - byte 0: encoding version
+/// - byte 1: age bracket
+/// - bytes 2..4: jurisdiction, as ASCII
+/// - byte 4: KYC tier
+/// - bytes 5..8: reserved, zero
+/// - bytes 8..16: expiry, zero if none
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DefaultAttributeEncoder;
+
+impl AttributeEncoder for DefaultAttributeEncoder {
+    type Claims = LicenseClaims;
+
+    fn encode(&self, claims: &LicenseClaims) -> Result<JubJubScalar, Error> {
+        let jurisdiction = claims.jurisdiction.as_bytes();
+        let jurisdiction = match jurisdiction.len() {
+            0 => [0u8; 2],
+            2 if jurisdiction.iter().all(u8::is_ascii_uppercase) => {
+                [jurisdiction[0], jurisdiction[1]]
+            }
+            _ => {
+                return Err(Error::AttributeEncoding(
+                    "jurisdiction must be an ISO 3166-1 alpha-2 code".into(),
+                ))
+            }
+        };
+        let expiry = match claims.expiry {
+            Some(0) => {
+                return Err(Error::AttributeEncoding(
+                    "expiry must be greater than zero".into(),
+                ))
+            }
+            Some(expiry) => expiry,
+            None => 0,
+        };
+        let mut bytes = [0u8; JubJubScalar::SIZE];
+        bytes[0] = ATTRIBUTES_ENCODING_VERSION;
+        bytes[1] = claims.age_bracket as u8;
+        bytes[2..4].copy_from_slice(&jurisdiction);
+        bytes[4] = claims.kyc_tier;
+        bytes[8..16].copy_from_slice(&expiry.to_le_bytes());
+        Ok(JubJubScalar::from_bytes(&bytes)?)
+    }
+
+    fn decode(&self, attr_data: &JubJubScalar) -> Result<LicenseClaims, Error> {
+        let bytes = attr_data.to_bytes();
+        if bytes[0] != ATTRIBUTES_ENCODING_VERSION {
+            return Err(Error::AttributeEncoding(
+                "unsupported encoding version".into(),
+            ));
+        }
+        if bytes[5..8].iter().chain(&bytes[16..]).any(|b| *b != 0) {
+            return Err(Error::AttributeEncoding(
+                "reserved bits are set".into(),
+            ));
+        }
+        let jurisdiction = match &bytes[2..4] {
+            [0, 0] => String::new(),
+            [a, b] if a.is_ascii_uppercase() && b.is_ascii_uppercase() => {
+                String::from_utf8_lossy(&bytes[2..4]).into_owned()
+            }
+            _ => {
+                return Err(Error::AttributeEncoding(
+                    "invalid jurisdiction".into(),
+                ))
+            }
+        };
+        let mut expiry_bytes = [0u8; 8];
+        expiry_bytes.copy_from_slice(&bytes[8..16]);
+        let expiry = u64::from_le_bytes(expiry_bytes);
+        Ok(LicenseClaims {
+            age_bracket: AgeBracket::try_from(bytes[1])?,
+            jurisdiction,
+            kyc_tier: bytes[4],
+            expiry: (expiry != 0).then_some(expiry),
+        })
+    }
+}
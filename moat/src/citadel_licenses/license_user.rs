@@ -6,8 +6,8 @@
 
 use crate::wallet_accessor::{BlockchainAccessConfig, Password};
 use crate::{
-    Error, LicenseCircuit, PayloadSender, LICENSE_CONTRACT_ID,
-    USE_LICENSE_METHOD_NAME,
+    Error, LicenseCircuit, PayloadSender, UseLicenseBundle,
+    LICENSE_CONTRACT_ID, USE_LICENSE_METHOD_NAME,
 };
 use crate::{ARITY, DEPTH};
 use bytecheck::CheckBytes;
@@ -18,6 +18,7 @@ use dusk_plonk::prelude::{Proof, Prover, Verifier};
 use dusk_wallet::WalletPath;
 use poseidon_merkle::Opening;
 use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
 use rkyv::{Archive, Deserialize, Serialize};
 use zk_citadel::license::{CitadelProverParameters, License, SessionCookie};
 
@@ -52,6 +53,87 @@ impl LicenseUser {
         gas_limit: u64,
         gas_price: u64,
     ) -> Result<(BlsScalar, SessionCookie), Error> {
+        let bundle = Self::create_use_license_bundle(
+            ssk_user, psk_lp, psk_sp, prover, verifier, license, opening, rng,
+            challenge,
+        )?;
+        let tx_id = Self::broadcast_use_license(
+            blockchain_config,
+            wallet_path,
+            password,
+            &bundle,
+            gas_limit,
+            gas_price,
+        )
+        .await?;
+        Ok((tx_id, bundle.session_cookie))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    /// Calculates and verifies proof of the use of a license, without
+    /// contacting the blockchain. Merkle opening of the license needs to be
+    /// obtained beforehand.
+    /// Returns a bundle to be broadcast with `broadcast_use_license`.
+    pub fn create_use_license_bundle<R: RngCore + CryptoRng>(
+        ssk_user: &SecretSpendKey,
+        psk_lp: &PublicSpendKey,
+        psk_sp: &PublicSpendKey,
+        prover: &Prover,
+        verifier: &Verifier,
+        license: &License,
+        opening: Opening<(), DEPTH, ARITY>,
+        rng: &mut R,
+        challenge: &JubJubScalar,
+    ) -> Result<UseLicenseBundle, Error> {
+        let (use_license_arg, session_cookie) = Self::prove_license(
+            ssk_user, psk_lp, psk_sp, prover, verifier, license, opening, rng,
+            challenge,
+        )?;
+        Ok(UseLicenseBundle {
+            use_license_arg,
+            session_cookie,
+        })
+    }
+
+    /// Sends a previously created use license bundle to the license
+    /// contract's use_license method, paid for by a given wallet.
+    /// Returns transaction id.
+    pub async fn broadcast_use_license(
+        blockchain_config: &BlockchainAccessConfig,
+        wallet_path: &WalletPath,
+        password: &Password,
+        bundle: &UseLicenseBundle,
+        gas_limit: u64,
+        gas_price: u64,
+    ) -> Result<BlsScalar, Error> {
+        PayloadSender::execute_contract_method(
+            bundle.use_license_arg.clone(),
+            blockchain_config,
+            wallet_path,
+            password,
+            gas_limit,
+            gas_price,
+            LICENSE_CONTRACT_ID,
+            USE_LICENSE_METHOD_NAME,
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    /// Calculates and verifies proof of the use of a license.
+    /// Returns argument of the license contract's use_license method and a
+    /// session cookie.
+    pub fn prove_license<R: RngCore + CryptoRng>(
+        ssk_user: &SecretSpendKey,
+        psk_lp: &PublicSpendKey,
+        psk_sp: &PublicSpendKey,
+        prover: &Prover,
+        verifier: &Verifier,
+        license: &License,
+        opening: Opening<(), DEPTH, ARITY>,
+        rng: &mut R,
+        challenge: &JubJubScalar,
+    ) -> Result<(UseLicenseArg, SessionCookie), Error> {
         let (cpp, sc) = CitadelProverParameters::compute_parameters(
             ssk_user, license, psk_lp, psk_sp, challenge, rng, opening,
         );
@@ -70,18 +152,6 @@ impl LicenseUser {
             proof,
             public_inputs,
         };
-
-        let tx_id = PayloadSender::execute_contract_method(
-            use_license_arg,
-            blockchain_config,
-            wallet_path,
-            password,
-            gas_limit,
-            gas_price,
-            LICENSE_CONTRACT_ID,
-            USE_LICENSE_METHOD_NAME,
-        )
-        .await?;
-        Ok((tx_id, sc))
+        Ok((use_license_arg, sc))
     }
 }
@@ -4,6 +4,13 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+mod license_attributes;
 mod license_user;
+mod use_license_bundle;
 
-pub use license_user::LicenseUser;
+pub use license_attributes::{
+    AgeBracket, AttributeEncoder, DefaultAttributeEncoder, LicenseClaims,
+    ATTRIBUTES_ENCODING_VERSION,
+};
+pub use license_user::{LicenseUser, UseLicenseArg};
+pub use use_license_bundle::UseLicenseBundle;
@@ -0,0 +1,40 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::{Error, MoatCoreUtils, UseLicenseArg};
+use bytecheck::CheckBytes;
+use rkyv::{Archive, Deserialize, Serialize};
+use zk_citadel::license::SessionCookie;
+
+/// Proof of the use of a license, created offline, along with the session
+/// cookie to be presented to the Service Provider.
+/// Bundles can be serialized and broadcast later from any machine, so that
+/// the user's secret key never needs to be on a connected device.
+#[derive(Debug, Clone, Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes))]
+pub struct UseLicenseBundle {
+    pub use_license_arg: UseLicenseArg,
+    pub session_cookie: SessionCookie,
+}
+
+impl UseLicenseBundle {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        MoatCoreUtils::to_blob(self)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        rkyv::from_bytes(bytes)
+            .map_err(|_| Error::LocalState("invalid use license bundle".into()))
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.to_bytes())
+    }
+
+    pub fn from_hex(bundle_hex: impl AsRef<[u8]>) -> Result<Self, Error> {
+        Self::from_bytes(hex::decode(bundle_hex)?.as_slice())
+    }
+}
@@ -14,6 +14,7 @@ use crate::{
     LICENSE_CONTRACT_ID,
 };
 use bytes::Bytes;
+use dusk_bls12_381::BlsScalar;
 use dusk_pki::SecretSpendKey;
 use dusk_wallet::RuskHttpClient;
 use poseidon_merkle::Opening;
@@ -62,6 +63,20 @@ impl CitadelInquirer {
         .await
     }
 
+    /// Provides current root of the licenses merkle tree, or None if no
+    /// license has been issued yet.
+    pub async fn get_merkle_root(
+        client: &RuskHttpClient,
+    ) -> Result<Option<BlsScalar>, Error> {
+        let (num_licenses, _, _) = Self::get_info(client).await?;
+        if num_licenses == 0 {
+            return Ok(None);
+        }
+        let opening =
+            Self::get_merkle_opening(client, num_licenses as u64 - 1).await?;
+        Ok(opening.map(|opening| opening.root().hash))
+    }
+
     /// Provides session with a given session id, or None if not found.
     pub async fn get_session(
         client: &RuskHttpClient,
@@ -0,0 +1,192 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::atomic_file::AtomicFile;
+use crate::utils::MoatCoreUtils;
+use crate::{BcInquirer, CitadelInquirer, Error, JsonLoader};
+use dusk_pki::SecretSpendKey;
+use dusk_wallet::{RuskHttpClient, WalletPath};
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use tracing::{trace, warn};
+use zk_citadel::license::License;
+
+pub const LICENSE_CACHE_FILE_NAME: &str = "moat_licenses.json";
+
+/// Serialized form of the license cache.
+/// Licenses are kept as hex encoded rkyv blobs, keyed by their position in
+/// the license contract's merkle tree.
+#[derive(Debug, Default, Clone, serde::Deserialize, serde::Serialize)]
+struct LicenseCacheFile {
+    next_height: u64,
+    licenses: BTreeMap<u64, String>,
+}
+
+impl JsonLoader for LicenseCacheFile {}
+
+/// Local index of licenses, filled incrementally by block height ranges.
+/// Licenses are indexed by position and by license hash.
+#[derive(Debug, Default)]
+pub struct LicenseCache {
+    path: Option<PathBuf>,
+    next_height: u64,
+    licenses: BTreeMap<u64, License>,
+    positions: BTreeMap<String, u64>,
+}
+
+impl LicenseCache {
+    /// Creates an empty cache which is not persisted.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads cache from a given file, or creates an empty one if the file
+    /// does not exist. A corrupted file is discarded and the cache rebuilt.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        let mut cache = Self {
+            path: Some(path.clone()),
+            ..Self::default()
+        };
+        if !path.exists() {
+            return Ok(cache);
+        }
+        let cache_file: LicenseCacheFile =
+            match LicenseCacheFile::from_file(&path) {
+                Ok(cache_file) => cache_file,
+                Err(e) => {
+                    warn!("discarding license cache {:?}: {}", path, e);
+                    return Ok(cache);
+                }
+            };
+        for (pos, license_hex) in cache_file.licenses.iter() {
+            match Self::license_from_hex(license_hex) {
+                Ok(license) => cache.insert(*pos, license),
+                Err(e) => {
+                    warn!("discarding license cache {:?}: {}", path, e);
+                    cache.clear();
+                    return Ok(cache);
+                }
+            }
+        }
+        cache.next_height = cache_file.next_height;
+        Ok(cache)
+    }
+
+    /// Loads cache placed in the wallet directory, or creates a cache which
+    /// is not persisted if the wallet path has no directory.
+    pub fn load_from_wallet_dir(
+        wallet_path: &WalletPath,
+    ) -> Result<Self, Error> {
+        match wallet_path.dir() {
+            Some(dir) => Self::load(dir.join(LICENSE_CACHE_FILE_NAME)),
+            None => Ok(Self::new()),
+        }
+    }
+
+    /// Fetches licenses issued since the last sync and saves the cache.
+    /// If the blockchain height went backwards, the cache is rebuilt.
+    /// Returns the block height range which has been fetched.
+    pub async fn sync(
+        &mut self,
+        client: &RuskHttpClient,
+    ) -> Result<Range<u64>, Error> {
+        let end_height = BcInquirer::block_height(client).await?;
+        if end_height + 1 < self.next_height {
+            warn!(
+                "block height went backwards to {}, rebuilding license cache",
+                end_height
+            );
+            self.clear();
+        }
+        let block_range = self.next_height..(end_height + 1);
+        if block_range.is_empty() {
+            return Ok(block_range);
+        }
+        trace!("syncing license cache within range {:?}", block_range);
+        let mut licenses_stream =
+            CitadelInquirer::get_licenses(client, block_range.clone()).await?;
+        let pairs = CitadelInquirer::find_all_licenses(&mut licenses_stream)?;
+        for (pos, license) in pairs.into_iter() {
+            self.insert(pos, license);
+        }
+        self.next_height = block_range.end;
+        self.save()?;
+        Ok(block_range)
+    }
+
+    /// Block height range covered by the cache.
+    pub fn synced_range(&self) -> Range<u64> {
+        0..self.next_height
+    }
+
+    /// All cached licenses along with their positions.
+    pub fn licenses(&self) -> impl Iterator<Item = (u64, &License)> {
+        self.licenses.iter().map(|(pos, license)| (*pos, license))
+    }
+
+    /// Cached licenses owned by a given secret key, along with their
+    /// positions.
+    pub fn owned_licenses(&self, ssk: &SecretSpendKey) -> Vec<(u64, License)> {
+        let vk = ssk.view_key();
+        self.licenses()
+            .filter(|(_, license)| vk.owns(&license.lsa))
+            .map(|(pos, license)| (pos, license.clone()))
+            .collect()
+    }
+
+    /// License at a given position, or None if not found.
+    pub fn get(&self, pos: u64) -> Option<&License> {
+        self.licenses.get(&pos)
+    }
+
+    /// License with a given license hash along with its position, or None if
+    /// not found.
+    pub fn get_by_hash(
+        &self,
+        license_hash: impl AsRef<str>,
+    ) -> Option<(u64, &License)> {
+        self.positions
+            .get(license_hash.as_ref())
+            .and_then(|pos| self.licenses.get(pos).map(|l| (*pos, l)))
+    }
+
+    fn insert(&mut self, pos: u64, license: License) {
+        self.positions
+            .insert(MoatCoreUtils::to_hash_hex(&license), pos);
+        self.licenses.insert(pos, license);
+    }
+
+    fn clear(&mut self) {
+        self.next_height = 0;
+        self.licenses.clear();
+        self.positions.clear();
+    }
+
+    fn save(&self) -> Result<(), Error> {
+        if let Some(path) = self.path.as_ref() {
+            let cache_file = LicenseCacheFile {
+                next_height: self.next_height,
+                licenses: self
+                    .licenses
+                    .iter()
+                    .map(|(pos, license)| {
+                        (*pos, MoatCoreUtils::to_blob_hex(license))
+                    })
+                    .collect(),
+            };
+            AtomicFile::write(path, serde_json::to_vec(&cache_file)?)?;
+        }
+        Ok(())
+    }
+
+    fn license_from_hex(license_hex: &str) -> Result<License, Error> {
+        let license_blob = hex::decode(license_hex)?;
+        rkyv::from_bytes(license_blob.as_slice())
+            .map_err(|_| Error::LocalState("invalid cached license".into()))
+    }
+}
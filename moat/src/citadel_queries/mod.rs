@@ -7,7 +7,9 @@
 mod citadel_inquirer;
 mod citadel_inquirer_ws;
 mod citadel_types;
+mod license_cache;
 
 pub use citadel_inquirer::CitadelInquirer;
 pub use citadel_inquirer_ws::CitadelInquirerWs;
 pub use citadel_types::{LicenseSession, LicenseSessionId};
+pub use license_cache::{LicenseCache, LICENSE_CACHE_FILE_NAME};
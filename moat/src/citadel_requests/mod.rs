@@ -4,10 +4,18 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+mod rejection_notice;
 mod request_creator;
+mod request_history;
 mod request_scanner;
 mod request_sender;
 
+pub use rejection_notice::RejectionNotice;
 pub use request_creator::RequestCreator;
-pub use request_scanner::RequestScanner;
+pub use request_history::{
+    RequestHistory, RequestStatus, REQUEST_HISTORY_FILE_NAME,
+};
+pub use request_scanner::{
+    RequestScanner, TrackedScan, WatchConfig, BLOCKS_RANGE_LEN,
+};
 pub use request_sender::RequestSender;
@@ -0,0 +1,105 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::error::Error;
+use crate::{JsonLoader, MoatCoreUtils};
+use dusk_bytes::{DeserializableSlice, Serializable};
+use dusk_jubjub::{
+    JubJubAffine, JubJubExtended, JubJubScalar, GENERATOR_EXTENDED,
+};
+use dusk_pki::{SecretSpendKey, StealthAddress, ViewKey};
+use rand::{CryptoRng, RngCore};
+use zk_citadel::license::{License, Request};
+
+const NOTICE_KEY_CONTEXT: &str = "moat 2023 license request rejection notice";
+
+/// Notice informing the user that a license request has been rejected by
+/// the License Provider, to be delivered off-chain.
+/// The reason is encrypted for the license stealth address contained in the
+/// request, so that only the user who created the request can read it.
+/// All fields except the request hash are hex encoded.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct RejectionNotice {
+    pub request_hash: String,
+    pub lsa: String,
+    pub ephemeral_key: String,
+    pub enc_reason: String,
+}
+
+impl JsonLoader for RejectionNotice {}
+
+impl RejectionNotice {
+    /// Creates notice for a given request and rejection reason.
+    pub fn create<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        request: &Request,
+        ssk_lp: &SecretSpendKey,
+        reason: &str,
+    ) -> Self {
+        // license is not issued, it only serves to decrypt the license
+        // stealth address from the request
+        let lsa = License::new(&JubJubScalar::zero(), ssk_lp, request, rng).lsa;
+        let r = JubJubScalar::random(rng);
+        let ephemeral_key = JubJubAffine::from(GENERATOR_EXTENDED * r);
+        let shared_key = JubJubAffine::from(lsa.pk_r().as_ref() * r);
+        let mut enc_reason = reason.as_bytes().to_vec();
+        Self::apply_keystream(&shared_key, enc_reason.as_mut_slice());
+        Self {
+            request_hash: MoatCoreUtils::to_hash_hex(request),
+            lsa: hex::encode(lsa.to_bytes()),
+            ephemeral_key: hex::encode(ephemeral_key.to_bytes()),
+            enc_reason: hex::encode(enc_reason),
+        }
+    }
+
+    /// Returns true if the notice concerns a request created by the owner
+    /// of a given view key.
+    pub fn is_addressed_to(&self, vk: &ViewKey) -> Result<bool, Error> {
+        Ok(vk.owns(&self.lsa()?))
+    }
+
+    /// Decrypts reason of the rejection, returns None if the notice is not
+    /// addressed to the owner of a given secret spend key.
+    pub fn decrypt_reason(
+        &self,
+        ssk_user: &SecretSpendKey,
+    ) -> Result<Option<String>, Error> {
+        let lsa = self.lsa()?;
+        if !ssk_user.view_key().owns(&lsa) {
+            return Ok(None);
+        }
+        let lsk = ssk_user.sk_r(&lsa);
+        let ephemeral_key = JubJubAffine::from_slice(
+            hex::decode(&self.ephemeral_key)?.as_slice(),
+        )?;
+        let shared_key = JubJubAffine::from(
+            JubJubExtended::from(ephemeral_key) * lsk.as_ref(),
+        );
+        let mut reason = hex::decode(&self.enc_reason)?;
+        Self::apply_keystream(&shared_key, reason.as_mut_slice());
+        String::from_utf8(reason).map(Some).map_err(|_| {
+            Error::LocalState("invalid rejection notice reason".into())
+        })
+    }
+
+    fn lsa(&self) -> Result<StealthAddress, Error> {
+        let lsa =
+            StealthAddress::from_slice(hex::decode(&self.lsa)?.as_slice())?;
+        Ok(lsa)
+    }
+
+    fn apply_keystream(shared_key: &JubJubAffine, data: &mut [u8]) {
+        let key =
+            blake3::derive_key(NOTICE_KEY_CONTEXT, &shared_key.to_bytes());
+        let mut keystream = vec![0u8; data.len()];
+        blake3::Hasher::new_keyed(&key)
+            .finalize_xof()
+            .fill(keystream.as_mut_slice());
+        data.iter_mut()
+            .zip(keystream)
+            .for_each(|(byte, key_byte)| *byte ^= key_byte);
+    }
+}
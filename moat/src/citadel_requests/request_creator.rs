@@ -7,7 +7,7 @@
 use crate::error::Error;
 use dusk_bytes::DeserializableSlice;
 use dusk_jubjub::{JubJubAffine, JubJubScalar, GENERATOR_EXTENDED};
-use dusk_pki::{PublicSpendKey, SecretSpendKey};
+use dusk_pki::{PublicSpendKey, SecretSpendKey, StealthAddress};
 use dusk_poseidon::sponge;
 use rand::{CryptoRng, RngCore};
 use zk_citadel::license::Request;
@@ -22,6 +22,17 @@ impl RequestCreator {
         psk_lp: &PublicSpendKey,
         rng: &mut R,
     ) -> Result<Request, Error> {
+        Self::create_with_lsa(ssk_user, psk_lp, rng).map(|(request, _)| request)
+    }
+
+    /// Creates request like `create`, returns it along with the license
+    /// stealth address encrypted in the request. The license issued for the
+    /// request will have the same license stealth address.
+    pub fn create_with_lsa<R: RngCore + CryptoRng>(
+        ssk_user: &SecretSpendKey,
+        psk_lp: &PublicSpendKey,
+        rng: &mut R,
+    ) -> Result<(Request, StealthAddress), Error> {
         let psk = ssk_user.public_spend_key();
         let lsa = psk.gen_stealth_address(&JubJubScalar::random(rng));
         let lsk = ssk_user.sk_r(&lsa);
@@ -30,7 +41,7 @@ impl RequestCreator {
                 * sponge::truncated::hash(&[(*lsk.as_ref()).into()]),
         );
         let request = Request::new(psk_lp, &lsa, &k_lic, rng);
-        Ok(request)
+        Ok((request, lsa))
     }
 
     /// Creates request from user's secret spend key
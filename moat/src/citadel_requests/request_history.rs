@@ -0,0 +1,174 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::atomic_file::AtomicFile;
+use crate::utils::MoatCoreUtils;
+use crate::Error::TransactionNotFound;
+use crate::{Error, JsonLoader, LicenseCache, RequestReceipt, TxInquirer};
+use dusk_bytes::Serializable;
+use dusk_pki::ViewKey;
+use dusk_wallet::{RuskHttpClient, WalletPath};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+pub const REQUEST_HISTORY_FILE_NAME: &str = "moat_requests.json";
+
+/// Status of a license request submitted by the user.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct RequestStatus {
+    pub request_hash: String,
+    pub tx_id: String,
+    /// Height of the block containing the request, None if the request
+    /// transaction is not found on-chain.
+    pub block_height: Option<u64>,
+    /// Position of the license issued for the request, None if no license
+    /// has been issued yet.
+    pub license_pos: Option<u64>,
+    pub license_hash: Option<String>,
+}
+
+impl RequestStatus {
+    /// Determines status of a request with a given receipt. License issued
+    /// for the request is looked up in a given license cache, which is
+    /// expected to be synced.
+    pub async fn query(
+        client: &RuskHttpClient,
+        receipt: &RequestReceipt,
+        license_cache: &LicenseCache,
+    ) -> Result<Self, Error> {
+        let block_height =
+            match TxInquirer::retrieve_tx(&receipt.tx_id, client).await {
+                Ok((_, block_height)) => Some(block_height),
+                Err(TransactionNotFound) => None,
+                Err(e) => return Err(e),
+            };
+        let license = match receipt.lsa()? {
+            Some(lsa) => license_cache
+                .licenses()
+                .find(|(_, license)| license.lsa.to_bytes() == lsa.to_bytes()),
+            None => None,
+        };
+        Ok(Self {
+            request_hash: receipt.request_hash.clone(),
+            tx_id: receipt.tx_id.clone(),
+            block_height,
+            license_pos: license.map(|(pos, _)| pos),
+            license_hash: license
+                .map(|(_, license)| MoatCoreUtils::to_hash_hex(license)),
+        })
+    }
+
+    pub fn is_on_chain(&self) -> bool {
+        self.block_height.is_some()
+    }
+
+    pub fn is_licensed(&self) -> bool {
+        self.license_pos.is_some()
+    }
+}
+
+/// Serialized form of the request history, receipts keyed by request hash.
+#[derive(Debug, Default, Clone, serde::Deserialize, serde::Serialize)]
+struct RequestHistoryFile {
+    receipts: BTreeMap<String, RequestReceipt>,
+}
+
+impl JsonLoader for RequestHistoryFile {}
+
+/// Local record of the license requests submitted by the user, allowing
+/// to follow up on them.
+#[derive(Debug, Default)]
+pub struct RequestHistory {
+    path: Option<PathBuf>,
+    receipts: BTreeMap<String, RequestReceipt>,
+}
+
+impl RequestHistory {
+    /// Creates an empty history which is not persisted.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads history from a given file, or creates an empty one if the file
+    /// does not exist. A file which cannot be parsed is moved aside with
+    /// `.corrupt` appended to its name, so that it can be recovered, before
+    /// an empty history is started.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        let mut history = Self {
+            path: Some(path.clone()),
+            ..Self::default()
+        };
+        if !path.exists() {
+            return Ok(history);
+        }
+        match RequestHistoryFile::from_file(&path) {
+            Ok(history_file) => history.receipts = history_file.receipts,
+            Err(e) => {
+                let mut corrupt_name = path.as_os_str().to_os_string();
+                corrupt_name.push(".corrupt");
+                let corrupt_path = PathBuf::from(corrupt_name);
+                fs::rename(&path, &corrupt_path)?;
+                warn!(
+                    "moved corrupt request history {:?} to {:?}: {}",
+                    path, corrupt_path, e
+                );
+            }
+        }
+        Ok(history)
+    }
+
+    /// Loads history placed in the wallet directory, or creates a history
+    /// which is not persisted if the wallet path has no directory.
+    pub fn load_from_wallet_dir(
+        wallet_path: &WalletPath,
+    ) -> Result<Self, Error> {
+        match wallet_path.dir() {
+            Some(dir) => Self::load(dir.join(REQUEST_HISTORY_FILE_NAME)),
+            None => Ok(Self::new()),
+        }
+    }
+
+    /// Records receipt of a submitted request and saves the history.
+    pub fn insert(&mut self, receipt: RequestReceipt) -> Result<(), Error> {
+        self.receipts.insert(receipt.request_hash.clone(), receipt);
+        self.save()
+    }
+
+    /// Receipt of a request with a given request hash, or None if not
+    /// found.
+    pub fn get(
+        &self,
+        request_hash: impl AsRef<str>,
+    ) -> Option<&RequestReceipt> {
+        self.receipts.get(request_hash.as_ref())
+    }
+
+    /// All recorded receipts.
+    pub fn receipts(&self) -> impl Iterator<Item = &RequestReceipt> {
+        self.receipts.values()
+    }
+
+    /// Receipts of requests created by the owner of a given view key.
+    /// Receipts without license stealth address are not included.
+    pub fn owned_receipts(&self, vk: &ViewKey) -> Vec<&RequestReceipt> {
+        self.receipts()
+            .filter(|receipt| matches!(receipt.lsa(), Ok(Some(lsa)) if vk.owns(&lsa)))
+            .collect()
+    }
+
+    fn save(&self) -> Result<(), Error> {
+        if let Some(path) = self.path.as_ref() {
+            let history_file = RequestHistoryFile {
+                receipts: self.receipts.clone(),
+            };
+            AtomicFile::write(path, serde_json::to_vec(&history_file)?)?;
+        }
+        Ok(())
+    }
+}
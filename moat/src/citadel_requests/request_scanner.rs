@@ -6,15 +6,117 @@
 
 use crate::blockchain_payloads::PayloadExtractor;
 use crate::error::Error;
+use crate::utils::MoatCoreUtils;
 use crate::wallet_accessor::BlockchainAccessConfig;
-use crate::{Transactions, TxInquirer};
+use crate::{BcInquirer, BlockHashes, ForkCheck, Transactions, TxInquirer};
 use dusk_bls12_381::BlsScalar;
+use dusk_pki::ViewKey;
 use dusk_wallet::RuskHttpClient;
+use futures::stream::{self, Stream};
 use phoenix_core::Transaction;
+use std::collections::{BTreeMap, VecDeque};
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::trace;
 use zk_citadel::license::Request;
 
+/// Number of blocks scanned at once.
+pub const BLOCKS_RANGE_LEN: u64 = 10000;
+const DEFAULT_POLL_INTERVAL_SECS: u64 = 5;
+
 pub struct RequestScanner;
 
+/// Configuration of watching the blockchain for new requests.
+#[derive(Debug, Clone)]
+pub struct WatchConfig {
+    /// Interval between queries for new blocks, once the chain tip is
+    /// reached.
+    pub poll_interval: Duration,
+    /// Block height from which watching starts.
+    pub start_height: u64,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_secs(DEFAULT_POLL_INTERVAL_SECS),
+            start_height: 0,
+        }
+    }
+}
+
+struct WatchState {
+    vk: ViewKey,
+    client: RuskHttpClient,
+    poll_interval: Duration,
+    next_height: u64,
+    block_hashes: BlockHashes,
+    /// Heights of the blocks containing the yielded requests, keyed by
+    /// request hash.
+    found: BTreeMap<String, u64>,
+    pending: VecDeque<Request>,
+    at_tip: bool,
+}
+
+impl WatchState {
+    /// Scans the next block range, queueing newly found requests.
+    async fn poll(&mut self) -> Result<(), Error> {
+        let height_end = self.next_height + BLOCKS_RANGE_LEN;
+        let scan = RequestScanner::scan_block_range_tracked(
+            self.next_height,
+            height_end,
+            &self.client,
+            &mut self.block_hashes,
+        )
+        .await?;
+        match scan {
+            TrackedScan::Scanned { requests, top } => {
+                for (height, request) in requests {
+                    let request_hash = MoatCoreUtils::to_hash_hex(&request);
+                    if self.vk.owns(&request.rsa)
+                        && !self.found.contains_key(&request_hash)
+                    {
+                        self.found.insert(request_hash, height);
+                        self.pending.push_back(request);
+                    }
+                }
+                // blocks below the tracked ones are never scanned again,
+                // unless there is a fork with no tracked common ancestor
+                if let Some((lowest, _)) = self.block_hashes.first() {
+                    self.found.retain(|_, height| *height >= lowest);
+                }
+                // the last block of the range is scanned again next time,
+                // as it might not have been complete
+                self.next_height = height_end.min(top);
+                self.at_tip = top <= height_end;
+            }
+            TrackedScan::Fork { common_ancestor } => {
+                match common_ancestor {
+                    Some(height) => self.block_hashes.rollback(height),
+                    None => self.block_hashes.clear(),
+                }
+                self.next_height = common_ancestor.unwrap_or_default();
+                self.at_tip = false;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Result of scanning a block range with fork detection.
+#[derive(Debug)]
+pub enum TrackedScan {
+    /// Requests found along with heights of the blocks containing them, and
+    /// the current top block-height.
+    Scanned {
+        requests: Vec<(u64, Request)>,
+        top: u64,
+    },
+    /// Previously scanned blocks are no longer part of the chain, the caller
+    /// is expected to roll back to the common ancestor and rescan.
+    Fork { common_ancestor: Option<u64> },
+}
+
 impl RequestScanner {
     /// Returns requests found in the given collection of transactions.
     pub fn scan_transactions(txs: Transactions) -> Vec<Request> {
@@ -86,6 +188,80 @@ impl RequestScanner {
         Ok((requests, top))
     }
 
+    /// Scans block range like `scan_block_range`, first checking whether the
+    /// blocks tracked in `block_hashes` are still part of the chain.
+    /// Hashes of the newly scanned blocks are recorded in `block_hashes`.
+    pub async fn scan_block_range_tracked(
+        height_beg: u64,
+        height_end: u64,
+        client: &RuskHttpClient,
+        block_hashes: &mut BlockHashes,
+    ) -> Result<TrackedScan, Error> {
+        if let ForkCheck::Fork { common_ancestor } =
+            block_hashes.check_fork(client).await?
+        {
+            return Ok(TrackedScan::Fork { common_ancestor });
+        }
+        let (spent_txs, top) = TxInquirer::spent_txs_from_block_range(
+            client, height_beg, height_end,
+        )
+        .await?;
+        let mut requests = Vec::new();
+        for spent_tx in spent_txs.iter() {
+            block_hashes.record(spent_tx.block_height, &spent_tx.block_hash);
+            if let Ok(request) =
+                PayloadExtractor::payload_from_tx::<Request>(&spent_tx.tx)
+            {
+                requests.push((spent_tx.block_height, request))
+            }
+        }
+        // last block of the range is recorded even if it contains no
+        // transactions, so that the next scan can detect a fork
+        let last_height = height_end.min(top);
+        if let Some(hash) = BcInquirer::block_hash(client, last_height).await? {
+            block_hashes.record(last_height, hash);
+        }
+        Ok(TrackedScan::Scanned { requests, top })
+    }
+
+    /// Follows the chain tip and yields requests addressed to a given view
+    /// key as blocks arrive. Requests are yielded once, even if the blocks
+    /// containing them are scanned again after a fork, as long as the
+    /// common ancestor is among the tracked blocks. Errors are yielded
+    /// as they occur and watching continues with the next poll.
+    /// The returned stream never ends.
+    pub fn watch(
+        vk: ViewKey,
+        cfg: &BlockchainAccessConfig,
+        watch_config: WatchConfig,
+    ) -> impl Stream<Item = Result<Request, Error>> {
+        let state = WatchState {
+            vk,
+            client: RuskHttpClient::new(cfg.rusk_address.clone()),
+            poll_interval: watch_config.poll_interval,
+            next_height: watch_config.start_height,
+            block_hashes: BlockHashes::new(),
+            found: BTreeMap::new(),
+            pending: VecDeque::new(),
+            at_tip: false,
+        };
+        stream::unfold(state, |mut state| async move {
+            loop {
+                if let Some(request) = state.pending.pop_front() {
+                    return Some((Ok(request), state));
+                }
+                if state.at_tip {
+                    trace!("watching requests from {}", state.next_height);
+                    sleep(state.poll_interval).await;
+                }
+                if let Err(e) = state.poll().await {
+                    state.at_tip = true;
+                    return Some((Err(e), state));
+                }
+            }
+        })
+    }
+
     /// Scans requests related to notes from a given list of note hashes.
     pub async fn scan_related_to_notes_in_block_range(
         height_beg: u64,
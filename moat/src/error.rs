@@ -38,6 +38,8 @@ pub enum Error {
     InvalidQueryResponse(Cow<'static, str>),
     #[error("Transaction error: {0:?}")]
     Transaction(Cow<'static, str>),
+    #[error("Cancelled")]
+    Cancelled,
     #[error("Stream item not present or stream error: {0:?}")]
     Stream(Cow<'static, str>),
     #[error("A PLONK error occurred: {0:?}")]
@@ -46,6 +48,14 @@ pub enum Error {
     CRS(Cow<'static, str>),
     #[error(transparent)]
     HttpClient(Arc<reqwest::Error>),
+    #[error("A local state error occurred: {0:?}")]
+    LocalState(Cow<'static, str>),
+    #[error("An attribute encoding error occurred: {0:?}")]
+    AttributeEncoding(Cow<'static, str>),
+    #[error("Invalid service request: {0:?}")]
+    InvalidServiceRequest(Cow<'static, str>),
+    #[error("Invalid proof: {0:?}")]
+    InvalidProof(Cow<'static, str>),
 }
 
 impl From<serde_json::Error> for Error {
@@ -11,10 +11,12 @@
 //! Integration test for the library are provided in the `integration-test`
 //! subproject.
 
+mod atomic_file;
 mod bc_types;
 mod blockchain_payloads;
 mod blockchain_queries;
 mod circuit;
+mod citadel_keys;
 mod citadel_licenses;
 mod citadel_queries;
 mod citadel_requests;
@@ -22,6 +24,8 @@ mod citadel_types;
 mod contract_queries;
 mod error;
 mod json_loader;
+mod receipts;
+mod service;
 mod utils;
 
 pub mod api;
@@ -32,17 +36,39 @@ pub use bc_types::*;
 pub use blockchain_payloads::{
     PayloadExtractor, PayloadRetriever, PayloadSender,
 };
-pub use blockchain_queries::{BcInquirer, CrsGetter, TxAwaiter, TxInquirer};
+pub use blockchain_queries::{
+    AwaitConfig, Backoff, BcInquirer, BlockHashes, CrsGetter, ForkCheck,
+    GasEstimate, GasEstimator, TxAwaiter, TxInquirer, TxOutcome,
+    DEFAULT_GAS_ESTIMATE_TTL, GAS_ESTIMATION_BLOCKS, GAS_LIMIT_MARGIN_PERCENT,
+    MAX_TRACKED_BLOCKS,
+};
 pub use circuit::*;
-pub use citadel_licenses::LicenseUser;
+pub use citadel_keys::CitadelKeys;
+pub use citadel_licenses::{
+    AgeBracket, AttributeEncoder, DefaultAttributeEncoder, LicenseClaims,
+    LicenseUser, UseLicenseArg, UseLicenseBundle, ATTRIBUTES_ENCODING_VERSION,
+};
 pub use citadel_queries::{
-    CitadelInquirer, CitadelInquirerWs, LicenseSession, LicenseSessionId,
+    CitadelInquirer, CitadelInquirerWs, LicenseCache, LicenseSession,
+    LicenseSessionId, LICENSE_CACHE_FILE_NAME,
+};
+pub use citadel_requests::{
+    RejectionNotice, RequestCreator, RequestHistory, RequestScanner,
+    RequestSender, RequestStatus, TrackedScan, WatchConfig, BLOCKS_RANGE_LEN,
+    REQUEST_HISTORY_FILE_NAME,
 };
-pub use citadel_requests::{RequestCreator, RequestScanner, RequestSender};
 pub use citadel_types::*;
 pub use contract_queries::{
     block::*, ContractInquirer, ContractInquirerWs, StreamAux,
 };
 pub use error::Error;
 pub use json_loader::JsonLoader;
+pub use receipts::{LicenseReceipt, RequestReceipt, UseLicenseReceipt};
+pub use service::{
+    Challenge, ChallengeIssuer, ProofVerifier, ServiceClient, ServiceRequest,
+    ServiceResponse, ServiceVerifier, UsedSessions, CHALLENGE_ENDPOINT,
+    DEFAULT_CHALLENGE_TTL, MAX_PENDING_CHALLENGES,
+    MAX_PENDING_CHALLENGES_PER_PEER, MAX_RECENT_ROOTS,
+    MERKLE_ROOT_PUBLIC_INPUT_INDEX, SERVICE_ENDPOINT,
+};
 pub use utils::MoatCoreUtils;
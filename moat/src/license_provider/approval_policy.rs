@@ -0,0 +1,66 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use dusk_jubjub::JubJubScalar;
+use std::borrow::Cow;
+use zk_citadel::license::Request;
+
+/// Decision taken by the License Provider about a license request.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Decision {
+    /// Issue license with given attribute data.
+    Approve { attr_data: JubJubScalar },
+    /// Do not issue license, for a given reason.
+    Reject { reason: Cow<'static, str> },
+}
+
+/// Policy deciding whether a license should be issued for a given request,
+/// and computing the attribute data committed in the license.
+pub trait ApprovalPolicy {
+    fn decide(&self, request: &Request) -> Decision;
+}
+
+/// Policy approving all requests with the same attribute data.
+#[derive(Debug, Clone)]
+pub struct ApproveAll {
+    attr_data: JubJubScalar,
+}
+
+impl ApproveAll {
+    pub fn new(attr_data: JubJubScalar) -> Self {
+        Self { attr_data }
+    }
+}
+
+impl ApprovalPolicy for ApproveAll {
+    fn decide(&self, _request: &Request) -> Decision {
+        Decision::Approve {
+            attr_data: self.attr_data,
+        }
+    }
+}
+
+/// Policy rejecting all requests with the same reason.
+#[derive(Debug, Clone)]
+pub struct RejectAll {
+    reason: Cow<'static, str>,
+}
+
+impl RejectAll {
+    pub fn new(reason: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            reason: reason.into(),
+        }
+    }
+}
+
+impl ApprovalPolicy for RejectAll {
+    fn decide(&self, _request: &Request) -> Decision {
+        Decision::Reject {
+            reason: self.reason.clone(),
+        }
+    }
+}
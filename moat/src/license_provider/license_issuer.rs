@@ -4,9 +4,10 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
-use crate::wallet_accessor::{BlockchainAccessConfig, Password};
+use crate::wallet_accessor::{BlockchainAccessConfig, Password, WalletSession};
 use crate::{
-    Error, PayloadSender, TxAwaiter, ISSUE_LICENSE_METHOD_NAME,
+    AwaitConfig, BcInquirer, Error, GasEstimator, LicenseReceipt,
+    PayloadSender, TxAwaiter, TxOutcome, ISSUE_LICENSE_METHOD_NAME,
     LICENSE_CONTRACT_ID, MAX_LICENSE_SIZE,
 };
 use dusk_bls12_381::BlsScalar;
@@ -14,16 +15,50 @@ use dusk_jubjub::{JubJubAffine, JubJubScalar};
 use dusk_pki::SecretSpendKey;
 use dusk_poseidon::sponge;
 use dusk_wallet::{RuskHttpClient, WalletPath};
+use futures::future::join_all;
 use rand::{CryptoRng, RngCore};
 use tracing::trace;
 use zk_citadel::license::{License, Request};
 
+/// Submitted issue license transaction, along with the license it issues
+/// and the block height at the time of the submission.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LicenseSubmission {
+    pub tx_id: BlsScalar,
+    pub license_blob: Vec<u8>,
+    pub height: u64,
+}
+
+impl LicenseSubmission {
+    /// Creates receipt of the submission, once its transaction has been
+    /// included in a block at a given height.
+    pub fn receipt(
+        &self,
+        request: &Request,
+        block_height: u64,
+        gas_spent: u64,
+    ) -> LicenseReceipt {
+        LicenseReceipt::new(
+            &self.tx_id,
+            block_height,
+            gas_spent,
+            request,
+            self.license_blob.as_slice(),
+        )
+    }
+}
+
 pub struct LicenseIssuer {
     config: BlockchainAccessConfig,
+    client: RuskHttpClient,
     wallet_path: WalletPath,
     password: Password,
     gas_limit: u64,
     gas_price: u64,
+    await_config: AwaitConfig,
+    estimate_gas: bool,
+    gas_estimator: GasEstimator,
+    address_index: Option<usize>,
 }
 
 impl LicenseIssuer {
@@ -35,47 +70,276 @@ impl LicenseIssuer {
         gas_price: u64,
     ) -> Self {
         Self {
+            client: RuskHttpClient::new(config.rusk_address.clone()),
             config,
             wallet_path,
             password,
             gas_limit,
             gas_price,
+            await_config: AwaitConfig::default(),
+            estimate_gas: false,
+            gas_estimator: GasEstimator::new(),
+            address_index: None,
         }
     }
 
+    /// Makes the issuer estimate gas limit from past issue license calls,
+    /// the gas limit given at creation is used when no estimate is
+    /// available.
+    pub fn with_gas_estimation(mut self, estimate_gas: bool) -> Self {
+        self.estimate_gas = estimate_gas;
+        self
+    }
+
+    /// Sets estimator of the gas limit, allowing to configure its window
+    /// and to share its cached estimates.
+    pub fn with_gas_estimator(mut self, gas_estimator: GasEstimator) -> Self {
+        self.gas_estimator = gas_estimator;
+        self
+    }
+
+    /// Sets client used for querying the blockchain, instead of the client
+    /// created from the configured Rusk address.
+    pub fn with_client(mut self, client: RuskHttpClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Sets index of the wallet address paying for the transactions
+    /// submitted in wallet sessions, the default address is used if no
+    /// index is given.
+    pub fn with_address_index(mut self, address_index: Option<usize>) -> Self {
+        self.address_index = address_index;
+        self
+    }
+
+    /// Sets configuration of waiting for the issue license transaction.
+    pub fn with_await_config(mut self, await_config: AwaitConfig) -> Self {
+        self.await_config = await_config;
+        self
+    }
+
     /// Issue license for a given request, License Provider SSK, and attribute
-    /// data. Returns a serialized license.
+    /// data. Returns receipt containing the serialized license.
     pub async fn issue_license<R: RngCore + CryptoRng>(
         &self,
         rng: &mut R,
         request: &Request,
         ssk_lp: &SecretSpendKey,
         attr_data: &JubJubScalar,
-    ) -> Result<(BlsScalar, Vec<u8>), Error> {
-        let license = License::new(attr_data, ssk_lp, request, rng);
-        let license_blob = rkyv::to_bytes::<_, MAX_LICENSE_SIZE>(&license)
-            .expect("Serializing should be infallible")
-            .to_vec();
-        let lpk = JubJubAffine::from(license.lsa.pk_r().as_ref());
-        let license_hash = sponge::hash(&[lpk.get_u(), lpk.get_v()]);
+    ) -> Result<LicenseReceipt, Error> {
+        let submission =
+            self.submit_license(rng, request, ssk_lp, attr_data).await?;
+        self.wait_for_receipt(request, &submission).await
+    }
+
+    /// Submits issue license transaction for a given request, License
+    /// Provider SSK, and attribute data, without waiting for it.
+    pub async fn submit_license<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        request: &Request,
+        ssk_lp: &SecretSpendKey,
+        attr_data: &JubJubScalar,
+    ) -> Result<LicenseSubmission, Error> {
+        let (license_blob, license_hash) =
+            Self::license_payload(rng, request, ssk_lp, attr_data);
         let tuple = (license_blob.clone(), license_hash);
         trace!(
             "sending issue license with license blob size={}",
             tuple.0.len()
         );
+        let gas_limit = self.gas_limit().await?;
+        let height = BcInquirer::block_height(&self.client).await?;
         let tx_id = PayloadSender::execute_contract_method(
             tuple,
             &self.config,
             &self.wallet_path,
             &self.password,
-            self.gas_limit,
+            gas_limit,
             self.gas_price,
             LICENSE_CONTRACT_ID,
             ISSUE_LICENSE_METHOD_NAME,
         )
         .await?;
-        let client = RuskHttpClient::new(self.config.rusk_address.clone());
-        TxAwaiter::wait_for(&client, tx_id).await?;
-        Ok((tx_id, license_blob))
+        Ok(LicenseSubmission {
+            tx_id,
+            license_blob,
+            height,
+        })
+    }
+
+    /// Opens a wallet session which can be reused for issuing many
+    /// licenses, without reconnecting and syncing the wallet each time.
+    pub async fn open_session(&self) -> Result<WalletSession, Error> {
+        let session = WalletSession::open(
+            self.wallet_path.clone(),
+            self.password.clone(),
+            &self.config,
+        )
+        .await?;
+        Ok(session
+            .with_client(self.client.clone())
+            .with_await_config(self.await_config.clone())
+            .with_address_index(self.address_index))
+    }
+
+    /// Issue license for a given request, License Provider SSK, and attribute
+    /// data, submitting the transaction within a given wallet session.
+    pub async fn issue_license_in_session<R: RngCore + CryptoRng>(
+        &self,
+        session: &mut WalletSession,
+        rng: &mut R,
+        request: &Request,
+        ssk_lp: &SecretSpendKey,
+        attr_data: &JubJubScalar,
+    ) -> Result<LicenseReceipt, Error> {
+        let requests = [(request.clone(), *attr_data)];
+        let submission = self
+            .submit_licenses_in_session(session, rng, &requests, ssk_lp)
+            .await
+            .remove(0)?;
+        self.wait_for_receipt(request, &submission).await
+    }
+
+    /// Issue licenses for given requests and their attribute data, within a
+    /// newly opened wallet session.
+    pub async fn issue_licenses<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        requests: &[(Request, JubJubScalar)],
+        ssk_lp: &SecretSpendKey,
+    ) -> Result<Vec<Result<LicenseReceipt, Error>>, Error> {
+        let mut session = self.open_session().await?;
+        Ok(self
+            .issue_licenses_in_session(&mut session, rng, requests, ssk_lp)
+            .await)
+    }
+
+    /// Issue licenses for given requests and their attribute data, within a
+    /// given wallet session. Confirmations of all transactions are awaited
+    /// together, see `submit_licenses_in_session`. Returns the outcome for
+    /// each request, in the order of the requests.
+    pub async fn issue_licenses_in_session<R: RngCore + CryptoRng>(
+        &self,
+        session: &mut WalletSession,
+        rng: &mut R,
+        requests: &[(Request, JubJubScalar)],
+        ssk_lp: &SecretSpendKey,
+    ) -> Vec<Result<LicenseReceipt, Error>> {
+        let submitted = self
+            .submit_licenses_in_session(session, rng, requests, ssk_lp)
+            .await;
+        join_all(submitted.into_iter().zip(requests).map(
+            |(submission, (request, _))| async move {
+                self.wait_for_receipt(request, &submission?).await
+            },
+        ))
+        .await
+    }
+
+    /// Submits issue license transactions for given requests and their
+    /// attribute data, within a given wallet session. Transactions are
+    /// submitted one after another, waiting for the previous ones only as
+    /// long as the session has to, see
+    /// `WalletSession::execute_contract_method`. Returns the submission for
+    /// each request, in the order of the requests.
+    pub async fn submit_licenses_in_session<R: RngCore + CryptoRng>(
+        &self,
+        session: &mut WalletSession,
+        rng: &mut R,
+        requests: &[(Request, JubJubScalar)],
+        ssk_lp: &SecretSpendKey,
+    ) -> Vec<Result<LicenseSubmission, Error>> {
+        let gas_limit_height = async {
+            let gas_limit = self.gas_limit().await?;
+            let height = BcInquirer::block_height(&self.client).await?;
+            Ok::<_, Error>((gas_limit, height))
+        };
+        let (gas_limit, height) = match gas_limit_height.await {
+            Ok(gas_limit_height) => gas_limit_height,
+            Err(e) => return requests.iter().map(|_| Err(e.clone())).collect(),
+        };
+
+        let mut submitted = Vec::with_capacity(requests.len());
+        for (request, attr_data) in requests {
+            let (license_blob, license_hash) =
+                Self::license_payload(rng, request, ssk_lp, attr_data);
+            trace!(
+                "sending issue license {} of batch with license blob size={}",
+                submitted.len(),
+                license_blob.len()
+            );
+            let tx_id = session
+                .execute_contract_method(
+                    (license_blob.clone(), license_hash),
+                    LICENSE_CONTRACT_ID,
+                    ISSUE_LICENSE_METHOD_NAME,
+                    gas_limit,
+                    self.gas_price,
+                )
+                .await;
+            submitted.push(tx_id.map(|tx_id| LicenseSubmission {
+                tx_id,
+                license_blob,
+                height,
+            }));
+        }
+        submitted
+    }
+
+    /// Waits for a submitted issue license transaction, returns its outcome.
+    /// A transaction which has not been included within the configured
+    /// timeout is reported as dropped, although it might still be included
+    /// later.
+    pub async fn wait_for_submission(
+        &self,
+        submission: &LicenseSubmission,
+    ) -> Result<TxOutcome, Error> {
+        TxAwaiter::wait_for_outcome(
+            &self.client,
+            submission.tx_id,
+            &self.await_config,
+        )
+        .await
+    }
+
+    async fn gas_limit(&self) -> Result<u64, Error> {
+        self.gas_estimator
+            .gas_limit_for(
+                &self.client,
+                ISSUE_LICENSE_METHOD_NAME,
+                self.gas_limit,
+                self.estimate_gas,
+            )
+            .await
+    }
+
+    async fn wait_for_receipt(
+        &self,
+        request: &Request,
+        submission: &LicenseSubmission,
+    ) -> Result<LicenseReceipt, Error> {
+        let (block_height, gas_spent) =
+            self.wait_for_submission(submission).await?.into_result()?;
+        Ok(submission.receipt(request, block_height, gas_spent))
+    }
+
+    /// Creates license for a given request, License Provider SSK, and
+    /// attribute data. Returns the serialized license along with the license
+    /// hash, as expected by the license contract's issue license method.
+    pub fn license_payload<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        request: &Request,
+        ssk_lp: &SecretSpendKey,
+        attr_data: &JubJubScalar,
+    ) -> (Vec<u8>, BlsScalar) {
+        let license = License::new(attr_data, ssk_lp, request, rng);
+        let license_blob = rkyv::to_bytes::<_, MAX_LICENSE_SIZE>(&license)
+            .expect("Serializing should be infallible")
+            .to_vec();
+        let lpk = JubJubAffine::from(license.lsa.pk_r().as_ref());
+        let license_hash = sponge::hash(&[lpk.get_u(), lpk.get_v()]);
+        (license_blob, license_hash)
     }
 }
@@ -0,0 +1,109 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::atomic_file::AtomicFile;
+use crate::{BlockHashes, Error, JsonLoader};
+use dusk_wallet::WalletPath;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+pub const LP_CHECKPOINT_FILE_NAME: &str = "moat_lp_checkpoint.json";
+
+/// Persistent state of the License Provider's scanning process.
+/// Requests hashes are hex encoded, requests are hex encoded rkyv blobs.
+/// Requests heights hold heights of the blocks containing the requests, in
+/// the order of requests, if known. Rejected requests map request hashes
+/// to rejection reasons. Processed requests hold hashes of the requests
+/// for which licenses have been issued, so that they are not taken up again
+/// when blocks are rescanned. In flight requests map request hashes to the
+/// requests taken for issuing, whose licenses have not been confirmed yet.
+/// Public spend key of the License Provider is hex encoded, checkpoint saved
+/// by a different License Provider is not restored.
+#[derive(
+    Debug, Default, Clone, PartialEq, serde::Deserialize, serde::Serialize,
+)]
+pub struct LPCheckpoint {
+    #[serde(default)]
+    pub psk_lp: Option<String>,
+    pub next_height: u64,
+    pub requests_hashes: Vec<String>,
+    pub requests: Vec<String>,
+    #[serde(default)]
+    pub requests_heights: Vec<Option<u64>>,
+    #[serde(default)]
+    pub block_hashes: BlockHashes,
+    #[serde(default)]
+    pub rejected_requests: BTreeMap<String, String>,
+    #[serde(default)]
+    pub processed_requests: BTreeSet<String>,
+    #[serde(default)]
+    pub in_flight_requests: BTreeMap<String, InFlightRequest>,
+}
+
+impl JsonLoader for LPCheckpoint {}
+
+/// Request taken for issuing, along with its issue license transaction if
+/// it has been submitted. Request and license are hex encoded rkyv blobs,
+/// transaction id is hex encoded.
+#[derive(
+    Debug, Default, Clone, PartialEq, serde::Deserialize, serde::Serialize,
+)]
+pub struct InFlightRequest {
+    pub request: String,
+    #[serde(default)]
+    pub submission: Option<InFlightSubmission>,
+}
+
+/// Submitted issue license transaction of an in flight request, with the
+/// block height at the time of the submission.
+#[derive(
+    Debug, Default, Clone, PartialEq, serde::Deserialize, serde::Serialize,
+)]
+pub struct InFlightSubmission {
+    pub tx_id: String,
+    pub license_blob: String,
+    pub height: u64,
+}
+
+/// File based store of the License Provider's checkpoint.
+pub struct LPCheckpointStore {
+    path: PathBuf,
+}
+
+impl LPCheckpointStore {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Creates store of a checkpoint placed in the wallet directory, or None
+    /// if the wallet path has no directory.
+    pub fn from_wallet_dir(wallet_path: &WalletPath) -> Option<Self> {
+        wallet_path
+            .dir()
+            .map(|dir| Self::new(dir.join(LP_CHECKPOINT_FILE_NAME)))
+    }
+
+    pub fn path(&self) -> &Path {
+        self.path.as_path()
+    }
+
+    /// Loads checkpoint, returns None if no checkpoint has been saved yet.
+    pub fn load(&self) -> Result<Option<LPCheckpoint>, Error> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        LPCheckpoint::from_file(&self.path).map(Some)
+    }
+
+    /// Saves checkpoint atomically, so that a process dying in the middle
+    /// of the save leaves the previous checkpoint intact.
+    pub fn save(&self, checkpoint: &LPCheckpoint) -> Result<(), Error> {
+        let json = serde_json::to_vec_pretty(checkpoint)?;
+        AtomicFile::write(&self.path, json)
+    }
+}
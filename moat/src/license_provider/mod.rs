@@ -4,8 +4,15 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+mod approval_policy;
 mod license_issuer;
+mod lp_checkpoint;
 mod reference_lp;
 
-pub use license_issuer::LicenseIssuer;
-pub use reference_lp::ReferenceLP;
+pub use approval_policy::{ApprovalPolicy, ApproveAll, Decision, RejectAll};
+pub use license_issuer::{LicenseIssuer, LicenseSubmission};
+pub use lp_checkpoint::{
+    InFlightRequest, InFlightSubmission, LPCheckpoint, LPCheckpointStore,
+    LP_CHECKPOINT_FILE_NAME,
+};
+pub use reference_lp::{LPConfig, ReferenceLP, IN_FLIGHT_EXPIRY_BLOCKS};
@@ -4,16 +4,29 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+use crate::license_provider::{
+    InFlightRequest, InFlightSubmission, LPCheckpoint, LPCheckpointStore,
+    LicenseSubmission,
+};
 use crate::utils::MoatCoreUtils;
 use crate::wallet_accessor::BlockchainAccessConfig;
-use crate::{Error, JsonLoader, RequestScanner, MAX_REQUEST_SIZE};
+use crate::{
+    BcInquirer, BlockHashes, Error, JsonLoader, RequestScanner, TrackedScan,
+    TxAwaiter, TxOutcome, BLOCKS_RANGE_LEN, MAX_REQUEST_SIZE,
+};
 use blake3::OUT_LEN;
-use dusk_bytes::DeserializableSlice;
+use dusk_bls12_381::BlsScalar;
+use dusk_bytes::{DeserializableSlice, Serializable};
 use dusk_pki::{PublicSpendKey, SecretSpendKey, ViewKey};
-use std::collections::BTreeSet;
+use dusk_wallet::RuskHttpClient;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::Path;
 use zk_citadel::license::Request;
 
+/// Number of blocks after which an issue license transaction which has not
+/// been included is considered lost, so that the license is issued again.
+pub const IN_FLIGHT_EXPIRY_BLOCKS: u64 = 360;
+
 #[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
 pub struct LPConfig {
     pub psk_lp: String,
@@ -21,14 +34,24 @@ pub struct LPConfig {
 }
 impl JsonLoader for LPConfig {}
 
-const BLOCKS_RANGE_LEN: u64 = 10000;
-
 pub struct ReferenceLP {
     pub psk_lp: PublicSpendKey,
     pub ssk_lp: SecretSpendKey,
     pub vk_lp: ViewKey,
     pub requests_to_process: Vec<Request>,
     pub requests_hashes: BTreeSet<[u8; OUT_LEN]>,
+    pub next_height: u64,
+    pub block_hashes: BlockHashes,
+    requests_heights: BTreeMap<[u8; OUT_LEN], u64>,
+    rejected_requests: BTreeMap<String, String>,
+    processed_requests: BTreeSet<String>,
+    in_flight_requests: BTreeMap<String, InFlight>,
+}
+
+/// Request taken for issuing, whose license has not been confirmed yet.
+struct InFlight {
+    request: Request,
+    submission: Option<LicenseSubmission>,
 }
 
 impl ReferenceLP {
@@ -43,6 +66,12 @@ impl ReferenceLP {
             vk_lp,
             requests_to_process: Vec::new(),
             requests_hashes: BTreeSet::new(),
+            next_height: 0,
+            block_hashes: BlockHashes::new(),
+            requests_heights: BTreeMap::new(),
+            rejected_requests: BTreeMap::new(),
+            processed_requests: BTreeSet::new(),
+            in_flight_requests: BTreeMap::new(),
         }
     }
 
@@ -61,28 +90,80 @@ impl ReferenceLP {
         Ok(Self::new(psk_lp, *ssk_lp, vk_lp))
     }
 
-    /// Scans the entire blockchain for the requests to process.
+    /// Creates License Provider and restores its state from a given
+    /// checkpoint store, if a checkpoint has been saved before.
+    pub fn create_with_checkpoint(
+        ssk_lp: &SecretSpendKey,
+        store: &LPCheckpointStore,
+    ) -> Result<Self, Error> {
+        let mut reference_lp = Self::create_with_ssk(ssk_lp)?;
+        if let Some(checkpoint) = store.load()? {
+            reference_lp.restore(&checkpoint)?;
+        }
+        Ok(reference_lp)
+    }
+
+    /// Scans the blockchain for the requests to process, starting from the
+    /// height at which the previous scan ended.
     /// Returns total number of requests found and number of requests addressed
     /// to this LP.
     pub async fn scan(
         &mut self,
-        cfg: &BlockchainAccessConfig,
+        client: &RuskHttpClient,
     ) -> Result<(usize, usize), Error> {
-        let mut height = 0;
+        self.scan_from_next_height(client, None).await
+    }
+
+    /// Scans the blockchain like `scan`, saving a checkpoint after each
+    /// scanned range of blocks, so that an interrupted scan can be resumed.
+    pub async fn scan_with_checkpoint(
+        &mut self,
+        client: &RuskHttpClient,
+        store: &LPCheckpointStore,
+    ) -> Result<(usize, usize), Error> {
+        self.scan_from_next_height(client, Some(store)).await
+    }
+
+    async fn scan_from_next_height(
+        &mut self,
+        client: &RuskHttpClient,
+        store: Option<&LPCheckpointStore>,
+    ) -> Result<(usize, usize), Error> {
+        let mut height = self.next_height;
         let mut total = 0usize;
         let mut total_owned = 0usize;
         loop {
             let height_end = height + BLOCKS_RANGE_LEN;
-            let (requests, top) =
-                RequestScanner::scan_block_range(height, height_end, cfg)
-                    .await?;
+            let scan = RequestScanner::scan_block_range_tracked(
+                height,
+                height_end,
+                client,
+                &mut self.block_hashes,
+            )
+            .await?;
+            let (requests, top) = match scan {
+                TrackedScan::Scanned { requests, top } => (requests, top),
+                TrackedScan::Fork { common_ancestor } => {
+                    self.rollback(common_ancestor);
+                    height = self.next_height;
+                    continue;
+                }
+            };
             total += requests.len();
-            let owned_requests = self.retain_owned_requests(requests);
-            for owned_request in owned_requests {
-                if self.insert_request(owned_request) {
+            for (request_height, request) in requests {
+                if self.is_owned_request(&request)
+                    && self.insert_request(request, Some(request_height))
+                {
                     total_owned += 1;
                 }
             }
+            // the last block of the range is scanned again next time, as it
+            // might not have been complete, requests already found, taken,
+            // or rejected are filtered out
+            self.next_height = height_end.min(top);
+            if let Some(store) = store {
+                store.save(&self.checkpoint())?;
+            }
             if top <= height_end {
                 return Ok((total, total_owned));
             }
@@ -90,6 +171,30 @@ impl ReferenceLP {
         }
     }
 
+    /// Rolls back the scanning state to a given common ancestor block,
+    /// dropping requests found in blocks which are no longer part of the
+    /// chain. If there is no common ancestor, scanning starts from scratch.
+    fn rollback(&mut self, common_ancestor: Option<u64>) {
+        let height = common_ancestor.unwrap_or_default();
+        match common_ancestor {
+            Some(height) => self.block_hashes.rollback(height),
+            None => self.block_hashes.clear(),
+        }
+        let orphaned: BTreeSet<[u8; OUT_LEN]> = self
+            .requests_heights
+            .iter()
+            .filter(|(_, request_height)| **request_height > height)
+            .map(|(hash, _)| *hash)
+            .collect();
+        self.requests_to_process
+            .retain(|request| !orphaned.contains(&Self::hash_request(request)));
+        for hash in orphaned.iter() {
+            self.requests_hashes.remove(hash);
+            self.requests_heights.remove(hash);
+        }
+        self.next_height = height;
+    }
+
     /// Scans last n blocks for the requests to process.
     /// Returns the total number of requests found and the number of requests
     /// addressed to this LP.
@@ -104,7 +209,7 @@ impl ReferenceLP {
         total += requests.len();
         let owned_requests = self.retain_owned_requests(requests);
         for owned_request in owned_requests {
-            if self.insert_request(owned_request) {
+            if self.insert_request(owned_request, None) {
                 total_owned += 1;
             }
         }
@@ -125,9 +230,23 @@ impl ReferenceLP {
         self.vk_lp.owns(&request.rsa)
     }
 
-    fn insert_request(&mut self, request: Request) -> bool {
+    fn insert_request(
+        &mut self,
+        request: Request,
+        height: Option<u64>,
+    ) -> bool {
+        let request_hash = MoatCoreUtils::to_hash_hex(&request);
+        if self.is_rejected(&request_hash)
+            || self.is_processed(&request_hash)
+            || self.is_in_flight(&request_hash)
+        {
+            return false;
+        }
         let hash = Self::hash_request(&request);
         if self.requests_hashes.insert(hash) {
+            if let Some(height) = height {
+                self.requests_heights.insert(hash, height);
+            }
             self.requests_to_process.push(request);
             true
         } else {
@@ -136,22 +255,261 @@ impl ReferenceLP {
     }
 
     /// Take and remove one of the requests to process.
+    /// Taken request is in flight until it is marked as processed, rejected,
+    /// or returned, so that it is not taken up again when rescanning.
     pub fn take_request(&mut self) -> Option<Request> {
-        self.requests_to_process.pop().map(|request| {
-            self.requests_hashes.remove(&Self::hash_request(&request));
-            request
-        })
+        let request = self.requests_to_process.pop()?;
+        self.mark_in_flight(&request);
+        Some(request)
     }
 
     /// Retrieve request with a given request hash, or None if not found.
+    /// Retrieved request is in flight, like in `take_request`.
     pub fn get_request(&mut self, request_hash: &String) -> Option<Request> {
-        for (index, request) in self.requests_to_process.iter().enumerate() {
-            if MoatCoreUtils::to_hash_hex(request) == *request_hash {
-                self.requests_hashes.remove(&Self::hash_request(request));
-                return Some(self.requests_to_process.remove(index));
+        let index = self.requests_to_process.iter().position(|request| {
+            MoatCoreUtils::to_hash_hex(request) == *request_hash
+        })?;
+        let request = self.requests_to_process.remove(index);
+        self.mark_in_flight(&request);
+        Some(request)
+    }
+
+    /// Puts a taken request back to the requests to process, so that it is
+    /// processed again, used when processing of the request has failed.
+    pub fn return_request(&mut self, request: Request) {
+        let request_hash = MoatCoreUtils::to_hash_hex(&request);
+        self.processed_requests.remove(&request_hash);
+        self.in_flight_requests.remove(&request_hash);
+        self.insert_request(request, None);
+    }
+
+    /// Marks a given request as in flight, used for requests which are
+    /// processed without being taken from the requests to process.
+    pub fn mark_in_flight(&mut self, request: &Request) {
+        self.forget_request(request);
+        self.in_flight_requests
+            .entry(MoatCoreUtils::to_hash_hex(request))
+            .or_insert_with(|| InFlight {
+                request: request.clone(),
+                submission: None,
+            });
+    }
+
+    /// Records submitted issue license transaction of a given request, the
+    /// request stays in flight until the transaction is settled, see
+    /// `settle_in_flight`.
+    pub fn record_submission(
+        &mut self,
+        request: &Request,
+        submission: LicenseSubmission,
+    ) {
+        self.forget_request(request);
+        self.in_flight_requests.insert(
+            MoatCoreUtils::to_hash_hex(request),
+            InFlight {
+                request: request.clone(),
+                submission: Some(submission),
+            },
+        );
+    }
+
+    /// Marks a given request as processed, once its license has been
+    /// issued.
+    pub fn mark_processed(&mut self, request: &Request) {
+        let request_hash = MoatCoreUtils::to_hash_hex(request);
+        self.forget_request(request);
+        self.in_flight_requests.remove(&request_hash);
+        self.processed_requests.insert(request_hash);
+    }
+
+    /// Returns true if license for request with a given request hash has been
+    /// issued.
+    pub fn is_processed(&self, request_hash: &str) -> bool {
+        self.processed_requests.contains(request_hash)
+    }
+
+    /// Returns true if request with a given request hash has been taken for
+    /// issuing, but its license has not been confirmed yet.
+    pub fn is_in_flight(&self, request_hash: &str) -> bool {
+        self.in_flight_requests.contains_key(request_hash)
+    }
+
+    /// Settles in flight requests, without waiting for their transactions.
+    /// Requests whose transactions have been included are marked as
+    /// processed. Requests whose transactions have failed, or have not been
+    /// included within `IN_FLIGHT_EXPIRY_BLOCKS` blocks, are put back to the
+    /// requests to process, as are requests which have never been
+    /// submitted. Other requests stay in flight, as their transactions might
+    /// still be included.
+    /// Returns settled requests with their submissions and outcomes, lost
+    /// transactions have the dropped outcome.
+    pub async fn settle_in_flight(
+        &mut self,
+        client: &RuskHttpClient,
+    ) -> Result<Vec<(Request, LicenseSubmission, TxOutcome)>, Error> {
+        if self.in_flight_requests.is_empty() {
+            return Ok(Vec::new());
+        }
+        let top = BcInquirer::block_height(client).await?;
+        let mut settled = Vec::new();
+        let request_hashes: Vec<String> =
+            self.in_flight_requests.keys().cloned().collect();
+        for request_hash in request_hashes {
+            let in_flight = &self.in_flight_requests[&request_hash];
+            let request = in_flight.request.clone();
+            let submission = match in_flight.submission.clone() {
+                Some(submission) => submission,
+                None => {
+                    self.return_request(request);
+                    continue;
+                }
+            };
+            let outcome =
+                match TxAwaiter::query_outcome(client, submission.tx_id).await?
+                {
+                    Some(outcome) => outcome,
+                    None if top
+                        >= submission.height + IN_FLIGHT_EXPIRY_BLOCKS =>
+                    {
+                        TxOutcome::Dropped
+                    }
+                    None => continue,
+                };
+            match outcome {
+                TxOutcome::Included { .. } => self.mark_processed(&request),
+                _ => self.return_request(request.clone()),
+            }
+            settled.push((request, submission, outcome));
+        }
+        Ok(settled)
+    }
+
+    /// Removes a given request from the requests found by scanning.
+    fn forget_request(&mut self, request: &Request) {
+        let hash = Self::hash_request(request);
+        self.requests_hashes.remove(&hash);
+        self.requests_heights.remove(&hash);
+    }
+
+    /// Rejects request with a given request hash, removing it from the
+    /// requests to process. Rejected requests are not taken up again when
+    /// rescanning. Returns the rejected request, or None if not found.
+    pub fn reject_request(
+        &mut self,
+        request_hash: &String,
+        reason: impl Into<String>,
+    ) -> Option<Request> {
+        let request = self.get_request(request_hash)?;
+        self.mark_rejected(&request, reason);
+        Some(request)
+    }
+
+    /// Marks a given request as rejected, used for requests which have
+    /// already been taken from the requests to process.
+    pub fn mark_rejected(
+        &mut self,
+        request: &Request,
+        reason: impl Into<String>,
+    ) {
+        let request_hash = MoatCoreUtils::to_hash_hex(request);
+        self.forget_request(request);
+        self.in_flight_requests.remove(&request_hash);
+        self.rejected_requests.insert(request_hash, reason.into());
+    }
+
+    pub fn is_rejected(&self, request_hash: &str) -> bool {
+        self.rejected_requests.contains_key(request_hash)
+    }
+
+    /// Provides reason of rejection of a request with a given request hash,
+    /// or None if the request has not been rejected.
+    pub fn rejection_reason(&self, request_hash: &str) -> Option<&str> {
+        self.rejected_requests.get(request_hash).map(String::as_str)
+    }
+
+    /// Provides checkpoint of the current scanning state.
+    pub fn checkpoint(&self) -> LPCheckpoint {
+        LPCheckpoint {
+            psk_lp: Some(hex::encode(self.psk_lp.to_bytes())),
+            next_height: self.next_height,
+            requests_hashes: self
+                .requests_hashes
+                .iter()
+                .map(hex::encode)
+                .collect(),
+            requests: self
+                .requests_to_process
+                .iter()
+                .map(MoatCoreUtils::to_blob_hex)
+                .collect(),
+            requests_heights: self
+                .requests_to_process
+                .iter()
+                .map(|request| {
+                    self.requests_heights
+                        .get(&Self::hash_request(request))
+                        .copied()
+                })
+                .collect(),
+            block_hashes: self.block_hashes.clone(),
+            rejected_requests: self.rejected_requests.clone(),
+            processed_requests: self.processed_requests.clone(),
+            in_flight_requests: self
+                .in_flight_requests
+                .iter()
+                .map(|(request_hash, in_flight)| {
+                    (request_hash.clone(), in_flight.to_checkpoint())
+                })
+                .collect(),
+        }
+    }
+
+    /// Restores scanning state from a given checkpoint.
+    /// Fails if the checkpoint has been saved by a different License
+    /// Provider.
+    pub fn restore(&mut self, checkpoint: &LPCheckpoint) -> Result<(), Error> {
+        if let Some(psk_lp) = &checkpoint.psk_lp {
+            if *psk_lp != hex::encode(self.psk_lp.to_bytes()) {
+                return Err(Error::LocalState(
+                    "checkpoint of a different license provider".into(),
+                ));
             }
         }
-        None
+        let mut requests_hashes = BTreeSet::new();
+        for hash_hex in checkpoint.requests_hashes.iter() {
+            let mut hash = [0u8; OUT_LEN];
+            hex::decode_to_slice(hash_hex, hash.as_mut_slice())?;
+            requests_hashes.insert(hash);
+        }
+        let mut requests_heights = BTreeMap::new();
+        let mut requests_to_process = Vec::new();
+        for (i, request_hex) in checkpoint.requests.iter().enumerate() {
+            let request_blob = hex::decode(request_hex)?;
+            let request: Request = rkyv::from_bytes(request_blob.as_slice())
+                .map_err(|_| {
+                    Error::LocalState("invalid request in checkpoint".into())
+                })?;
+            let hash = Self::hash_request(&request);
+            requests_hashes.insert(hash);
+            if let Some(Some(height)) = checkpoint.requests_heights.get(i) {
+                requests_heights.insert(hash, *height);
+            }
+            requests_to_process.push(request);
+        }
+        let mut in_flight_requests = BTreeMap::new();
+        for (request_hash, in_flight) in checkpoint.in_flight_requests.iter() {
+            in_flight_requests
+                .insert(request_hash.clone(), InFlight::restore(in_flight)?);
+        }
+        self.next_height = checkpoint.next_height;
+        self.block_hashes = checkpoint.block_hashes.clone();
+        self.requests_hashes = requests_hashes;
+        self.requests_heights = requests_heights;
+        self.requests_to_process = requests_to_process;
+        self.rejected_requests = checkpoint.rejected_requests.clone();
+        self.processed_requests = checkpoint.processed_requests.clone();
+        self.in_flight_requests = in_flight_requests;
+        Ok(())
     }
 
     fn hash_request(request: &Request) -> [u8; OUT_LEN] {
@@ -163,3 +521,43 @@ impl ReferenceLP {
         .as_bytes()
     }
 }
+
+impl InFlight {
+    fn to_checkpoint(&self) -> InFlightRequest {
+        InFlightRequest {
+            request: MoatCoreUtils::to_blob_hex(&self.request),
+            submission: self.submission.as_ref().map(|submission| {
+                InFlightSubmission {
+                    tx_id: hex::encode(submission.tx_id.to_bytes()),
+                    license_blob: hex::encode(&submission.license_blob),
+                    height: submission.height,
+                }
+            }),
+        }
+    }
+
+    fn restore(in_flight: &InFlightRequest) -> Result<Self, Error> {
+        let request_blob = hex::decode(&in_flight.request)?;
+        let request: Request = rkyv::from_bytes(request_blob.as_slice())
+            .map_err(|_| {
+                Error::LocalState(
+                    "invalid in flight request in checkpoint".into(),
+                )
+            })?;
+        let submission = match &in_flight.submission {
+            Some(submission) => {
+                let tx_id = hex::decode(&submission.tx_id)?;
+                Some(LicenseSubmission {
+                    tx_id: BlsScalar::from_slice(tx_id.as_slice())?,
+                    license_blob: hex::decode(&submission.license_blob)?,
+                    height: submission.height,
+                })
+            }
+            None => None,
+        };
+        Ok(Self {
+            request,
+            submission,
+        })
+    }
+}
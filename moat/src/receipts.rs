@@ -0,0 +1,142 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::utils::MoatCoreUtils;
+use crate::Error;
+use dusk_bls12_381::BlsScalar;
+use dusk_bytes::{DeserializableSlice, Serializable};
+use dusk_pki::StealthAddress;
+use zk_citadel::license::{License, Request, SessionCookie};
+
+/// Receipt of a confirmed license request transaction.
+/// Transaction id, request blob and license stealth address are hex encoded.
+/// License stealth address is empty if not known.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct RequestReceipt {
+    pub tx_id: String,
+    pub block_height: u64,
+    pub gas_spent: u64,
+    pub request_hash: String,
+    pub request_blob: String,
+    #[serde(default)]
+    pub lsa: String,
+}
+
+impl RequestReceipt {
+    pub fn new(
+        tx_id: &BlsScalar,
+        block_height: u64,
+        gas_spent: u64,
+        request_blob: &[u8],
+    ) -> Self {
+        Self {
+            tx_id: hex::encode(tx_id.to_bytes()),
+            block_height,
+            gas_spent,
+            request_hash: MoatCoreUtils::blob_to_hash_hex(request_blob),
+            request_blob: hex::encode(request_blob),
+            lsa: String::new(),
+        }
+    }
+
+    /// Sets license stealth address encrypted in the request, which allows
+    /// for finding the license issued for the request.
+    pub fn with_lsa(mut self, lsa: &StealthAddress) -> Self {
+        self.lsa = hex::encode(lsa.to_bytes());
+        self
+    }
+
+    /// License stealth address encrypted in the request, or None if not
+    /// known.
+    pub fn lsa(&self) -> Result<Option<StealthAddress>, Error> {
+        if self.lsa.is_empty() {
+            return Ok(None);
+        }
+        let bytes = hex::decode(&self.lsa)?;
+        Ok(Some(StealthAddress::from_slice(bytes.as_slice())?))
+    }
+
+    pub fn request(&self) -> Result<Request, Error> {
+        let bytes = hex::decode(&self.request_blob)?;
+        rkyv::from_bytes(bytes.as_slice())
+            .map_err(|_| Error::LocalState("invalid request in receipt".into()))
+    }
+}
+
+/// Receipt of a confirmed issue license transaction.
+/// Transaction id and license blob are hex encoded.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct LicenseReceipt {
+    pub tx_id: String,
+    pub block_height: u64,
+    pub gas_spent: u64,
+    pub request_hash: String,
+    pub license_hash: String,
+    pub license_blob: String,
+}
+
+impl LicenseReceipt {
+    pub fn new(
+        tx_id: &BlsScalar,
+        block_height: u64,
+        gas_spent: u64,
+        request: &Request,
+        license_blob: &[u8],
+    ) -> Self {
+        Self {
+            tx_id: hex::encode(tx_id.to_bytes()),
+            block_height,
+            gas_spent,
+            request_hash: MoatCoreUtils::to_hash_hex(request),
+            license_hash: MoatCoreUtils::blob_to_hash_hex(license_blob),
+            license_blob: hex::encode(license_blob),
+        }
+    }
+
+    pub fn license(&self) -> Result<License, Error> {
+        let bytes = hex::decode(&self.license_blob)?;
+        rkyv::from_bytes(bytes.as_slice())
+            .map_err(|_| Error::LocalState("invalid license in receipt".into()))
+    }
+}
+
+/// Receipt of a confirmed use license transaction.
+/// Transaction id, session id and session cookie blob are hex encoded.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct UseLicenseReceipt {
+    pub tx_id: String,
+    pub block_height: u64,
+    pub gas_spent: u64,
+    pub license_hash: String,
+    pub session_id: String,
+    pub session_cookie: String,
+}
+
+impl UseLicenseReceipt {
+    pub fn new(
+        tx_id: &BlsScalar,
+        block_height: u64,
+        gas_spent: u64,
+        license: &License,
+        session_cookie: &SessionCookie,
+    ) -> Self {
+        Self {
+            tx_id: hex::encode(tx_id.to_bytes()),
+            block_height,
+            gas_spent,
+            license_hash: MoatCoreUtils::to_hash_hex(license),
+            session_id: hex::encode(session_cookie.session_id.to_bytes()),
+            session_cookie: MoatCoreUtils::to_blob_hex(session_cookie),
+        }
+    }
+
+    pub fn session_cookie(&self) -> Result<SessionCookie, Error> {
+        let bytes = hex::decode(&self.session_cookie)?;
+        rkyv::from_bytes(bytes.as_slice()).map_err(|_| {
+            Error::LocalState("invalid session cookie in receipt".into())
+        })
+    }
+}
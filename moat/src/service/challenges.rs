@@ -0,0 +1,203 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::Error;
+use dusk_bytes::{DeserializableSlice, Serializable};
+use dusk_jubjub::JubJubScalar;
+use dusk_pki::PublicSpendKey;
+use rand::{CryptoRng, RngCore};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::net::{IpAddr, Ipv6Addr};
+use std::time::Duration;
+
+/// Maximum number of challenges issued and not yet used, the ones closest to
+/// expiry are discarded first.
+pub const MAX_PENDING_CHALLENGES: usize = 10000;
+
+/// Maximum number of challenges issued to a single peer and not yet used,
+/// further challenges are refused until the pending ones are used or
+/// expire. IPv6 peers are counted per /64 prefix.
+pub const MAX_PENDING_CHALLENGES_PER_PEER: usize = 16;
+
+/// Default time-to-live of the challenges. Expiry is checked when the
+/// service is requested, hence the time-to-live needs to cover proving,
+/// possibly including the first compilation of the circuit, and the
+/// inclusion of the use license transaction.
+pub const DEFAULT_CHALLENGE_TTL: Duration = Duration::from_secs(3600);
+
+/// Challenge issued by a Service Provider, to be used by the user when
+/// proving the use of a license.
+/// Challenge is derived from the Service Provider's public spend key, a
+/// random nonce and the expiry, so that the user can check it has been
+/// issued for the intended Service Provider.
+/// Challenge and nonce are hex encoded, expiry is a unix timestamp in
+/// seconds.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Challenge {
+    pub challenge: String,
+    pub nonce: String,
+    pub expires_at: u64,
+}
+
+impl Challenge {
+    pub fn challenge(&self) -> Result<JubJubScalar, Error> {
+        let bytes = hex::decode(&self.challenge)?;
+        Ok(JubJubScalar::from_slice(bytes.as_slice())?)
+    }
+
+    /// Returns true if the challenge has been derived for a given Service
+    /// Provider.
+    pub fn is_bound_to(&self, psk_sp: &PublicSpendKey) -> bool {
+        match hex::decode(&self.nonce) {
+            Ok(nonce) => {
+                let challenge =
+                    derive_challenge(psk_sp, &nonce, self.expires_at);
+                hex::encode(challenge.to_bytes()) == self.challenge
+            }
+            Err(_) => false,
+        }
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at <= now
+    }
+}
+
+/// Issues single use, expiring challenges on behalf of a Service Provider,
+/// and redeems them when sessions are verified.
+/// Pending challenges are kept in memory only, they cannot be redeemed once
+/// the issuer is dropped. Pending challenges are ordered by expiry, so that
+/// expired challenges are pruned without scanning all of them.
+pub struct ChallengeIssuer {
+    psk_sp: PublicSpendKey,
+    ttl: Duration,
+    pending: BTreeMap<String, PendingChallenge>,
+    by_expiry: BTreeSet<(u64, String)>,
+    peers: HashMap<IpAddr, usize>,
+}
+
+struct PendingChallenge {
+    expires_at: u64,
+    peer: IpAddr,
+}
+
+impl ChallengeIssuer {
+    pub fn new(psk_sp: PublicSpendKey, ttl: Duration) -> Self {
+        Self {
+            psk_sp,
+            ttl,
+            pending: BTreeMap::new(),
+            by_expiry: BTreeSet::new(),
+            peers: HashMap::new(),
+        }
+    }
+
+    /// Issues a new challenge to a given peer, valid for the time-to-live
+    /// from a given unix timestamp. Returns None if the peer has reached
+    /// `MAX_PENDING_CHALLENGES_PER_PEER` pending challenges.
+    pub fn issue<R: RngCore + CryptoRng>(
+        &mut self,
+        rng: &mut R,
+        peer: IpAddr,
+        now: u64,
+    ) -> Option<Challenge> {
+        self.prune(now);
+        let peer = Self::peer_key(peer);
+        if self.peers.get(&peer).copied().unwrap_or_default()
+            >= MAX_PENDING_CHALLENGES_PER_PEER
+        {
+            return None;
+        }
+        while self.pending.len() >= MAX_PENDING_CHALLENGES {
+            match self.by_expiry.first().cloned() {
+                Some((_, challenge)) => self.remove(&challenge),
+                None => break,
+            };
+        }
+        let mut nonce = [0u8; 32];
+        rng.fill_bytes(&mut nonce);
+        let expires_at = now + self.ttl.as_secs();
+        let challenge = derive_challenge(&self.psk_sp, &nonce, expires_at);
+        let challenge = hex::encode(challenge.to_bytes());
+        self.pending
+            .insert(challenge.clone(), PendingChallenge { expires_at, peer });
+        self.by_expiry.insert((expires_at, challenge.clone()));
+        *self.peers.entry(peer).or_default() += 1;
+        Some(Challenge {
+            challenge,
+            nonce: hex::encode(nonce),
+            expires_at,
+        })
+    }
+
+    /// Redeems a challenge at a given unix timestamp.
+    /// Returns false if the challenge has not been issued, has expired, or
+    /// has already been redeemed.
+    pub fn redeem(&mut self, challenge: &JubJubScalar, now: u64) -> bool {
+        match self.remove(&hex::encode(challenge.to_bytes())) {
+            Some(expires_at) => expires_at > now,
+            None => false,
+        }
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Removes challenges which have expired at a given unix timestamp.
+    fn prune(&mut self, now: u64) {
+        while let Some((expires_at, challenge)) = self.by_expiry.first() {
+            if *expires_at > now {
+                break;
+            }
+            let challenge = challenge.clone();
+            self.remove(&challenge);
+        }
+    }
+
+    /// Removes a pending challenge, returns its expiry or None if the
+    /// challenge is not pending.
+    fn remove(&mut self, challenge: &str) -> Option<u64> {
+        let pending = self.pending.remove(challenge)?;
+        self.by_expiry
+            .remove(&(pending.expires_at, challenge.to_string()));
+        if let Some(count) = self.peers.get_mut(&pending.peer) {
+            *count -= 1;
+            if *count == 0 {
+                self.peers.remove(&pending.peer);
+            }
+        }
+        Some(pending.expires_at)
+    }
+
+    /// Maps peer address to the key its challenges are counted under, IPv6
+    /// addresses are reduced to their /64 prefix, as a single host usually
+    /// controls a whole prefix.
+    fn peer_key(peer: IpAddr) -> IpAddr {
+        match peer {
+            IpAddr::V4(_) => peer,
+            IpAddr::V6(ip) => {
+                let prefix = u128::from(ip) & !((1u128 << 64) - 1);
+                IpAddr::V6(Ipv6Addr::from(prefix))
+            }
+        }
+    }
+}
+
+fn derive_challenge(
+    psk_sp: &PublicSpendKey,
+    nonce: &[u8],
+    expires_at: u64,
+) -> JubJubScalar {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(b"moat-challenge");
+    hasher.update(&psk_sp.to_bytes());
+    hasher.update(nonce);
+    hasher.update(&expires_at.to_le_bytes());
+    let mut wide = [0u8; 64];
+    hasher.finalize_xof().fill(&mut wide);
+    JubJubScalar::from_bytes_wide(&wide)
+}
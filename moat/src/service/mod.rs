@@ -0,0 +1,26 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+mod challenges;
+mod proof_verifier;
+mod service_client;
+mod service_types;
+mod service_verifier;
+mod used_sessions;
+
+pub use challenges::{
+    Challenge, ChallengeIssuer, DEFAULT_CHALLENGE_TTL, MAX_PENDING_CHALLENGES,
+    MAX_PENDING_CHALLENGES_PER_PEER,
+};
+pub use proof_verifier::{
+    ProofVerifier, MAX_RECENT_ROOTS, MERKLE_ROOT_PUBLIC_INPUT_INDEX,
+};
+pub use service_client::ServiceClient;
+pub use service_types::{
+    ServiceRequest, ServiceResponse, CHALLENGE_ENDPOINT, SERVICE_ENDPOINT,
+};
+pub use service_verifier::ServiceVerifier;
+pub use used_sessions::UsedSessions;
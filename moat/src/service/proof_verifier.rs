@@ -0,0 +1,131 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::{CitadelInquirer, Error, UseLicenseArg};
+use dusk_bls12_381::BlsScalar;
+use dusk_jubjub::JubJubAffine;
+use dusk_pki::PublicSpendKey;
+use dusk_plonk::prelude::Verifier;
+use dusk_wallet::RuskHttpClient;
+use std::collections::VecDeque;
+use zk_citadel::license::{Session, SessionCookie};
+
+/// Index of the licenses merkle tree root among the public inputs of the
+/// license circuit, which follows the session's public inputs.
+pub const MERKLE_ROOT_PUBLIC_INPUT_INDEX: usize = 7;
+
+/// Maximum number of recent on-chain merkle roots accepted by default.
+pub const MAX_RECENT_ROOTS: usize = 16;
+
+/// Verifies proofs of the use of licenses locally, on behalf of a Service
+/// Provider, before the use license transaction is final.
+/// Proofs must have been computed against one of the recent roots of the
+/// licenses merkle tree observed on-chain, so that roots of trees made up by
+/// the user are rejected.
+pub struct ProofVerifier {
+    verifier: Verifier,
+    recent_roots: VecDeque<BlsScalar>,
+    max_recent_roots: usize,
+}
+
+impl ProofVerifier {
+    /// Creates proof verifier with the verifier of the compiled
+    /// `LicenseCircuit`.
+    pub fn new(verifier: Verifier) -> Self {
+        Self {
+            verifier,
+            recent_roots: VecDeque::new(),
+            max_recent_roots: MAX_RECENT_ROOTS,
+        }
+    }
+
+    /// Sets maximum number of recent roots accepted, the oldest roots are
+    /// forgotten first.
+    pub fn with_max_recent_roots(mut self, max_recent_roots: usize) -> Self {
+        self.max_recent_roots = max_recent_roots.max(1);
+        self
+    }
+
+    pub fn recent_roots(&self) -> impl Iterator<Item = &BlsScalar> {
+        self.recent_roots.iter()
+    }
+
+    /// Records a merkle root observed on-chain.
+    pub fn insert_root(&mut self, root: BlsScalar) {
+        if self.recent_roots.contains(&root) {
+            return;
+        }
+        self.recent_roots.push_back(root);
+        while self.recent_roots.len() > self.max_recent_roots {
+            self.recent_roots.pop_front();
+        }
+    }
+
+    /// Records the current on-chain merkle root.
+    pub async fn sync_root(
+        &mut self,
+        client: &RuskHttpClient,
+    ) -> Result<(), Error> {
+        if let Some(root) = CitadelInquirer::get_merkle_root(client).await? {
+            self.insert_root(root);
+        }
+        Ok(())
+    }
+
+    /// Provides merkle root the proof was computed against, or None if the
+    /// public inputs are too short.
+    pub fn merkle_root(use_license_arg: &UseLicenseArg) -> Option<BlsScalar> {
+        // public inputs are in negated form
+        use_license_arg
+            .public_inputs
+            .get(MERKLE_ROOT_PUBLIC_INPUT_INDEX)
+            .map(|root| -root)
+    }
+
+    /// Verifies the proof and checks that it was computed against one of
+    /// the recent roots, without contacting the blockchain.
+    pub fn verify(&self, use_license_arg: &UseLicenseArg) -> Result<(), Error> {
+        let root = Self::merkle_root(use_license_arg).ok_or(
+            Error::InvalidProof("merkle root missing in public inputs".into()),
+        )?;
+        if !self.recent_roots.contains(&root) {
+            return Err(Error::InvalidProof("unknown merkle root".into()));
+        }
+        self.verifier
+            .verify(&use_license_arg.proof, &use_license_arg.public_inputs)
+            .map_err(|_| Error::InvalidProof("proof does not verify".into()))
+    }
+
+    /// Records the current on-chain merkle root, then verifies the proof
+    /// and checks its merkle root.
+    pub async fn verify_with_chain(
+        &mut self,
+        client: &RuskHttpClient,
+        use_license_arg: &UseLicenseArg,
+    ) -> Result<(), Error> {
+        self.sync_root(client).await?;
+        self.verify(use_license_arg)
+    }
+
+    /// Checks that a session cookie corresponds to the session proven by the
+    /// use license argument, created for given License Provider and Service
+    /// Provider.
+    pub fn verify_session_cookie(
+        use_license_arg: &UseLicenseArg,
+        session_cookie: &SessionCookie,
+        psk_lp: &PublicSpendKey,
+        psk_sp: &PublicSpendKey,
+    ) -> bool {
+        if use_license_arg.public_inputs.len() < MERKLE_ROOT_PUBLIC_INPUT_INDEX
+        {
+            return false;
+        }
+        let pk_lp = JubJubAffine::from(*psk_lp.A());
+        let pk_sp = JubJubAffine::from(*psk_sp.A());
+        let session = Session::from(&use_license_arg.public_inputs);
+        session.verifies_ok(*session_cookie, pk_lp, pk_sp)
+    }
+}
@@ -0,0 +1,68 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::service::{
+    Challenge, ServiceRequest, ServiceResponse, CHALLENGE_ENDPOINT,
+    SERVICE_ENDPOINT,
+};
+use crate::Error;
+use reqwest::header::CONTENT_TYPE;
+use tracing::trace;
+
+/// Client sending service requests to Service Providers.
+pub struct ServiceClient;
+
+impl ServiceClient {
+    /// Sends a service request to the Service Provider at a given address,
+    /// e.g. `http://127.0.0.1:9090`, and returns its decision.
+    /// Fails if the Service Provider could not verify the request.
+    pub async fn request_service(
+        sp_address: impl AsRef<str>,
+        request: &ServiceRequest,
+    ) -> Result<ServiceResponse, Error> {
+        let url = format!(
+            "{}{}",
+            sp_address.as_ref().trim_end_matches('/'),
+            SERVICE_ENDPOINT
+        );
+        trace!("sending service request to {}", url);
+        let response = reqwest::Client::new()
+            .post(url)
+            .header(CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(request)?)
+            .send()
+            .await?;
+        let status = response.status();
+        let body = response.bytes().await?;
+        if !(status.is_success() || status.is_client_error()) {
+            return Err(Error::InvalidQueryResponse(
+                format!("service provider responded with {}", status).into(),
+            ));
+        }
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    /// Fetches a challenge from the Service Provider at a given address.
+    pub async fn get_challenge(
+        sp_address: impl AsRef<str>,
+    ) -> Result<Challenge, Error> {
+        let url = format!(
+            "{}{}",
+            sp_address.as_ref().trim_end_matches('/'),
+            CHALLENGE_ENDPOINT
+        );
+        trace!("fetching challenge from {}", url);
+        let response = reqwest::Client::new().get(url).send().await?;
+        let status = response.status();
+        let body = response.bytes().await?;
+        if !status.is_success() {
+            return Err(Error::InvalidQueryResponse(
+                format!("service provider responded with {}", status).into(),
+            ));
+        }
+        Ok(serde_json::from_slice(&body)?)
+    }
+}
@@ -0,0 +1,76 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::utils::MoatCoreUtils;
+use crate::Error;
+use dusk_bytes::Serializable;
+use dusk_pki::PublicSpendKey;
+use zk_citadel::license::SessionCookie;
+
+/// Path of the Service Provider's endpoint accepting service requests.
+pub const SERVICE_ENDPOINT: &str = "/service";
+
+/// Path of the Service Provider's endpoint issuing challenges.
+pub const CHALLENGE_ENDPOINT: &str = "/challenge";
+
+/// Request for a service, sent by the user to the Service Provider as JSON.
+/// Session cookie is a hex encoded rkyv blob, License Provider's public
+/// spend key is bs58 encoded.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct ServiceRequest {
+    pub session_cookie: String,
+    pub psk_lp: String,
+}
+
+impl ServiceRequest {
+    pub fn new(
+        session_cookie: &SessionCookie,
+        psk_lp: &PublicSpendKey,
+    ) -> Self {
+        Self {
+            session_cookie: MoatCoreUtils::to_blob_hex(session_cookie),
+            psk_lp: bs58::encode(psk_lp.to_bytes()).into_string(),
+        }
+    }
+
+    pub fn session_cookie(&self) -> Result<SessionCookie, Error> {
+        let bytes = hex::decode(&self.session_cookie)?;
+        rkyv::from_bytes(bytes.as_slice()).map_err(|_| {
+            Error::InvalidServiceRequest("invalid session cookie".into())
+        })
+    }
+
+    pub fn psk_lp(&self) -> Result<PublicSpendKey, Error> {
+        let bytes = bs58::decode(&self.psk_lp).into_vec().map_err(|_| {
+            Error::InvalidServiceRequest("invalid LP public key".into())
+        })?;
+        Ok(PublicSpendKey::from_slice(bytes.as_slice())?)
+    }
+}
+
+/// Service Provider's response to a service request.
+/// Reason is given when the service is denied.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct ServiceResponse {
+    pub granted: bool,
+    pub reason: Option<String>,
+}
+
+impl ServiceResponse {
+    pub fn grant() -> Self {
+        Self {
+            granted: true,
+            reason: None,
+        }
+    }
+
+    pub fn deny(reason: impl Into<String>) -> Self {
+        Self {
+            granted: false,
+            reason: Some(reason.into()),
+        }
+    }
+}
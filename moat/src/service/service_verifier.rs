@@ -0,0 +1,160 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::service::{
+    Challenge, ChallengeIssuer, ServiceRequest, ServiceResponse, UsedSessions,
+};
+use crate::wallet_accessor::BlockchainAccessConfig;
+use crate::{CitadelInquirer, Error, LicenseSessionId};
+use dusk_bytes::Serializable;
+use dusk_jubjub::JubJubAffine;
+use dusk_pki::PublicSpendKey;
+use dusk_wallet::RuskHttpClient;
+use rand::{CryptoRng, RngCore};
+use std::net::IpAddr;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::trace;
+use zk_citadel::license::{Session, SessionCookie};
+
+/// Challenges and used sessions, updated atomically.
+struct VerifierState {
+    used_sessions: UsedSessions,
+    challenges: ChallengeIssuer,
+}
+
+/// Verifies service requests on behalf of a Service Provider.
+/// Each session can be used to obtain the service only once, and must have
+/// been created with a challenge issued by the Service Provider.
+/// Requests can be verified concurrently, the blockchain is queried without
+/// holding the lock on the challenges and used sessions.
+pub struct ServiceVerifier {
+    config: BlockchainAccessConfig,
+    psk_sp: PublicSpendKey,
+    state: Mutex<VerifierState>,
+}
+
+impl ServiceVerifier {
+    /// Creates verifier issuing challenges valid for a given time-to-live.
+    pub fn new(
+        config: BlockchainAccessConfig,
+        psk_sp: PublicSpendKey,
+        used_sessions: UsedSessions,
+        challenge_ttl: Duration,
+    ) -> Self {
+        Self {
+            config,
+            psk_sp,
+            state: Mutex::new(VerifierState {
+                used_sessions,
+                challenges: ChallengeIssuer::new(psk_sp, challenge_ttl),
+            }),
+        }
+    }
+
+    /// Issues a challenge to a given peer, to be used by the user when
+    /// proving the use of a license. Returns None if the peer has too many
+    /// pending challenges.
+    pub fn issue_challenge<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        peer: IpAddr,
+    ) -> Option<Challenge> {
+        self.state().challenges.issue(rng, peer, Self::now())
+    }
+
+    /// Verifies a service request, granting the service if the session
+    /// cookie corresponds to an existing, unused session in the blockchain,
+    /// created with a pending challenge issued by this verifier.
+    /// Malformed requests are denied, errors are only returned when the
+    /// blockchain or the local state cannot be accessed.
+    pub async fn verify(
+        &self,
+        request: &ServiceRequest,
+    ) -> Result<ServiceResponse, Error> {
+        let (session_cookie, psk_lp) =
+            match (request.session_cookie(), request.psk_lp()) {
+                (Ok(session_cookie), Ok(psk_lp)) => (session_cookie, psk_lp),
+                (Err(e), _) | (_, Err(e)) => {
+                    return Ok(ServiceResponse::deny(e.to_string()))
+                }
+            };
+        let session_id = hex::encode(session_cookie.session_id.to_bytes());
+        let used = self.state().used_sessions.contains(&session_id);
+        if used {
+            trace!("session {} already used", session_id);
+            return Ok(ServiceResponse::deny("session already used"));
+        }
+        let client = RuskHttpClient::new(self.config.rusk_address.clone());
+        match Self::verify_session_cookie(
+            &client,
+            &session_cookie,
+            &psk_lp,
+            &self.psk_sp,
+        )
+        .await
+        {
+            Ok(true) => {
+                let mut state = self.state();
+                // session might have been used by a concurrent request
+                // while the blockchain was queried
+                if state.used_sessions.contains(&session_id) {
+                    return Ok(ServiceResponse::deny("session already used"));
+                }
+                if !state.challenges.redeem(&session_cookie.c, Self::now()) {
+                    return Ok(ServiceResponse::deny(
+                        "challenge not issued, expired or already used",
+                    ));
+                }
+                state.used_sessions.insert(session_id)?;
+                Ok(ServiceResponse::grant())
+            }
+            Ok(false) => {
+                Ok(ServiceResponse::deny("session cookie does not verify"))
+            }
+            Err(Error::SessionNotFound) => {
+                Ok(ServiceResponse::deny("session not found"))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Checks that a session cookie corresponds to an existing session in
+    /// the blockchain, created for given License Provider and Service
+    /// Provider.
+    pub async fn verify_session_cookie(
+        client: &RuskHttpClient,
+        session_cookie: &SessionCookie,
+        psk_lp: &PublicSpendKey,
+        psk_sp: &PublicSpendKey,
+    ) -> Result<bool, Error> {
+        let pk_lp = JubJubAffine::from(*psk_lp.A());
+        let pk_sp = JubJubAffine::from(*psk_sp.A());
+
+        let session_id = LicenseSessionId {
+            id: session_cookie.session_id,
+        };
+        let session = CitadelInquirer::get_session(client, session_id)
+            .await?
+            .ok_or(Error::SessionNotFound)?;
+
+        let session = Session::from(&session.public_inputs);
+        Ok(session.verifies_ok(*session_cookie, pk_lp, pk_sp))
+    }
+
+    fn state(&self) -> MutexGuard<'_, VerifierState> {
+        self.state
+            .lock()
+            .expect("Verifier state lock should not be poisoned")
+    }
+
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,83 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::atomic_file::AtomicFile;
+use crate::{Error, JsonLoader};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Serialized form of the used sessions, with hex encoded session ids.
+#[derive(Debug, Default, Clone, serde::Deserialize, serde::Serialize)]
+struct UsedSessionsFile {
+    session_ids: BTreeSet<String>,
+}
+
+impl JsonLoader for UsedSessionsFile {}
+
+/// Ids of sessions for which the service has already been granted, so that
+/// a session cookie cannot be replayed.
+#[derive(Debug, Default)]
+pub struct UsedSessions {
+    path: Option<PathBuf>,
+    session_ids: BTreeSet<String>,
+}
+
+impl UsedSessions {
+    /// Creates an empty set which is not persisted.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads used sessions from a given file, or creates an empty set if the
+    /// file does not exist.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        let session_ids = if path.exists() {
+            UsedSessionsFile::from_file(&path)?.session_ids
+        } else {
+            BTreeSet::new()
+        };
+        Ok(Self {
+            path: Some(path),
+            session_ids,
+        })
+    }
+
+    pub fn contains(&self, session_id: impl AsRef<str>) -> bool {
+        self.session_ids.contains(session_id.as_ref())
+    }
+
+    /// Marks session as used and saves the set.
+    /// Returns false if the session has been used before.
+    pub fn insert(
+        &mut self,
+        session_id: impl Into<String>,
+    ) -> Result<bool, Error> {
+        if !self.session_ids.insert(session_id.into()) {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    pub fn len(&self) -> usize {
+        self.session_ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.session_ids.is_empty()
+    }
+
+    fn save(&self) -> Result<(), Error> {
+        if let Some(path) = self.path.as_ref() {
+            let used_sessions_file = UsedSessionsFile {
+                session_ids: self.session_ids.clone(),
+            };
+            AtomicFile::write(path, serde_json::to_vec(&used_sessions_file)?)?;
+        }
+        Ok(())
+    }
+}
@@ -10,32 +10,18 @@ use dusk_pki::{PublicSpendKey, SecretSpendKey};
 use dusk_wallet::WalletPath;
 use rand::rngs::OsRng;
 
-use zk_citadel::license::{License, SessionCookie};
+use zk_citadel::license::License;
 
 use dusk_jubjub::JubJubScalar;
 
 use crate::{
-    BcInquirer, CitadelInquirer, CrsGetter, Error, LicenseCircuit, LicenseUser,
-    TxAwaiter,
+    AwaitConfig, CircuitKeyStore, CitadelInquirer, Error, LicenseCache,
+    LicenseUser, TxAwaiter, UseLicenseReceipt,
 };
 use dusk_wallet::RuskHttpClient;
 use rkyv::ser::serializers::AllocSerializer;
 use sha3::{Digest, Sha3_256};
 
-use dusk_bls12_381::BlsScalar;
-
-use dusk_plonk::prelude::*;
-
-use std::fs::File;
-use std::io::prelude::*;
-
-static LABEL: &[u8] = b"dusk-network";
-
-pub struct SetupHolder {
-    pub prover: Prover,
-    pub verifier: Verifier,
-}
-
 pub struct MoatCoreUtils {}
 
 const MAX_OBJECT_SIZE: usize = 16384;
@@ -75,35 +61,26 @@ impl MoatCoreUtils {
             .to_vec()
     }
 
+    /// Provides license with a given license hash, owned by a given secret
+    /// key, along with its position. Licenses are looked up in the license
+    /// cache, which is synced with the blockchain first.
     pub async fn get_license_to_use(
-        blockchain_access_config: &BlockchainAccessConfig,
+        client: &RuskHttpClient,
+        license_cache: &mut LicenseCache,
         ssk: &SecretSpendKey,
         license_hash: String,
     ) -> Result<Option<(u64, License)>, Error> {
-        let client =
-            RuskHttpClient::new(blockchain_access_config.rusk_address.clone());
-        let end_height = BcInquirer::block_height(&client).await?;
-        let block_heights = 0..(end_height + 1);
+        license_cache.sync(client).await?;
 
-        let mut licenses_stream =
-            CitadelInquirer::get_licenses(&client, block_heights).await?;
-
-        let pairs =
-            CitadelInquirer::find_owned_licenses(*ssk, &mut licenses_stream)?;
-        Ok(if pairs.is_empty() {
-            None
-        } else {
-            for (pos, license) in pairs.iter() {
-                if license_hash == Self::to_hash_hex(license) {
-                    return Ok(Some((*pos, license.clone())));
-                }
-            }
-            None
-        })
+        Ok(license_cache
+            .get_by_hash(license_hash)
+            .filter(|(_, license)| ssk.view_key().owns(&license.lsa))
+            .map(|(pos, license)| (pos, license.clone())))
     }
 
     #[allow(clippy::too_many_arguments)]
     pub async fn prove_and_send_use_license(
+        client: &RuskHttpClient,
         blockchain_access_config: &BlockchainAccessConfig,
         wallet_path: &WalletPath,
         psw: &Password,
@@ -115,60 +92,13 @@ impl MoatCoreUtils {
         pos: u64,
         gas_limit: u64,
         gas_price: u64,
-        sh_opt: &mut Option<SetupHolder>,
+        await_config: &AwaitConfig,
+        key_store: &mut CircuitKeyStore,
         rng: &mut OsRng,
-    ) -> Result<(BlsScalar, SessionCookie), Error> {
-        let client =
-            RuskHttpClient::new(blockchain_access_config.rusk_address.clone());
-
-        let setup_holder = match sh_opt {
-            Some(sh) => sh,
-            _ => {
-                let wallet_dir_path = match wallet_path.dir() {
-                    Some(path) => path,
-                    None => panic!(),
-                };
+    ) -> Result<UseLicenseReceipt, Error> {
+        let circuit_keys = key_store.get(client).await?;
 
-                let prover_path = &wallet_dir_path.join("moat_prover.dat");
-                let verifier_path = &wallet_dir_path.join("moat_verifier.dat");
-
-                if prover_path.exists() && verifier_path.exists() {
-                    let mut file = File::open(prover_path)?;
-                    let mut prover_bytes = vec![];
-                    file.read_to_end(&mut prover_bytes)?;
-                    let prover = Prover::try_from_bytes(prover_bytes)?;
-
-                    file = File::open(verifier_path)?;
-                    let mut verifier_bytes = vec![];
-                    file.read_to_end(&mut verifier_bytes)?;
-                    let verifier = Verifier::try_from_bytes(verifier_bytes)?;
-
-                    let sh = SetupHolder { prover, verifier };
-                    *sh_opt = Some(sh);
-                    sh_opt.as_ref().expect("setup holder is not empty")
-                } else {
-                    let pp_vec = CrsGetter::get_crs(&client).await?;
-                    let pp =
-                        // SAFETY: CRS vector is checked by the hash check when it is received from the node
-                        unsafe { PublicParameters::from_slice_unchecked(pp_vec.as_slice()) };
-                    let (prover, verifier) =
-                        Compiler::compile::<LicenseCircuit>(&pp, LABEL)
-                            .expect("Compiling circuit should succeed");
-
-                    let mut file = File::create(prover_path)?;
-                    file.write_all(prover.to_bytes().as_slice())?;
-
-                    file = File::create(verifier_path)?;
-                    file.write_all(verifier.to_bytes().as_slice())?;
-
-                    let sh = SetupHolder { prover, verifier };
-                    *sh_opt = Some(sh);
-                    sh_opt.as_ref().expect("setup holder is not empty")
-                }
-            }
-        };
-
-        let opening = CitadelInquirer::get_merkle_opening(&client, pos)
+        let opening = CitadelInquirer::get_merkle_opening(client, pos)
             .await?
             .expect("Opening obtained successfully");
 
@@ -179,8 +109,8 @@ impl MoatCoreUtils {
             ssk_user,
             psk_lp,
             psk_sp,
-            &setup_holder.prover,
-            &setup_holder.verifier,
+            &circuit_keys.prover,
+            &circuit_keys.verifier,
             license,
             opening,
             rng,
@@ -189,7 +119,16 @@ impl MoatCoreUtils {
             gas_price,
         )
         .await?;
-        TxAwaiter::wait_for(&client, tx_id).await?;
-        Ok((tx_id, session_cookie))
+        let (block_height, gas_spent) =
+            TxAwaiter::wait_for_outcome(client, tx_id, await_config)
+                .await?
+                .into_result()?;
+        Ok(UseLicenseReceipt::new(
+            &tx_id,
+            block_height,
+            gas_spent,
+            license,
+            &session_cookie,
+        ))
     }
 }
@@ -9,7 +9,9 @@ use crate::wallet_accessor::Password::{Pwd, PwdHash};
 use dusk_bls12_381::BlsScalar;
 use dusk_wallet::dat::{read_file_version, DatFileVersion};
 use dusk_wallet::gas::Gas;
-use dusk_wallet::{DecodedNote, Error, SecureWalletFile, Wallet, WalletPath};
+use dusk_wallet::{
+    Address, DecodedNote, Error, SecureWalletFile, Wallet, WalletPath,
+};
 use dusk_wallet_core::MAX_CALL_SIZE;
 use phoenix_core::transaction::ModuleId;
 use rkyv::ser::serializers::AllocSerializer;
@@ -70,7 +72,24 @@ impl WalletAccessor {
         })
     }
 
-    async fn get_wallet(
+    /// Provides the wallet address with a given index, or the default
+    /// address if no index is given.
+    pub(crate) fn address(
+        wallet: &Wallet<WalletAccessor>,
+        address_index: Option<usize>,
+    ) -> Result<&Address, crate::Error> {
+        match address_index {
+            Some(index) => wallet.addresses().get(index).ok_or_else(|| {
+                crate::Error::LocalState(
+                    format!("wallet address with index {} not found", index)
+                        .into(),
+                )
+            }),
+            None => Ok(wallet.default_address()),
+        }
+    }
+
+    pub(crate) async fn get_wallet(
         &self,
         cfg: &BlockchainAccessConfig,
     ) -> Result<Wallet<WalletAccessor>, dusk_wallet::Error> {
@@ -4,13 +4,39 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+use crate::Error;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use toml_base_config::BaseConfig;
 
 #[derive(Debug, Default, Deserialize, Serialize, Clone)]
 pub struct BlockchainAccessConfig {
     pub rusk_address: String,
     pub prover_address: String,
+    /// Hex encoded, known-good SHA-256 hash of the CRS. When given, CRS
+    /// obtained from the node or from a local file must match it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crs_hash: Option<String>,
+    /// Local CRS file, used instead of downloading the CRS from the node.
+    /// Without the pinned hash, the CRS is fully validated when loaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crs_path: Option<PathBuf>,
+}
+
+impl BlockchainAccessConfig {
+    /// Provides the pinned CRS hash, if given.
+    pub fn pinned_crs_hash(&self) -> Result<Option<[u8; 32]>, Error> {
+        match &self.crs_hash {
+            Some(crs_hash) => {
+                let mut hash = [0u8; 32];
+                hex::decode_to_slice(crs_hash, &mut hash).map_err(|_| {
+                    Error::CRS("invalid pinned CRS hash".into())
+                })?;
+                Ok(Some(hash))
+            }
+            None => Ok(None),
+        }
+    }
 }
 
 impl BaseConfig for BlockchainAccessConfig {
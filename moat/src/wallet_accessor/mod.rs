@@ -6,6 +6,8 @@
 
 pub mod accessor;
 mod config;
+mod session;
 
 pub use accessor::{Password, WalletAccessor};
 pub use config::BlockchainAccessConfig;
+pub use session::{PendingTx, WalletSession};
@@ -0,0 +1,319 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::wallet_accessor::{
+    BlockchainAccessConfig, Password, WalletAccessor,
+};
+use crate::{AwaitConfig, Error, TxAwaiter, TxOutcome};
+use dusk_bls12_381::BlsScalar;
+use dusk_wallet::gas::Gas;
+use dusk_wallet::{DecodedNote, RuskHttpClient, Wallet, WalletPath};
+use dusk_wallet_core::MAX_CALL_SIZE;
+use phoenix_core::transaction::ModuleId;
+use phoenix_core::Transaction;
+use rkyv::ser::serializers::AllocSerializer;
+use std::sync::Arc;
+use tracing::debug;
+
+/// Transaction submitted within a wallet session, along with the nullifiers
+/// of the notes it spends.
+#[derive(Debug, Clone)]
+pub struct PendingTx {
+    pub tx_id: BlsScalar,
+    pub nullifiers: Vec<BlsScalar>,
+    /// Transaction has not been included within the await timeout. It might
+    /// still be included, so its notes stay reserved until a transaction
+    /// submitted after it is included, or until a transaction spending the
+    /// same notes is submitted.
+    pub dropped: bool,
+}
+
+/// Connected and synced wallet, reused for submitting many transactions.
+/// Input notes of the submitted transactions are reserved until the
+/// transactions are included. Input notes are selected by the wallet, which
+/// is not aware of the reservations, so pending transactions reserving notes
+/// which are not spent yet are awaited before another transaction is
+/// submitted.
+pub struct WalletSession {
+    wallet: Wallet<WalletAccessor>,
+    client: RuskHttpClient,
+    await_config: AwaitConfig,
+    address_index: Option<usize>,
+    pending: Vec<PendingTx>,
+}
+
+impl WalletSession {
+    /// Opens wallet at a given path, connects it and syncs it.
+    pub async fn open(
+        wallet_path: WalletPath,
+        password: Password,
+        cfg: &BlockchainAccessConfig,
+    ) -> Result<Self, Error> {
+        let wallet_accessor = WalletAccessor::create(wallet_path, password)?;
+        let wallet = wallet_accessor.get_wallet(cfg).await?;
+        Ok(Self {
+            wallet,
+            client: RuskHttpClient::new(cfg.rusk_address.clone()),
+            await_config: AwaitConfig::default(),
+            address_index: None,
+            pending: Vec::new(),
+        })
+    }
+
+    /// Sets client used for querying the pending transactions, instead of
+    /// the client created from the configured Rusk address.
+    pub fn with_client(mut self, client: RuskHttpClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Sets configuration of waiting for the pending transactions.
+    pub fn with_await_config(mut self, await_config: AwaitConfig) -> Self {
+        self.await_config = await_config;
+        self
+    }
+
+    /// Sets index of the wallet address paying for the transactions, the
+    /// default address is used if no index is given.
+    pub fn with_address_index(mut self, address_index: Option<usize>) -> Self {
+        self.address_index = address_index;
+        self
+    }
+
+    pub fn wallet(&self) -> &Wallet<WalletAccessor> {
+        &self.wallet
+    }
+
+    /// Transactions submitted in this session whose input notes are still
+    /// reserved.
+    pub fn pending_txs(&self) -> &[PendingTx] {
+        self.pending.as_slice()
+    }
+
+    /// Returns true if a given nullifier is spent by a pending transaction.
+    pub fn is_pending_nullifier(&self, nullifier: &BlsScalar) -> bool {
+        self.pending
+            .iter()
+            .any(|pending_tx| pending_tx.nullifiers.contains(nullifier))
+    }
+
+    /// Syncs the wallet with the blockchain, only blocks not seen before
+    /// are processed.
+    pub async fn sync(&mut self) -> Result<(), Error> {
+        self.wallet.sync().await?;
+        Ok(())
+    }
+
+    /// Provides value of the notes of the sender address which are neither
+    /// spent nor reserved by pending transactions.
+    pub async fn available_value(&self) -> Result<u64, Error> {
+        Ok(self
+            .unspent_notes()
+            .await?
+            .iter()
+            .filter(|(nullifier, _)| !self.is_pending_nullifier(nullifier))
+            .map(|(_, amount)| amount)
+            .sum())
+    }
+
+    /// Returns true if a note of the sender address which is not spent yet
+    /// is reserved by a pending transaction which has not been dropped.
+    async fn has_reserved_notes(&self) -> Result<bool, Error> {
+        Ok(self.unspent_notes().await?.iter().any(|(nullifier, _)| {
+            self.pending.iter().any(|pending_tx| {
+                !pending_tx.dropped && pending_tx.nullifiers.contains(nullifier)
+            })
+        }))
+    }
+
+    /// Provides nullifiers and amounts of the notes of the sender address
+    /// which are not spent yet.
+    async fn unspent_notes(&self) -> Result<Vec<(BlsScalar, u64)>, Error> {
+        let sender = WalletAccessor::address(&self.wallet, self.address_index)?;
+        let (_psk, ssk) = self
+            .wallet
+            .spending_keys(sender)
+            .map_err(|e| Error::DuskWallet(Arc::from(e)))?;
+        let notes = self.wallet.get_all_notes(sender).await?;
+        Ok(notes
+            .iter()
+            .filter(|decoded| decoded.nullified_by.is_none())
+            .map(|decoded| (decoded.note.gen_nullifier(&ssk), decoded.amount))
+            .collect())
+    }
+
+    /// Releases notes of the pending transactions which have been included
+    /// since, as well as notes of the dropped transactions submitted before
+    /// an included one. Does not wait.
+    async fn update_pending(&mut self) -> Result<(), Error> {
+        let mut included = Vec::new();
+        for (i, pending_tx) in self.pending.iter().enumerate() {
+            if !pending_tx.dropped
+                && TxAwaiter::query_outcome(&self.client, pending_tx.tx_id)
+                    .await?
+                    .is_some()
+            {
+                included.push(i);
+            }
+        }
+        self.release(&included);
+        Ok(())
+    }
+
+    /// Removes pending transactions with given indices, which have been
+    /// included, along with the dropped transactions submitted before the
+    /// last of them.
+    fn release(&mut self, included: &[usize]) {
+        let last_included = match included.last() {
+            Some(last_included) => *last_included,
+            None => return,
+        };
+        let mut i = 0;
+        self.pending.retain(|pending_tx| {
+            let released = included.contains(&i)
+                || (pending_tx.dropped && i < last_included);
+            if released {
+                debug!(
+                    "releasing {} input notes of tx {}",
+                    pending_tx.nullifiers.len(),
+                    hex::encode(pending_tx.tx_id.to_bytes())
+                );
+            }
+            i += 1;
+            !released
+        });
+    }
+
+    /// Waits for the oldest pending transaction which has not been dropped.
+    /// Returns false if there is no such transaction.
+    async fn wait_for_oldest(&mut self) -> Result<bool, Error> {
+        let index = match self.pending.iter().position(|tx| !tx.dropped) {
+            Some(index) => index,
+            None => return Ok(false),
+        };
+        // input notes are spent as soon as the transaction is included
+        let await_config = AwaitConfig {
+            confirmations: 0,
+            ..self.await_config.clone()
+        };
+        let outcome = TxAwaiter::wait_for_outcome(
+            &self.client,
+            self.pending[index].tx_id,
+            &await_config,
+        )
+        .await?;
+        if let TxOutcome::Dropped = outcome {
+            debug!(
+                "tx {} dropped, keeping its input notes reserved",
+                hex::encode(self.pending[index].tx_id.to_bytes())
+            );
+            self.pending[index].dropped = true;
+        } else {
+            self.release(&[index]);
+        }
+        Ok(true)
+    }
+
+    /// Waits for all pending transactions to be either included or dropped,
+    /// and syncs the wallet. Notes of the dropped transactions stay
+    /// reserved.
+    pub async fn settle(&mut self) -> Result<(), Error> {
+        while self.wait_for_oldest().await? {}
+        self.sync().await
+    }
+
+    /// Submits a transaction which will execute a given method of a given
+    /// contract. Pending transactions are awaited first if they reserve notes
+    /// which are not spent yet, so that the transaction does not spend
+    /// reserved notes, or if the available notes do not cover the fee.
+    /// Dropped transactions whose notes are spent by the transaction are
+    /// superseded by it.
+    pub async fn execute_contract_method<P, M>(
+        &mut self,
+        payload: P,
+        contract_id: ModuleId,
+        method: M,
+        gas_limit: u64,
+        gas_price: u64,
+    ) -> Result<BlsScalar, Error>
+    where
+        P: rkyv::Serialize<AllocSerializer<MAX_CALL_SIZE>>,
+        M: AsRef<str>,
+    {
+        self.update_pending().await?;
+        self.sync().await?;
+        let fee = gas_limit.saturating_mul(gas_price);
+        while self.has_reserved_notes().await?
+            || self.available_value().await? < fee
+        {
+            if !self.wait_for_oldest().await? {
+                break;
+            }
+            self.sync().await?;
+        }
+
+        let tx = self
+            .submit(payload, contract_id, method.as_ref(), gas_limit, gas_price)
+            .await?;
+        let tx_id = rusk_abi::hash::Hasher::digest(tx.to_hash_input_bytes());
+        self.pending.retain(|pending_tx| {
+            let superseded = pending_tx.dropped
+                && pending_tx
+                    .nullifiers
+                    .iter()
+                    .any(|n| tx.nullifiers.contains(n));
+            if superseded {
+                debug!(
+                    "dropped tx {} superseded by tx {}",
+                    hex::encode(pending_tx.tx_id.to_bytes()),
+                    hex::encode(tx_id.to_bytes())
+                );
+            }
+            !superseded
+        });
+        self.pending.push(PendingTx {
+            tx_id,
+            nullifiers: tx.nullifiers.clone(),
+            dropped: false,
+        });
+        Ok(tx_id)
+    }
+
+    async fn submit<P>(
+        &self,
+        payload: P,
+        contract_id: ModuleId,
+        method: &str,
+        gas_limit: u64,
+        gas_price: u64,
+    ) -> Result<Transaction, Error>
+    where
+        P: rkyv::Serialize<AllocSerializer<MAX_CALL_SIZE>>,
+    {
+        debug!(
+            "Sending tx with a call to method '{}' of contract='{}'",
+            method,
+            hex::encode(contract_id)
+        );
+
+        let sender = WalletAccessor::address(&self.wallet, self.address_index)?;
+        let mut gas = Gas::new(gas_limit);
+        gas.set_price(gas_price);
+
+        let tx = self
+            .wallet
+            .execute(sender, contract_id, method.to_string(), payload, gas)
+            .await?;
+        Ok(tx)
+    }
+
+    /// Provides all notes belonging to the sender address.
+    pub async fn get_notes(&self) -> Result<Vec<DecodedNote>, Error> {
+        let sender = WalletAccessor::address(&self.wallet, self.address_index)?;
+        let notes = self.wallet.get_all_notes(sender).await?;
+        Ok(notes)
+    }
+}
@@ -0,0 +1,38 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use zk_citadel_moat::{BlockHashes, MAX_TRACKED_BLOCKS};
+
+#[test]
+fn block_hashes_rollback() {
+    let mut block_hashes = BlockHashes::new();
+    for height in 10..20u64 {
+        block_hashes.record(height, format!("hash{}", height));
+    }
+    assert_eq!(block_hashes.last(), Some((19, "hash19")));
+
+    block_hashes.rollback(14);
+
+    assert_eq!(block_hashes.last(), Some((14, "hash14")));
+    assert_eq!(block_hashes.get(15), None);
+    assert_eq!(block_hashes.get(10), Some("hash10"));
+}
+
+#[test]
+fn block_hashes_bounded() {
+    let mut block_hashes = BlockHashes::new();
+    let count = MAX_TRACKED_BLOCKS as u64 + 10;
+    for height in 0..count {
+        block_hashes.record(height, format!("hash{}", height));
+    }
+    // empty hashes are not recorded
+    block_hashes.record(count, "");
+
+    assert_eq!(block_hashes.get(9), None);
+    assert_eq!(block_hashes.get(10), Some("hash10"));
+    let last_hash = format!("hash{}", count - 1);
+    assert_eq!(block_hashes.last(), Some((count - 1, last_hash.as_str())));
+}
@@ -0,0 +1,80 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use dusk_pki::SecretSpendKey;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::Duration;
+use zk_citadel_moat::{
+    ChallengeIssuer, Error, MAX_PENDING_CHALLENGES_PER_PEER,
+};
+
+const PEER: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+#[test]
+fn challenge_single_use_and_expiring() -> Result<(), Error> {
+    let rng = &mut StdRng::seed_from_u64(0xbeef);
+    let psk_sp = SecretSpendKey::random(rng).public_spend_key();
+    let mut issuer = ChallengeIssuer::new(psk_sp, Duration::from_secs(60));
+
+    let challenge = issuer.issue(rng, PEER, 1000).expect("challenge issued");
+    assert!(challenge.is_bound_to(&psk_sp));
+    assert!(issuer.redeem(&challenge.challenge()?, 1059));
+    assert!(!issuer.redeem(&challenge.challenge()?, 1059));
+
+    let challenge = issuer.issue(rng, PEER, 1000).expect("challenge issued");
+    assert!(!issuer.redeem(&challenge.challenge()?, 1060));
+    Ok(())
+}
+
+#[test]
+fn challenge_bound_to_sp() {
+    let rng = &mut StdRng::seed_from_u64(0xbeef);
+    let psk_sp = SecretSpendKey::random(rng).public_spend_key();
+    let psk_other = SecretSpendKey::random(rng).public_spend_key();
+    let mut issuer = ChallengeIssuer::new(psk_sp, Duration::from_secs(60));
+
+    let mut challenge =
+        issuer.issue(rng, PEER, 1000).expect("challenge issued");
+    assert!(!challenge.is_bound_to(&psk_other));
+
+    challenge.expires_at += 1;
+    assert!(!challenge.is_bound_to(&psk_sp));
+}
+
+#[test]
+fn challenges_limited_per_peer() -> Result<(), Error> {
+    let rng = &mut StdRng::seed_from_u64(0xbeef);
+    let psk_sp = SecretSpendKey::random(rng).public_spend_key();
+    let mut issuer = ChallengeIssuer::new(psk_sp, Duration::from_secs(60));
+
+    let challenges = (0..MAX_PENDING_CHALLENGES_PER_PEER)
+        .map(|_| issuer.issue(rng, PEER, 1000).expect("challenge issued"))
+        .collect::<Vec<_>>();
+    assert!(issuer.issue(rng, PEER, 1000).is_none());
+
+    // other peers are not affected, addresses in one IPv6 /64 prefix are
+    // counted together
+    let other = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+    assert!(issuer.issue(rng, other, 1000).is_some());
+    let prefix = Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0);
+    for i in 0..MAX_PENDING_CHALLENGES_PER_PEER {
+        let ip = Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, i as u16);
+        assert!(issuer.issue(rng, IpAddr::V6(ip), 1000).is_some());
+    }
+    assert!(issuer.issue(rng, IpAddr::V6(prefix), 1000).is_none());
+
+    // redeeming a challenge frees a slot for the peer
+    assert!(issuer.redeem(&challenges[0].challenge()?, 1010));
+    assert!(issuer.issue(rng, PEER, 1010).is_some());
+    assert!(issuer.issue(rng, PEER, 1010).is_none());
+
+    // expired challenges free their slots as well
+    assert!(issuer.issue(rng, PEER, 1060).is_some());
+    assert_eq!(issuer.pending_count(), 2);
+    Ok(())
+}
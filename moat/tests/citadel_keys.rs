@@ -0,0 +1,63 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use dusk_bytes::Serializable;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use zk_citadel_moat::license_provider::{LPConfig, ReferenceLP};
+use zk_citadel_moat::{CitadelKeys, Error};
+
+#[test]
+fn citadel_keys_save_and_load() -> Result<(), Error> {
+    let rng = &mut StdRng::seed_from_u64(0xbeef);
+    let keys = CitadelKeys::random(rng);
+
+    let path = std::env::temp_dir()
+        .join(format!("moat_citadel_keys_{}.json", std::process::id()));
+    keys.save(&path)?;
+    let loaded = CitadelKeys::load(&path)?;
+    let reference_lp = ReferenceLP::create(&path)?;
+    std::fs::remove_file(&path)?;
+
+    assert_eq!(loaded.psk, keys.psk);
+    assert_eq!(loaded.ssk.to_bytes(), keys.ssk.to_bytes());
+    assert_eq!(reference_lp.psk_lp, keys.psk);
+    assert_eq!(
+        bs58::decode(keys.psk_bs58())
+            .into_vec()
+            .expect("valid bs58"),
+        hex::decode(keys.psk_hex())?
+    );
+    Ok(())
+}
+
+#[test]
+fn citadel_keys_from_config() -> Result<(), Error> {
+    let rng = &mut StdRng::seed_from_u64(0xbeef);
+    let keys = CitadelKeys::random(rng);
+    let other_keys = CitadelKeys::random(rng);
+
+    let config = LPConfig {
+        psk_lp: String::new(),
+        ..keys.to_config()
+    };
+    assert_eq!(CitadelKeys::from_config(&config)?.psk, keys.psk);
+
+    let config = LPConfig {
+        psk_lp: other_keys.psk_hex(),
+        ..keys.to_config()
+    };
+    assert!(CitadelKeys::from_config(&config).is_err());
+
+    let lp_config_path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/test_keys/test_keys_lp.json"
+    );
+    let keys = CitadelKeys::load(lp_config_path)?;
+    let reference_lp = ReferenceLP::create(lp_config_path)?;
+    assert_eq!(keys.psk, reference_lp.psk_lp);
+    Ok(())
+}
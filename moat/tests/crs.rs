@@ -0,0 +1,55 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use dusk_wallet::RuskHttpClient;
+use zk_citadel_moat::wallet_accessor::BlockchainAccessConfig;
+use zk_citadel_moat::{CrsGetter, Error};
+
+#[test]
+fn verify_crs_against_pinned_hash() -> Result<(), Error> {
+    let crs = b"crs bytes".to_vec();
+    let crs_hash = CrsGetter::hash_of_bytes(&crs);
+
+    CrsGetter::verify_crs(&crs, &crs_hash)?;
+    assert!(CrsGetter::verify_crs(b"other crs bytes", &crs_hash).is_err());
+
+    let config = BlockchainAccessConfig {
+        crs_hash: Some(hex::encode(crs_hash)),
+        ..Default::default()
+    };
+    assert_eq!(config.pinned_crs_hash()?, Some(crs_hash));
+
+    let config = BlockchainAccessConfig {
+        crs_hash: Some("not a hash".to_string()),
+        ..Default::default()
+    };
+    assert!(config.pinned_crs_hash().is_err());
+    assert_eq!(BlockchainAccessConfig::default().pinned_crs_hash()?, None);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_crs_from_local_file() -> Result<(), Error> {
+    let crs = b"crs bytes".to_vec();
+    let crs_path = std::env::temp_dir()
+        .join(format!("moat_crs_{}.bin", std::process::id()));
+    std::fs::write(&crs_path, &crs)?;
+
+    // the node is not contacted when the local CRS file is configured
+    let client = RuskHttpClient::new("http://127.0.0.1:1".to_string());
+    let mut config = BlockchainAccessConfig {
+        crs_hash: Some(hex::encode(CrsGetter::hash_of_bytes(&crs))),
+        crs_path: Some(crs_path.clone()),
+        ..Default::default()
+    };
+    assert_eq!(CrsGetter::get_configured_crs(&client, &config).await?, crs);
+
+    config.crs_hash = Some(hex::encode([0u8; 32]));
+    let result = CrsGetter::get_configured_crs(&client, &config).await;
+    std::fs::remove_file(&crs_path)?;
+    assert!(result.is_err());
+    Ok(())
+}
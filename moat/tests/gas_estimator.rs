@@ -0,0 +1,88 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use zk_citadel_moat::{
+    CallInfoJson, GasEstimator, SpentTx, Tx, ISSUE_LICENSE_METHOD_NAME,
+    LICENSE_CONTRACT_ID, REQUEST_LICENSE_METHOD_NAME,
+};
+
+fn spent_tx(
+    contract_id: String,
+    fn_name: &str,
+    gas_spent: u64,
+    err: Option<String>,
+) -> SpentTx {
+    SpentTx {
+        err,
+        gas_spent,
+        tx: Tx {
+            call_data: Some(CallInfoJson {
+                contract_id,
+                fn_name: fn_name.to_string(),
+                data: String::new(),
+            }),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn estimate_from_successful_license_contract_calls() {
+    let license_contract = hex::encode(LICENSE_CONTRACT_ID);
+    let spent_txs = vec![
+        spent_tx(
+            license_contract.clone(),
+            ISSUE_LICENSE_METHOD_NAME,
+            1000,
+            None,
+        ),
+        spent_tx(
+            license_contract.clone(),
+            ISSUE_LICENSE_METHOD_NAME,
+            2000,
+            None,
+        ),
+        spent_tx(
+            license_contract.clone(),
+            ISSUE_LICENSE_METHOD_NAME,
+            9000,
+            Some("failed".to_string()),
+        ),
+        spent_tx(license_contract, REQUEST_LICENSE_METHOD_NAME, 5000, None),
+        spent_tx(
+            hex::encode([1u8; 32]),
+            ISSUE_LICENSE_METHOD_NAME,
+            7000,
+            None,
+        ),
+        SpentTx::default(),
+    ];
+
+    let estimate =
+        GasEstimator::estimate_from_txs(&spent_txs, ISSUE_LICENSE_METHOD_NAME)
+            .expect("estimate should be available");
+
+    assert_eq!(estimate.samples, 2);
+    assert_eq!(estimate.max_gas_spent, 2000);
+    assert_eq!(estimate.avg_gas_spent, 1500);
+    assert_eq!(estimate.gas_limit, 2400);
+}
+
+#[test]
+fn no_estimate_without_calls() {
+    let spent_txs = vec![spent_tx(
+        hex::encode(LICENSE_CONTRACT_ID),
+        REQUEST_LICENSE_METHOD_NAME,
+        5000,
+        None,
+    )];
+    assert!(GasEstimator::estimate_from_txs(
+        &spent_txs,
+        ISSUE_LICENSE_METHOD_NAME
+    )
+    .is_none());
+}
@@ -0,0 +1,40 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use dusk_jubjub::JubJubScalar;
+use zk_citadel_moat::{
+    AgeBracket, AttributeEncoder, DefaultAttributeEncoder, LicenseClaims,
+};
+
+#[test]
+fn license_claims_roundtrip() {
+    let encoder = DefaultAttributeEncoder;
+    let claims = LicenseClaims {
+        age_bracket: AgeBracket::From21To64,
+        jurisdiction: "CH".to_string(),
+        kyc_tier: 2,
+        expiry: Some(1735689600),
+    };
+
+    let attr_data = encoder.encode(&claims).expect("claims encoded");
+
+    assert_eq!(encoder.decode(&attr_data).expect("claims decoded"), claims);
+    assert!(!claims.is_expired(1735689599));
+    assert!(claims.is_expired(1735689600));
+}
+
+#[test]
+fn license_claims_invalid() {
+    let encoder = DefaultAttributeEncoder;
+    let claims = LicenseClaims {
+        jurisdiction: "Switzerland".to_string(),
+        ..LicenseClaims::default()
+    };
+    assert!(encoder.encode(&claims).is_err());
+
+    // plain number attribute data is not a valid encoding
+    assert!(encoder.decode(&JubJubScalar::from(1234u64)).is_err());
+}
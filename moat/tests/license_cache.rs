@@ -0,0 +1,38 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use zk_citadel_moat::{Error, LicenseCache};
+
+#[test]
+fn license_cache_missing_file() -> Result<(), Error> {
+    let cache_path = std::env::temp_dir()
+        .join(format!("moat_licenses_missing_{}.json", std::process::id()));
+
+    let cache = LicenseCache::load(&cache_path)?;
+
+    assert_eq!(cache.synced_range(), 0..0);
+    assert_eq!(cache.licenses().count(), 0);
+    Ok(())
+}
+
+#[test]
+fn license_cache_corrupted_file() -> Result<(), Error> {
+    let cache_path = std::env::temp_dir().join(format!(
+        "moat_licenses_corrupted_{}.json",
+        std::process::id()
+    ));
+    std::fs::write(
+        &cache_path,
+        r#"{"next_height":100,"licenses":{"0":"not a license"}}"#,
+    )?;
+
+    let cache = LicenseCache::load(&cache_path)?;
+    std::fs::remove_file(&cache_path)?;
+
+    assert_eq!(cache.synced_range(), 0..0);
+    assert!(cache.get(0).is_none());
+    Ok(())
+}
@@ -0,0 +1,75 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use dusk_wallet::{RuskHttpClient, WalletPath};
+use std::path::Path;
+use std::time::Duration;
+use zk_citadel_moat::api::{MoatContext, DEFAULT_GAS_LIMIT, DEFAULT_GAS_PRICE};
+use zk_citadel_moat::wallet_accessor::{BlockchainAccessConfig, Password};
+use zk_citadel_moat::AwaitConfig;
+
+fn blockchain_access_config() -> BlockchainAccessConfig {
+    BlockchainAccessConfig {
+        rusk_address: "http://127.0.0.1:8080".to_string(),
+        prover_address: "http://127.0.0.1:8080".to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn moat_context_builder_defaults() {
+    let moat_context = MoatContext::builder(
+        blockchain_access_config(),
+        WalletPath::from(Path::new("wallet.dat")),
+        Password::PwdHash("0123".to_string()),
+    )
+    .build();
+
+    assert_eq!(
+        moat_context.blockchain_access_config().rusk_address,
+        "http://127.0.0.1:8080"
+    );
+    assert_eq!(moat_context.gas_limit(), DEFAULT_GAS_LIMIT);
+    assert_eq!(moat_context.gas_price(), DEFAULT_GAS_PRICE);
+    assert!(!moat_context.estimate_gas());
+    assert!(matches!(
+        moat_context.wallet_password(),
+        Password::PwdHash(hash) if hash == "0123"
+    ));
+}
+
+#[test]
+fn moat_context_builder_settings() {
+    let await_config = AwaitConfig {
+        timeout: Duration::from_secs(5),
+        confirmations: 2,
+        ..Default::default()
+    };
+    let moat_context = MoatContext::builder(
+        blockchain_access_config(),
+        WalletPath::from(Path::new("wallet.dat")),
+        Password::Pwd("password".to_string()),
+    )
+    .client(RuskHttpClient::new("http://127.0.0.1:9000".to_string()))
+    .gas_limit(1000)
+    .gas_price(2)
+    .await_config(await_config)
+    .gas_estimation(true)
+    .lp_checkpoint_path("lp_checkpoint.json")
+    .build();
+
+    assert_eq!(moat_context.gas_limit(), 1000);
+    assert_eq!(moat_context.gas_price(), 2);
+    assert!(moat_context.estimate_gas());
+    assert_eq!(moat_context.await_config().timeout, Duration::from_secs(5));
+    assert_eq!(moat_context.await_config().confirmations, 2);
+    assert_eq!(
+        moat_context
+            .lp_checkpoint_store()
+            .map(|store| store.path().to_path_buf()),
+        Some(Path::new("lp_checkpoint.json").to_path_buf())
+    );
+}
@@ -0,0 +1,69 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use dusk_bls12_381::BlsScalar;
+use dusk_jubjub::JubJubScalar;
+use dusk_pki::SecretSpendKey;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use zk_citadel_moat::license_provider::LicenseIssuer;
+use zk_citadel_moat::{
+    Error, LicenseReceipt, MoatCoreUtils, RequestCreator, RequestReceipt,
+};
+
+#[test]
+fn receipts_round_trip() -> Result<(), Error> {
+    let rng = &mut StdRng::seed_from_u64(0xcafe);
+    let ssk_user = SecretSpendKey::random(rng);
+    let ssk_lp = SecretSpendKey::random(rng);
+    let request =
+        RequestCreator::create(&ssk_user, &ssk_lp.public_spend_key(), rng)?;
+    let tx_id = BlsScalar::from(42u64);
+
+    let request_blob = MoatCoreUtils::to_blob(&request);
+    let request_receipt =
+        RequestReceipt::new(&tx_id, 10, 1000, request_blob.as_slice());
+    assert_eq!(
+        request_receipt.request_hash,
+        MoatCoreUtils::to_hash_hex(&request)
+    );
+    assert_eq!(
+        MoatCoreUtils::to_hash_hex(&request_receipt.request()?),
+        request_receipt.request_hash
+    );
+
+    let (license_blob, _license_hash) = LicenseIssuer::license_payload(
+        rng,
+        &request,
+        &ssk_lp,
+        &JubJubScalar::from(1234u64),
+    );
+    let license_receipt = LicenseReceipt::new(
+        &tx_id,
+        11,
+        2000,
+        &request,
+        license_blob.as_slice(),
+    );
+    assert_eq!(license_receipt.request_hash, request_receipt.request_hash);
+    assert_eq!(
+        MoatCoreUtils::to_hash_hex(&license_receipt.license()?),
+        license_receipt.license_hash
+    );
+
+    let json = serde_json::to_string(&license_receipt)?;
+    let deserialized: LicenseReceipt = serde_json::from_str(&json)?;
+    assert_eq!(deserialized, license_receipt);
+    Ok(())
+}
+
+#[test]
+fn receipt_with_invalid_blob() {
+    let mut receipt = RequestReceipt::new(&BlsScalar::one(), 1, 1, &[1, 2, 3]);
+    assert!(receipt.request().is_err());
+    receipt.request_blob = "not hex".to_string();
+    assert!(receipt.request().is_err());
+}
@@ -0,0 +1,35 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use dusk_pki::SecretSpendKey;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use zk_citadel_moat::{Error, MoatCoreUtils, RejectionNotice, RequestCreator};
+
+#[test]
+fn rejection_notice_readable_by_user_only() -> Result<(), Error> {
+    let rng = &mut StdRng::seed_from_u64(0xbeef);
+    let ssk_user = SecretSpendKey::random(rng);
+    let ssk_lp = SecretSpendKey::random(rng);
+    let ssk_other = SecretSpendKey::random(rng);
+
+    let request =
+        RequestCreator::create(&ssk_user, &ssk_lp.public_spend_key(), rng)?;
+    const REASON: &str = "attributes could not be verified";
+    let notice = RejectionNotice::create(rng, &request, &ssk_lp, REASON);
+
+    assert_eq!(notice.request_hash, MoatCoreUtils::to_hash_hex(&request));
+    assert!(!notice.enc_reason.contains(&hex::encode(REASON)));
+    assert!(notice.is_addressed_to(&ssk_user.view_key())?);
+    assert!(!notice.is_addressed_to(&ssk_other.view_key())?);
+    assert_eq!(notice.decrypt_reason(&ssk_user)?, Some(REASON.to_string()));
+    assert_eq!(notice.decrypt_reason(&ssk_other)?, None);
+
+    let json = serde_json::to_string(&notice)?;
+    let deserialized: RejectionNotice = serde_json::from_str(&json)?;
+    assert_eq!(deserialized, notice);
+    Ok(())
+}
@@ -0,0 +1,112 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use dusk_bls12_381::BlsScalar;
+use dusk_bytes::Serializable;
+use dusk_pki::SecretSpendKey;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use zk_citadel_moat::{
+    Error, MoatCoreUtils, RequestCreator, RequestHistory, RequestReceipt,
+};
+
+#[test]
+fn request_history_persisted_and_filtered_by_owner() -> Result<(), Error> {
+    let rng = &mut StdRng::seed_from_u64(0xbeef);
+    let ssk_user = SecretSpendKey::random(rng);
+    let ssk_other = SecretSpendKey::random(rng);
+    let psk_lp = SecretSpendKey::random(rng).public_spend_key();
+
+    let path = std::env::temp_dir()
+        .join(format!("moat_request_history_{}.json", std::process::id()));
+    let mut history = RequestHistory::load(&path)?;
+    assert_eq!(history.receipts().count(), 0);
+
+    let mut request_hashes = vec![];
+    for ssk in [&ssk_user, &ssk_other] {
+        let (request, lsa) =
+            RequestCreator::create_with_lsa(ssk, &psk_lp, rng)?;
+        let receipt = RequestReceipt::new(
+            &BlsScalar::one(),
+            1,
+            1,
+            MoatCoreUtils::to_blob(&request).as_slice(),
+        )
+        .with_lsa(&lsa);
+        assert_eq!(
+            receipt.lsa()?.map(|lsa| lsa.to_bytes()),
+            Some(lsa.to_bytes())
+        );
+        request_hashes.push(receipt.request_hash.clone());
+        history.insert(receipt)?;
+    }
+
+    let restored = RequestHistory::load(&path)?;
+    std::fs::remove_file(&path)?;
+
+    assert_eq!(restored.receipts().count(), 2);
+    assert!(restored.get(&request_hashes[0]).is_some());
+    let owned = restored.owned_receipts(&ssk_user.view_key());
+    assert_eq!(owned.len(), 1);
+    assert_eq!(owned[0].request_hash, request_hashes[0]);
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn request_history_readable_by_owner_only() -> Result<(), Error> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let rng = &mut StdRng::seed_from_u64(0xbeef);
+    let ssk_user = SecretSpendKey::random(rng);
+    let psk_lp = SecretSpendKey::random(rng).public_spend_key();
+
+    let path = std::env::temp_dir().join(format!(
+        "moat_request_history_mode_{}.json",
+        std::process::id()
+    ));
+    // an existing file with wider permissions is replaced
+    std::fs::write(&path, br#"{"receipts":{}}"#)?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644))?;
+
+    let mut history = RequestHistory::load(&path)?;
+    let (request, _) =
+        RequestCreator::create_with_lsa(&ssk_user, &psk_lp, rng)?;
+    history.insert(RequestReceipt::new(
+        &BlsScalar::one(),
+        1,
+        1,
+        MoatCoreUtils::to_blob(&request).as_slice(),
+    ))?;
+
+    let mode = std::fs::metadata(&path)?.permissions().mode();
+    std::fs::remove_file(&path)?;
+    assert_eq!(mode & 0o777, 0o600);
+    Ok(())
+}
+
+#[test]
+fn corrupt_request_history_moved_aside() -> Result<(), Error> {
+    let path = std::env::temp_dir().join(format!(
+        "moat_request_history_corrupt_{}.json",
+        std::process::id()
+    ));
+    let corrupt_path = path.with_file_name(format!(
+        "moat_request_history_corrupt_{}.json.corrupt",
+        std::process::id()
+    ));
+    std::fs::write(&path, b"not a request history")?;
+
+    let history = RequestHistory::load(&path)?;
+    let corrupt = std::fs::read(&corrupt_path)?;
+    let exists = path.exists();
+    std::fs::remove_file(&corrupt_path)?;
+
+    assert_eq!(history.receipts().count(), 0);
+    assert!(!exists);
+    assert_eq!(corrupt, b"not a request history");
+    Ok(())
+}
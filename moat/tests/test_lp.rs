@@ -4,8 +4,15 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
-use zk_citadel_moat::license_provider::ReferenceLP;
-use zk_citadel_moat::{Error, JsonLoader, RequestScanner, Transactions};
+use dusk_pki::SecretSpendKey;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use zk_citadel_moat::license_provider::{
+    LPCheckpoint, LPCheckpointStore, ReferenceLP,
+};
+use zk_citadel_moat::{
+    Error, JsonLoader, MoatCoreUtils, RequestScanner, Transactions,
+};
 
 #[test]
 fn lp_filter_requests() -> Result<(), Error> {
@@ -29,3 +36,119 @@ fn lp_filter_requests() -> Result<(), Error> {
 
     Ok(())
 }
+
+#[test]
+fn lp_checkpoint_restore() -> Result<(), Error> {
+    let lp_config_path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/test_keys/test_keys_lp.json"
+    );
+    let reference_lp = ReferenceLP::create(&lp_config_path)?;
+
+    let txs_path =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/tx/transactions_lp.json");
+
+    let txs = Transactions::from_file(txs_path)
+        .expect("transactions file should load correctly");
+
+    let requests = RequestScanner::scan_transactions(txs);
+    let owned_requests = reference_lp.retain_owned_requests(requests);
+
+    const NEXT_HEIGHT: u64 = 1234;
+    let checkpoint = LPCheckpoint {
+        next_height: NEXT_HEIGHT,
+        requests_hashes: vec![],
+        requests: owned_requests
+            .iter()
+            .map(MoatCoreUtils::to_blob_hex)
+            .collect(),
+        ..LPCheckpoint::default()
+    };
+
+    let store_path = std::env::temp_dir()
+        .join(format!("moat_lp_checkpoint_{}.json", std::process::id()));
+    let store = LPCheckpointStore::new(&store_path);
+    assert!(store.load()?.is_none());
+    store.save(&checkpoint)?;
+
+    let restored_lp =
+        ReferenceLP::create_with_checkpoint(&reference_lp.ssk_lp, &store)?;
+    std::fs::remove_file(&store_path)?;
+
+    assert_eq!(restored_lp.next_height, NEXT_HEIGHT);
+    assert_eq!(restored_lp.requests_to_process.len(), 2);
+    assert_eq!(restored_lp.requests_hashes.len(), 2);
+    assert_eq!(restored_lp.checkpoint().requests, checkpoint.requests);
+
+    Ok(())
+}
+
+#[test]
+fn lp_reject_request() -> Result<(), Error> {
+    let lp_config_path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/test_keys/test_keys_lp.json"
+    );
+    let reference_lp = ReferenceLP::create(&lp_config_path)?;
+
+    let txs_path =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/tx/transactions_lp.json");
+
+    let txs = Transactions::from_file(txs_path)
+        .expect("transactions file should load correctly");
+
+    let requests = RequestScanner::scan_transactions(txs);
+    let owned_requests = reference_lp.retain_owned_requests(requests);
+
+    let checkpoint = LPCheckpoint {
+        requests: owned_requests
+            .iter()
+            .map(MoatCoreUtils::to_blob_hex)
+            .collect(),
+        ..LPCheckpoint::default()
+    };
+    let mut reference_lp = ReferenceLP::create_with_ssk(&reference_lp.ssk_lp)?;
+    reference_lp.restore(&checkpoint)?;
+
+    let request_hash = MoatCoreUtils::to_hash_hex(&owned_requests[0]);
+    assert!(reference_lp
+        .reject_request(&request_hash, "missing attributes")
+        .is_some());
+    assert!(reference_lp
+        .reject_request(&request_hash, "missing attributes")
+        .is_none());
+    assert_eq!(reference_lp.requests_to_process.len(), 1);
+    assert!(reference_lp.is_rejected(&request_hash));
+    assert_eq!(
+        reference_lp.rejection_reason(&request_hash),
+        Some("missing attributes")
+    );
+
+    let mut restored_lp = ReferenceLP::create_with_ssk(&reference_lp.ssk_lp)?;
+    restored_lp.restore(&reference_lp.checkpoint())?;
+    assert_eq!(
+        restored_lp.rejection_reason(&request_hash),
+        Some("missing attributes")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn lp_checkpoint_of_other_lp_not_restored() -> Result<(), Error> {
+    let lp_config_path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/test_keys/test_keys_lp.json"
+    );
+    let reference_lp = ReferenceLP::create(&lp_config_path)?;
+    let checkpoint = reference_lp.checkpoint();
+
+    let mut restored_lp = ReferenceLP::create(&lp_config_path)?;
+    assert!(restored_lp.restore(&checkpoint).is_ok());
+
+    let ssk_other = SecretSpendKey::random(&mut StdRng::seed_from_u64(0xdead));
+    let mut other_lp = ReferenceLP::create_with_ssk(&ssk_other)?;
+    assert!(other_lp.restore(&checkpoint).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,24 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use zk_citadel_moat::{Error, UsedSessions};
+
+#[test]
+fn used_sessions_not_replayed_after_reload() -> Result<(), Error> {
+    let sessions_path = std::env::temp_dir()
+        .join(format!("moat_sp_sessions_{}.json", std::process::id()));
+
+    let mut used_sessions = UsedSessions::load(&sessions_path)?;
+    assert!(used_sessions.insert("aa01")?);
+    assert!(!used_sessions.insert("aa01")?);
+
+    let used_sessions = UsedSessions::load(&sessions_path)?;
+    std::fs::remove_file(&sessions_path)?;
+
+    assert!(used_sessions.contains("aa01"));
+    assert!(!used_sessions.contains("aa02"));
+    Ok(())
+}